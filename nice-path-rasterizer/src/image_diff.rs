@@ -0,0 +1,91 @@
+use crate::RgbaImage;
+
+/// The largest YIQ difference between two colours.
+const MAX_YIQ_DELTA: f32 = 35215.0;
+
+/// How different two images can be before they count as different.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffTolerance {
+	/// How perceptually different two pixels can be before they mismatch, from 0 for the same colour to 1 for the most
+	/// different ones. Black and white are 0.97 apart.
+	pub threshold: f32,
+	/// The fraction of pixels that can mismatch.
+	pub max_mismatched: f32,
+}
+impl Default for DiffTolerance {
+	/// Lets through the differences in anti-aliasing between GPUs, which are barely visible, but nothing else.
+	fn default() -> Self {
+		Self { threshold: 0.1, max_mismatched: 0.0 }
+	}
+}
+
+/// The result of comparing two images with [`RgbaImage::diff`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageDiff {
+	/// How many pixels are more different than the threshold.
+	pub mismatched: u32,
+	/// The largest difference between two pixels, on the same scale as [`DiffTolerance::threshold`].
+	pub max_difference: f32,
+	/// The expected image faded out, with mismatched pixels in red and pixels that differ within the threshold in
+	/// yellow.
+	pub image: RgbaImage,
+}
+impl ImageDiff {
+	pub fn is_within(&self, tolerance: &DiffTolerance) -> bool {
+		let pixels = (self.image.width * self.image.height) as f32;
+		self.mismatched as f32 <= tolerance.max_mismatched * pixels
+	}
+}
+
+impl RgbaImage {
+	/// Compares this image to `expected` pixel by pixel. Pixels are put over white, since they're premultiplied, and
+	/// compared by their distance in YIQ, which weighs brightness more than hue, like eyes do. Panics if the images
+	/// aren't the same size.
+	pub fn diff(&self, expected: &RgbaImage, tolerance: &DiffTolerance) -> ImageDiff {
+		assert_eq!(
+			(self.width, self.height),
+			(expected.width, expected.height),
+			"can't compare images of different sizes"
+		);
+		let mut mismatched = 0;
+		let mut max_difference = 0.0f32;
+		let mut data = Vec::with_capacity(expected.data.len());
+		for (actual, expected) in self.data.chunks(4).zip(expected.data.chunks(4)) {
+			let (actual, expected) = (over_white(actual), over_white(expected));
+			let difference = (yiq_delta(actual, expected) / MAX_YIQ_DELTA).sqrt();
+			max_difference = max_difference.max(difference);
+			let pixel = match difference {
+				d if d > tolerance.threshold => {
+					mismatched += 1;
+					[255, 0, 0, 255]
+				},
+				d if d > 0.0 => [255, 255, 0, 255],
+				_ => {
+					let faded = (255.0 - (255.0 - luma(expected)) * 0.1) as u8;
+					[faded, faded, faded, 255]
+				},
+			};
+			data.extend(pixel);
+		}
+		ImageDiff { mismatched, max_difference, image: RgbaImage { width: self.width, height: self.height, data } }
+	}
+}
+
+fn over_white(pixel: &[u8]) -> [f32; 3] {
+	let background = 255.0 - pixel[3] as f32;
+	[pixel[0], pixel[1], pixel[2]].map(|c| c as f32 + background)
+}
+
+fn luma(rgb: [f32; 3]) -> f32 {
+	rgb[0] * 0.2989 + rgb[1] * 0.5866 + rgb[2] * 0.1145
+}
+
+/// The squared distance in YIQ, weighted by how visible each axis is, from Kotsarenko and Ramos, "Measuring perceived
+/// color difference using YIQ NTSC transmission color space in mobile applications".
+fn yiq_delta(a: [f32; 3], b: [f32; 3]) -> f32 {
+	let [dr, dg, db] = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+	let y = dr * 0.2989 + dg * 0.5866 + db * 0.1145;
+	let i = dr * 0.5960 - dg * 0.2742 - db * 0.3218;
+	let q = dr * 0.2115 - dg * 0.5226 + db * 0.3111;
+	0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
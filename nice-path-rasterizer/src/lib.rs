@@ -1,12 +1,32 @@
+mod image_diff;
+mod offscreen_target;
+mod paint_buf;
 mod path_bufs;
+mod pick_target;
 mod pipelines;
 mod rasterize_target;
+mod reference;
+mod scene_bufs;
+#[cfg(feature = "compute")]
+mod tile_scene_bufs;
 
+pub use image_diff::*;
 pub use nice_path_tessellator::*;
+pub use offscreen_target::*;
+pub use paint_buf::*;
 pub use path_bufs::*;
+pub use pick_target::*;
 pub use pipelines::*;
 pub use rasterize_target::*;
+pub use reference::*;
+pub use scene_bufs::*;
+#[cfg(feature = "compute")]
+pub use tile_scene_bufs::*;
 
 use wgpu::TextureFormat;
 
 const JITTER_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+const CLIP_FORMAT: TextureFormat = TextureFormat::R8Unorm;
+const PICK_FORMAT: TextureFormat = TextureFormat::R32Uint;
+const STENCIL_FORMAT: TextureFormat = TextureFormat::Depth24PlusStencil8;
+const STENCIL_SAMPLE_COUNT: u32 = 4;
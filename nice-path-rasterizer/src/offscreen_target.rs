@@ -0,0 +1,250 @@
+use std::{
+	fs::File,
+	future::Future,
+	io::{self, BufReader, BufWriter, Read, Write},
+	num::NonZeroU32,
+	path::Path,
+};
+
+use wgpu::{
+	util::backend_bits_from_env, Backends, BufferAsyncError, BufferDescriptor, BufferUsages, Color,
+	CommandEncoderDescriptor, Device, DeviceDescriptor, DownlevelFlags, Extent3d, Features, ImageCopyBuffer,
+	ImageCopyTexture, ImageDataLayout, Instance, Limits, MapMode, Origin3d, Queue, RequestAdapterOptions, Texture,
+	TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
+	TextureViewDescriptor, COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+use crate::{IntermediateBufs, PathPipelines, SceneBufs};
+
+/// Opens a device without a window, for [`OffscreenTarget`]s. The backends can be chosen with the `WGPU_BACKEND`
+/// environment variable. Returns `None` if there's no adapter, e.g. on a machine without any GPU drivers.
+///
+/// The device is limited like WebGL 2, so what renders headlessly renders in a browser too, except with the `compute`
+/// feature on an adapter that has compute shaders, which `TilePipeline` needs.
+pub async fn request_headless_device() -> Option<(Device, Queue)> {
+	let instance = Instance::new(backend_bits_from_env().unwrap_or_else(Backends::all));
+	let adapter = instance.request_adapter(&RequestAdapterOptions::default()).await?;
+	let compute = adapter.get_downlevel_properties().flags.contains(DownlevelFlags::COMPUTE_SHADERS);
+	let limits = match cfg!(feature = "compute") && compute {
+		true => Limits::downlevel_defaults(),
+		false => Limits::downlevel_webgl2_defaults(),
+	}
+	.using_resolution(adapter.limits());
+	let descriptor = DeviceDescriptor { label: None, features: Features::empty(), limits };
+	adapter.request_device(&descriptor, None).await.ok()
+}
+
+/// A texture to render into without a window, which can be read back to the CPU. It's in `Rgba8Unorm`,
+/// `Rgba8UnormSrgb`, `Bgra8Unorm` or `Bgra8UnormSrgb`, so it reads back to 8 bits per component.
+pub struct OffscreenTarget {
+	texture: Texture,
+	view: TextureView,
+	intermediate_bufs: IntermediateBufs,
+	format: TextureFormat,
+	size: [u32; 2],
+}
+impl OffscreenTarget {
+	/// Panics if `format` isn't one of the 8 bit RGBA or BGRA formats.
+	pub fn new(device: &Device, width: u32, height: u32, format: TextureFormat) -> Self {
+		assert!(
+			matches!(
+				format,
+				TextureFormat::Rgba8Unorm
+					| TextureFormat::Rgba8UnormSrgb
+					| TextureFormat::Bgra8Unorm
+					| TextureFormat::Bgra8UnormSrgb
+			),
+			"offscreen targets can't be {:?}",
+			format
+		);
+		let texture = device.create_texture(&TextureDescriptor {
+			label: Some("Offscreen Texture"),
+			size: Extent3d { width, height, depth_or_array_layers: 1 },
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: TextureDimension::D2,
+			format,
+			usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+		});
+		let view = texture.create_view(&TextureViewDescriptor::default());
+		let intermediate_bufs = IntermediateBufs::new(device, width, height);
+
+		OffscreenTarget { texture, view, intermediate_bufs, format, size: [width, height] }
+	}
+
+	pub fn view(&self) -> &TextureView {
+		&self.view
+	}
+
+	/// For [`crate::RasterizePipeline::draw_blended`], which can copy from it.
+	pub fn texture(&self) -> &Texture {
+		&self.texture
+	}
+
+	pub fn intermediate_bufs(&self) -> &IntermediateBufs {
+		&self.intermediate_bufs
+	}
+
+	/// For pushing and popping clips.
+	pub fn intermediate_bufs_mut(&mut self) -> &mut IntermediateBufs {
+		&mut self.intermediate_bufs
+	}
+
+	pub fn format(&self) -> TextureFormat {
+		self.format
+	}
+
+	pub fn size(&self) -> [u32; 2] {
+		self.size
+	}
+
+	/// Draws `scene` over `background`, with `pipelines` made for this target's format and size.
+	pub fn render(
+		&self,
+		device: &Device,
+		queue: &Queue,
+		pipelines: &PathPipelines,
+		scene: &SceneBufs,
+		background: Color,
+	) {
+		queue.submit(pipelines.draw_scene(device, &self.intermediate_bufs, &self.texture, scene, background));
+	}
+
+	/// Draws `scene` over `background` in a compute pass, with `pipeline` made for this target's format and size.
+	#[cfg(feature = "compute")]
+	pub fn render_tiles(
+		&self,
+		device: &Device,
+		queue: &Queue,
+		pipeline: &crate::TilePipeline,
+		scene: &crate::TileSceneBufs,
+		background: Color,
+	) {
+		queue.submit(Some(pipeline.draw_scene(device, &self.view, scene, background)));
+	}
+
+	/// Copies the texture back to the CPU. The copy is submitted right away, so it sees everything submitted before
+	/// it, but on native the future only resolves once the device is polled, e.g. with `device.poll(Maintain::Wait)`.
+	pub fn read_rgba(
+		&self,
+		device: &Device,
+		queue: &Queue,
+	) -> impl Future<Output = Result<RgbaImage, BufferAsyncError>> {
+		let [width, height] = self.size;
+		// rows in the copy have to start at multiples of the alignment
+		let row_bytes = width * 4;
+		let padded_row_bytes = row_bytes.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+
+		let readback = device.create_buffer(&BufferDescriptor {
+			label: Some("Offscreen Readback Buffer"),
+			size: (padded_row_bytes * height) as u64,
+			usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+
+		let mut readback_cmds = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+		readback_cmds.copy_texture_to_buffer(
+			ImageCopyTexture {
+				texture: &self.texture,
+				mip_level: 0,
+				origin: Origin3d::ZERO,
+				aspect: TextureAspect::All,
+			},
+			ImageCopyBuffer {
+				buffer: &readback,
+				layout: ImageDataLayout {
+					offset: 0,
+					bytes_per_row: NonZeroU32::new(padded_row_bytes),
+					rows_per_image: None,
+				},
+			},
+			Extent3d { width, height, depth_or_array_layers: 1 },
+		);
+		queue.submit(Some(readback_cmds.finish()));
+
+		let bgra = matches!(self.format, TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb);
+		let mapped = readback.slice(..).map_async(MapMode::Read);
+		async move {
+			mapped.await?;
+			let mut data = Vec::with_capacity((row_bytes * height) as usize);
+			{
+				let padded = readback.slice(..).get_mapped_range();
+				for row in padded.chunks(padded_row_bytes as usize) {
+					data.extend_from_slice(&row[..row_bytes as usize]);
+				}
+			}
+			readback.unmap();
+			if bgra {
+				data.chunks_mut(4).for_each(|pixel| pixel.swap(0, 2));
+			}
+			Ok(RgbaImage { width, height, data })
+		}
+	}
+}
+
+/// 8 bit RGBA pixels, row by row from the top, without padding. The colour space is the one of the format they were
+/// read from, which is sRGB for the `Srgb` formats. Scenes are blended premultiplied, so what's read back from them is
+/// premultiplied too, which only makes a difference where the background isn't opaque.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RgbaImage {
+	pub width: u32,
+	pub height: u32,
+	pub data: Vec<u8>,
+}
+impl RgbaImage {
+	pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+		let i = ((y * self.width + x) * 4) as usize;
+		[self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3]]
+	}
+
+	pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+		let mut encoder = png::Encoder::new(writer, self.width, self.height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		let mut writer = encoder.write_header()?;
+		writer.write_image_data(&self.data)?;
+		Ok(writer.finish()?)
+	}
+
+	pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		self.write_png(BufWriter::new(File::create(path)?))
+	}
+
+	/// Reads the first frame of a PNG, converting it to 8 bit RGBA. Palettes and grayscale are expanded, 16 bit
+	/// components are truncated, and images without alpha are opaque.
+	pub fn read_png(reader: impl Read) -> Result<Self, png::DecodingError> {
+		let mut decoder = png::Decoder::new(reader);
+		decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+		let mut reader = decoder.read_info()?;
+		let mut buf = vec![0; reader.output_buffer_size()];
+		let info = reader.next_frame(&mut buf)?;
+		buf.truncate(info.buffer_size());
+		let data = match info.color_type {
+			png::ColorType::Rgba => buf,
+			png::ColorType::Rgb => buf.chunks(3).flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255]).collect(),
+			png::ColorType::GrayscaleAlpha => {
+				buf.chunks(2).flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]]).collect()
+			},
+			png::ColorType::Grayscale => buf.iter().flat_map(|&gray| [gray, gray, gray, 255]).collect(),
+			// expanded to RGB
+			png::ColorType::Indexed => unreachable!(),
+		};
+		Ok(RgbaImage { width: info.width, height: info.height, data })
+	}
+
+	pub fn load_png(path: impl AsRef<Path>) -> Result<Self, png::DecodingError> {
+		Self::read_png(BufReader::new(File::open(path)?))
+	}
+
+	/// Writes a binary PPM, which has no alpha channel, so alpha is dropped.
+	pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+		write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+		let rgb: Vec<u8> = self.data.chunks(4).flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+		writer.write_all(&rgb)?;
+		writer.flush()
+	}
+
+	pub fn save_ppm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		self.write_ppm(BufWriter::new(File::create(path)?))
+	}
+}
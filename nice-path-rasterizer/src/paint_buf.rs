@@ -0,0 +1,102 @@
+use bytemuck::{Pod, Zeroable};
+use nice_path_tessellator::{gradient_color, Color, GradientStop, Paint, SpreadMethod, Transform};
+use wgpu::{
+	util::{BufferInitDescriptor, DeviceExt},
+	Buffer, BufferUsages, Device, TextureFormat,
+};
+
+/// How many gradient stops the rasterize shader takes. Gradients with more stops are resampled to this many evenly
+/// spaced ones.
+pub const MAX_GRADIENT_STOPS: usize = 16;
+
+/// The layout of `Paint` in `common.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub(crate) struct PaintUniform {
+	/// a, b, c, d of the transform from pixels to the gradient's unit space
+	matrix: [f32; 4],
+	/// e, f of that transform, then the radial gradient's focal point in unit space
+	translation_focal: [f32; 4],
+	/// kind (0: solid, 1: linear, 2: radial), spread (0: pad, 1: reflect, 2: repeat), stop count, and whether the view
+	/// is sRGB, so colours have to be converted to linear
+	info: [u32; 4],
+	offsets: [[f32; 4]; MAX_GRADIENT_STOPS / 4],
+	/// premultiplied, with the draw's opacity
+	colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+}
+impl PaintUniform {
+	/// `to_view` maps the space of the painted path to pixels.
+	pub fn new(paint: &Paint, opacity: f32, to_view: &Transform, view_format: TextureFormat) -> Self {
+		// like Paint::color_at, degenerate gradients are their last stop, and gradients without stops are transparent
+		let solid = |stops: &[GradientStop]| {
+			let color = stops.last().map_or(Color::TRANSPARENT, |stop| stop.color);
+			(0, None, [0.0; 2], vec![GradientStop { offset: 0.0, color }])
+		};
+		let (kind, to_unit, focal, stops) = match paint {
+			Paint::Solid(color) => (0, None, [0.0; 2], vec![GradientStop { offset: 0.0, color: *color }]),
+			Paint::LinearGradient(gradient) => match gradient.unit_transform() {
+				Some(to_unit) if !gradient.stops.is_empty() => {
+					(1, Some((to_unit, gradient.spread)), [0.0; 2], gradient.stops.clone())
+				},
+				_ => solid(&gradient.stops),
+			},
+			Paint::RadialGradient(gradient) => match gradient.unit_transform() {
+				Some(to_unit) if !gradient.stops.is_empty() => {
+					(2, Some((to_unit, gradient.spread)), gradient.unit_focal(), gradient.stops.clone())
+				},
+				_ => solid(&gradient.stops),
+			},
+		};
+		let stops = match stops.len() > MAX_GRADIENT_STOPS {
+			true => (0..MAX_GRADIENT_STOPS)
+				.map(|i| {
+					let offset = i as f32 / (MAX_GRADIENT_STOPS - 1) as f32;
+					GradientStop { offset, color: gradient_color(&stops, offset) }
+				})
+				.collect(),
+			false => stops,
+		};
+
+		// a view that isn't invertible means the path has no area, so nothing is painted anyway
+		let (to_unit, spread) = to_unit.unwrap_or((Transform::IDENTITY, SpreadMethod::Pad));
+		let pixels_to_unit = to_view.invert().unwrap_or(Transform::IDENTITY).then(&to_unit);
+		let [a, b, c, d, e, f] = pixels_to_unit.0;
+		let mut uniform = Self {
+			matrix: [a, b, c, d],
+			translation_focal: [e, f, focal[0], focal[1]],
+			info: [
+				kind,
+				match spread {
+					SpreadMethod::Pad => 0,
+					SpreadMethod::Reflect => 1,
+					SpreadMethod::Repeat => 2,
+				},
+				stops.len() as u32,
+				view_format.describe().srgb as u32,
+			],
+			offsets: [[0.0; 4]; MAX_GRADIENT_STOPS / 4],
+			colors: [[0.0; 4]; MAX_GRADIENT_STOPS],
+		};
+		for (i, stop) in stops.iter().enumerate() {
+			uniform.offsets[i / 4][i % 4] = stop.offset;
+			uniform.colors[i] = stop.color.with_alpha(stop.color.a * opacity).premultiplied();
+		}
+		uniform
+	}
+}
+
+/// A [`Paint`] in a uniform buffer, for [`crate::RasterizePipeline::draw_paint`].
+pub struct PaintBuf {
+	pub uniform: Buffer,
+}
+impl PaintBuf {
+	/// `to_view` maps the space of the painted path to pixels.
+	pub fn new(device: &Device, paint: &Paint, opacity: f32, to_view: &Transform, view_format: TextureFormat) -> Self {
+		let uniform = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Paint Buffer"),
+			contents: bytemuck::bytes_of(&PaintUniform::new(paint, opacity, to_view, view_format)),
+			usage: BufferUsages::UNIFORM,
+		});
+		Self { uniform }
+	}
+}
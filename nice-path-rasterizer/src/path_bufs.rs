@@ -6,15 +6,20 @@ use wgpu::{
 
 pub struct PathBufs {
 	pub verts: Buffer,
+	/// one `[f32; 4]` per vertex, see [`nice_path_tessellator::BufsBuilder::push_vert`]
+	pub curve_coords: Buffer,
 	pub fill_idxs: Buffer,
 	pub fill_idx_len: usize,
 	pub quadratic_idxs: Buffer,
 	pub quadratic_idx_len: usize,
+	/// see [`nice_path_tessellator::VecPathVertBufs::fan_idxs`]
+	pub fan_idxs: Buffer,
+	pub fan_idx_len: usize,
 }
 impl PathBufs {
 	pub fn new(device: &Device, path: &Path) -> Self {
 		// TODO: send data directly to the gpu
-		let cpu_bufs = fill_tessellate(&path);
+		let cpu_bufs = fill_tessellate(path);
 
 		let verts = device.create_buffer_init(&BufferInitDescriptor {
 			label: Some("Vertex Buffer"),
@@ -22,6 +27,12 @@ impl PathBufs {
 			usage: BufferUsages::VERTEX,
 		});
 
+		let curve_coords = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Curve Coordinate Buffer"),
+			contents: bytemuck::cast_slice(&cpu_bufs.curve_coords),
+			usage: BufferUsages::VERTEX,
+		});
+
 		let fill_idxs = device.create_buffer_init(&BufferInitDescriptor {
 			label: Some("Index Buffer"),
 			contents: bytemuck::cast_slice(&cpu_bufs.fill_idxs),
@@ -34,12 +45,21 @@ impl PathBufs {
 			usage: BufferUsages::INDEX,
 		});
 
+		let fan_idxs = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Index Buffer"),
+			contents: bytemuck::cast_slice(&cpu_bufs.fan_idxs),
+			usage: BufferUsages::INDEX,
+		});
+
 		Self {
 			verts,
+			curve_coords,
 			fill_idxs,
 			fill_idx_len: cpu_bufs.fill_idxs.len(),
 			quadratic_idxs,
 			quadratic_idx_len: cpu_bufs.quadratic_idxs.len(),
+			fan_idxs,
+			fan_idx_len: cpu_bufs.fan_idxs.len(),
 		}
 	}
 }
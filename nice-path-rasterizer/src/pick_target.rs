@@ -0,0 +1,87 @@
+use std::{future::Future, num::NonZeroU32};
+
+use wgpu::{
+	BufferAsyncError, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Extent3d, ImageCopyBuffer,
+	ImageCopyTexture, ImageDataLayout, MapMode, Origin3d, Queue, Texture, TextureAspect, TextureDescriptor,
+	TextureDimension, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+use crate::PICK_FORMAT;
+
+/// Identifies a path drawn by [`crate::PickPipeline`]. `u32::MAX` is reserved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PathId(pub u32);
+
+/// An `R32Uint` texture holding the id of the topmost path covering each pixel.
+pub struct PickTarget {
+	texture: Texture,
+	pick_tex: TextureView,
+	size: [u32; 2],
+}
+impl PickTarget {
+	pub fn new(device: &Device, width: u32, height: u32) -> Self {
+		let texture = device.create_texture(&TextureDescriptor {
+			label: Some("Pick Texture"),
+			size: Extent3d { width, height, depth_or_array_layers: 1 },
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: TextureDimension::D2,
+			format: PICK_FORMAT,
+			usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+		});
+		let pick_tex = texture.create_view(&TextureViewDescriptor::default());
+
+		PickTarget { texture, pick_tex, size: [width, height] }
+	}
+
+	pub fn pick_tex(&self) -> &TextureView {
+		&self.pick_tex
+	}
+
+	/// Reads back the path under pixel `(x, y)`, which is `None` outside the target. The copy is submitted straight
+	/// away, but on native the future only resolves once the device is polled, e.g. with `device.poll(Maintain::Wait)`.
+	pub fn pick(
+		&self,
+		device: &Device,
+		queue: &Queue,
+		x: u32,
+		y: u32,
+	) -> impl Future<Output = Result<Option<PathId>, BufferAsyncError>> {
+		// outside the target a pixel is still read, so there's one kind of future, but what's in it is thrown away
+		let inside = x < self.size[0] && y < self.size[1];
+		let (x, y) = if inside { (x, y) } else { (0, 0) };
+
+		let readback = device.create_buffer(&BufferDescriptor {
+			label: Some("Pick Readback Buffer"),
+			size: 4,
+			usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+
+		let mut pick_cmds = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+		pick_cmds.copy_texture_to_buffer(
+			ImageCopyTexture {
+				texture: &self.texture,
+				mip_level: 0,
+				origin: Origin3d { x, y, z: 0 },
+				aspect: TextureAspect::All,
+			},
+			ImageCopyBuffer {
+				buffer: &readback,
+				layout: ImageDataLayout { offset: 0, bytes_per_row: NonZeroU32::new(256), rows_per_image: None },
+			},
+			Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+		);
+		queue.submit(Some(pick_cmds.finish()));
+
+		let mapped = readback.slice(..).map_async(MapMode::Read);
+		async move {
+			mapped.await?;
+			let value = bytemuck::cast_slice::<u8, u32>(&readback.slice(..).get_mapped_range())[0];
+			readback.unmap();
+
+			// 0 is the clear value, so ids are stored off by one
+			Ok(value.checked_sub(1).filter(|_| inside).map(PathId))
+		}
+	}
+}
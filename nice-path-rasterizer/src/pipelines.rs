@@ -1,20 +1,133 @@
 mod jitter;
+mod pick;
 mod rasterize;
+mod stencil_cover;
+#[cfg(feature = "compute")]
+mod tile;
 
 pub use jitter::*;
+pub use pick::*;
 pub use rasterize::*;
+pub use stencil_cover::*;
+#[cfg(feature = "compute")]
+pub use tile::*;
 
-use wgpu::{Device, TextureFormat};
+use wgpu::{CommandBuffer, Device, LoadOp, Texture, TextureFormat, TextureViewDescriptor};
+
+use crate::{IntermediateBufs, PathBufs, PathId, PickTarget, SceneBufs};
+use nice_path_tessellator::FillRule;
+
+/// How [`PathPipelines`] work out how much of each pixel a path covers, before it's painted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RasterBackend {
+	/// Draws the path at 6 jittered offsets, counting each one in a nibble of the intermediate texture by additive
+	/// blending. It always fills with the even-odd rule, so nonzero paths have to be resolved first, like
+	/// [`nice_path_tessellator::Draw::outline`] does.
+	#[default]
+	Jitter,
+	/// Winds a 4x multisampled stencil buffer, with `INVERT` for even-odd fills and wrapping increments and decrements
+	/// for nonzero ones, then covers the view where the stencil is set and resolves the samples into the intermediate
+	/// texture. It handles both fill rules, and suits targets that are better at stencils than blending.
+	StencilCover,
+}
 
 pub struct PathPipelines {
+	pub backend: RasterBackend,
 	pub jitter: JitterPipeline,
+	/// Only made for [`RasterBackend::StencilCover`], since its buffers are as big as the view.
+	pub stencil_cover: Option<StencilCoverPipeline>,
 	pub rasterize: RasterizePipeline,
+	pub pick: PickPipeline,
 }
 impl PathPipelines {
+	/// Rasterizes with the default backend.
 	pub fn new(device: &Device, view_format: TextureFormat, view_size: [u32; 2]) -> Self {
+		Self::with_backend(device, view_format, view_size, RasterBackend::default())
+	}
+
+	pub fn with_backend(
+		device: &Device,
+		view_format: TextureFormat,
+		view_size: [u32; 2],
+		backend: RasterBackend,
+	) -> Self {
+		let stencil_cover = match backend {
+			RasterBackend::Jitter => None,
+			RasterBackend::StencilCover => Some(StencilCoverPipeline::new(device, view_size)),
+		};
 		Self {
+			backend,
 			jitter: JitterPipeline::new(device, view_size),
-			rasterize: RasterizePipeline::new(device, view_format, view_size),
+			stencil_cover,
+			rasterize: RasterizePipeline::new(device, view_format, view_size, backend),
+			pick: PickPipeline::new(device, backend),
+		}
+	}
+
+	/// Works out the coverage of `path_bufs` in `target` with the backend's passes, ready for
+	/// [`RasterizePipeline::draw_paint`]. The jitter backend ignores `fill_rule` and fills with the even-odd rule.
+	pub fn draw_coverage(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		path_bufs: &PathBufs,
+		fill_rule: FillRule,
+	) -> CommandBuffer {
+		match &self.stencil_cover {
+			Some(stencil_cover) => stencil_cover.draw(device, target, path_bufs, fill_rule),
+			None => self.jitter.draw(device, target, path_bufs),
+		}
+	}
+
+	/// Works out the coverage of `path_bufs` in `target` and writes `path_id` into `pick_target` wherever it covers at
+	/// least half of what `target`'s clips leave of a pixel. Drawing paths back to front leaves the topmost one in each
+	/// pixel. `pick_target` has to be the same size as `target`.
+	pub fn draw_pick(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		pick_target: &PickTarget,
+		path_bufs: &PathBufs,
+		fill_rule: FillRule,
+		path_id: PathId,
+	) -> Vec<CommandBuffer> {
+		vec![
+			self.draw_coverage(device, target, path_bufs, fill_rule),
+			self.pick.draw(device, target, pick_target, path_id),
+		]
+	}
+
+	/// Draws every path in `scene` onto `view` over `background`, back to front. `scene` has to be made for this
+	/// backend, or for the jitter one, see [`SceneBufs::with_backend`]. Every path gets its own coverage and
+	/// rasterize passes, since they all share `target`. `view` needs [`wgpu::TextureUsages::COPY_SRC`] if any of the
+	/// paths are blended in the shader, see [`RasterizePipeline::draw_blended`].
+	pub fn draw_scene(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		view: &Texture,
+		scene: &SceneBufs,
+		background: wgpu::Color,
+	) -> Vec<CommandBuffer> {
+		let mut cmds = vec![];
+		if scene.draws.is_empty() {
+			let mut clear_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+			clear_cmds.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: None,
+				color_attachments: &[wgpu::RenderPassColorAttachment {
+					view: &view.create_view(&TextureViewDescriptor::default()),
+					resolve_target: None,
+					ops: wgpu::Operations { load: LoadOp::Clear(background), store: true },
+				}],
+				depth_stencil_attachment: None,
+			});
+			cmds.push(clear_cmds.finish());
+		}
+		for (i, (path_bufs, fill_rule, paint, blend_mode)) in scene.draws.iter().enumerate() {
+			let load = if i == 0 { LoadOp::Clear(background) } else { LoadOp::Load };
+			cmds.push(self.draw_coverage(device, target, path_bufs, *fill_rule));
+			cmds.push(self.rasterize.draw_blended(device, target, view, paint, *blend_mode, load));
 		}
+		cmds
 	}
 }
@@ -0,0 +1,264 @@
+// Shared by rasterize.wgsl, tile.wgsl and pick.wgsl, which have to decode coverage, paint and blend the same. It's
+// prepended to each, so it can't use their bindings.
+
+// BlendMode's discriminants, which tests/blend.rs checks these against
+let BLEND_CLEAR: u32 = 0u;
+let BLEND_SRC: u32 = 1u;
+let BLEND_DST: u32 = 2u;
+let BLEND_SRC_OVER: u32 = 3u;
+let BLEND_DST_OVER: u32 = 4u;
+let BLEND_SRC_IN: u32 = 5u;
+let BLEND_DST_IN: u32 = 6u;
+let BLEND_SRC_OUT: u32 = 7u;
+let BLEND_DST_OUT: u32 = 8u;
+let BLEND_SRC_ATOP: u32 = 9u;
+let BLEND_DST_ATOP: u32 = 10u;
+let BLEND_XOR: u32 = 11u;
+let BLEND_PLUS: u32 = 12u;
+let BLEND_MULTIPLY: u32 = 13u;
+let BLEND_SCREEN: u32 = 14u;
+let BLEND_OVERLAY: u32 = 15u;
+let BLEND_DARKEN: u32 = 16u;
+let BLEND_LIGHTEN: u32 = 17u;
+let BLEND_COLOR_DODGE: u32 = 18u;
+let BLEND_COLOR_BURN: u32 = 19u;
+let BLEND_HARD_LIGHT: u32 = 20u;
+let BLEND_SOFT_LIGHT: u32 = 21u;
+let BLEND_DIFFERENCE: u32 = 22u;
+let BLEND_EXCLUSION: u32 = 23u;
+let BLEND_HUE: u32 = 24u;
+let BLEND_SATURATION: u32 = 25u;
+let BLEND_COLOR: u32 = 26u;
+let BLEND_LUMINOSITY: u32 = 27u;
+
+// The coverage the jitter pass counted in the intermediate texture around `pixel`
+fn jitter_coverage(intermediate: texture_2d<f32>, pixel: vec2<f32>) -> f32 {
+	// Get samples for -2/3 and -1/3
+	var tex_coord_l: vec2<f32> = pixel - vec2<f32>(1.0, 0.0);
+	var value_l: vec2<f32> = textureLoad(intermediate, vec2<i32>(tex_coord_l), 0).xy * 255.0;
+	var lower_l: vec2<f32> = value_l % 16.0;
+	var upper_l: vec2<f32> = (value_l - lower_l) / 16.0;
+	var alpha_l: vec2<f32> = (upper_l % 2.0 + lower_l % 2.0) / 2.0;
+
+	// Get samples for 0, +1/3, and +2/3
+	var value_r: vec3<f32> = textureLoad(intermediate, vec2<i32>(pixel), 0).xyz * 255.0;
+	var lower_r: vec3<f32> = value_r % 16.0;
+	var upper_r: vec3<f32> = (value_r - lower_r) / 16.0;
+	var alpha_r: vec3<f32> = (upper_r % 2.0 + lower_r % 2.0) / 2.0;
+
+	// Average the energy over the pixels on either side
+	return (alpha_l.x + alpha_r.z + alpha_r.y) / 3.0;
+}
+
+// the stencil and cover passes resolve their samples to the coverage in red
+fn resolved_coverage(intermediate: texture_2d<f32>, pixel: vec2<f32>) -> f32 {
+	return textureLoad(intermediate, vec2<i32>(pixel), 0).r;
+}
+
+// how much of the pixel the clip stack leaves, since the masks hold how much they clip away
+fn unclipped(clip_mask: texture_2d<f32>, pixel: vec2<f32>) -> f32 {
+	return 1.0 - textureLoad(clip_mask, vec2<i32>(pixel), 0).r;
+}
+
+// see PaintUniform in paint_buf.rs
+struct Paint {
+	matrix: vec4<f32>;
+	translation_focal: vec4<f32>;
+	info: vec4<u32>;
+	offsets: array<vec4<f32>, 4>;
+	colors: array<vec4<f32>, 16>;
+};
+
+fn gradient_t(paint: Paint, pixel: vec2<f32>) -> f32 {
+	var p: vec2<f32> = vec2<f32>(
+		paint.matrix.x * pixel.x + paint.matrix.z * pixel.y + paint.translation_focal.x,
+		paint.matrix.y * pixel.x + paint.matrix.w * pixel.y + paint.translation_focal.y,
+	);
+	if (paint.info.x == 1u) {
+		return p.x;
+	}
+
+	// where p is between the focal point and the unit circle, along the ray from the focal point
+	var focal: vec2<f32> = paint.translation_focal.zw;
+	var d: vec2<f32> = p - focal;
+	var dd: f32 = dot(d, d);
+	if (dd == 0.0) {
+		return 0.0;
+	}
+	var fd: f32 = dot(focal, d);
+	var s: f32 = (-fd + sqrt(fd * fd - dd * (dot(focal, focal) - 1.0))) / dd;
+	return 1.0 / s;
+}
+
+// premultiplied
+fn paint_color(paint: Paint, pixel: vec2<f32>) -> vec4<f32> {
+	if (paint.info.x == 0u) {
+		return paint.colors[0];
+	}
+
+	var t: f32 = gradient_t(paint, pixel);
+	if (paint.info.y == 1u) {
+		t = 1.0 - abs(fract(t * 0.5) * 2.0 - 1.0);
+	} else if (paint.info.y == 2u) {
+		t = fract(t);
+	} else {
+		t = clamp(t, 0.0, 1.0);
+	}
+
+	// copied, since only variables can be indexed dynamically
+	var stop_offsets: array<vec4<f32>, 4> = paint.offsets;
+	var stop_colors: array<vec4<f32>, 16> = paint.colors;
+	var count: u32 = paint.info.z;
+	var i: u32 = 0u;
+	loop {
+		if (i >= count) {
+			break;
+		}
+		var offset: f32 = stop_offsets[i / 4u][i % 4u];
+		if (offset > t) {
+			if (i == 0u) {
+				return stop_colors[0];
+			}
+			var prev: f32 = stop_offsets[(i - 1u) / 4u][(i - 1u) % 4u];
+			return mix(stop_colors[i - 1u], stop_colors[i], (t - prev) / (offset - prev));
+		}
+		i = i + 1u;
+	}
+	return stop_colors[count - 1u];
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+	var low: vec3<f32> = c / 12.92;
+	var high: vec3<f32> = pow((c + 0.055) / 1.055, vec3<f32>(2.4));
+	return select(high, low, c <= vec3<f32>(0.04045));
+}
+
+// straight rgb, in linear light if the paint is, with the coverage in alpha
+fn shade(paint: Paint, position: vec2<f32>, coverage: f32) -> vec4<f32> {
+	var color: vec4<f32> = paint_color(paint, position);
+	if (color.a <= 0.0) {
+		return vec4<f32>(0.0);
+	}
+	var rgb: vec3<f32> = color.rgb / color.a;
+	if (paint.info.w == 1u) {
+		rgb = srgb_to_linear(rgb);
+	}
+	return vec4<f32>(rgb, color.a * coverage);
+}
+
+fn luminosity(c: vec3<f32>) -> f32 {
+	return dot(c, vec3<f32>(0.3, 0.59, 0.11));
+}
+
+fn saturation(c: vec3<f32>) -> f32 {
+	return max(max(c.r, c.g), c.b) - min(min(c.r, c.g), c.b);
+}
+
+// stretches c between 0 and s, keeping its hue
+fn with_saturation(c: vec3<f32>, s: f32) -> vec3<f32> {
+	var low: f32 = min(min(c.r, c.g), c.b);
+	var high: f32 = max(max(c.r, c.g), c.b);
+	if (high > low) {
+		return (c - low) * s / (high - low);
+	}
+	return vec3<f32>(0.0);
+}
+
+// shifts c to the luminosity l, then pulls it back between 0 and 1 towards grey
+fn with_luminosity(c: vec3<f32>, l: f32) -> vec3<f32> {
+	var shifted: vec3<f32> = c + (l - luminosity(c));
+	var mid: f32 = luminosity(shifted);
+	var low: f32 = min(min(shifted.r, shifted.g), shifted.b);
+	var high: f32 = max(max(shifted.r, shifted.g), shifted.b);
+	if (low < 0.0) {
+		shifted = mid + (shifted - mid) * mid / (mid - low);
+	}
+	if (high > 1.0) {
+		shifted = mid + (shifted - mid) * (1.0 - mid) / (high - mid);
+	}
+	return shifted;
+}
+
+fn hard_light(s: vec3<f32>, d: vec3<f32>) -> vec3<f32> {
+	var screened: vec3<f32> = 2.0 * s - 1.0;
+	return select(d + screened - d * screened, d * 2.0 * s, s <= vec3<f32>(0.5));
+}
+
+// the colour the blend modes give where the straight src and dst are both opaque, see BlendMode::blend
+fn mix_colors(mode: u32, s: vec3<f32>, d: vec3<f32>) -> vec3<f32> {
+	var zero: vec3<f32> = vec3<f32>(0.0);
+	var one: vec3<f32> = vec3<f32>(1.0);
+	if (mode == BLEND_MULTIPLY) {
+		return s * d;
+	} else if (mode == BLEND_SCREEN) {
+		return s + d - s * d;
+	} else if (mode == BLEND_OVERLAY) {
+		return hard_light(d, s);
+	} else if (mode == BLEND_DARKEN) {
+		return min(s, d);
+	} else if (mode == BLEND_LIGHTEN) {
+		return max(s, d);
+	} else if (mode == BLEND_COLOR_DODGE) {
+		return select(select(min(one, d / (1.0 - s)), one, s >= one), zero, d <= zero);
+	} else if (mode == BLEND_COLOR_BURN) {
+		return select(select(1.0 - min(one, (1.0 - d) / s), zero, s <= zero), one, d >= one);
+	} else if (mode == BLEND_HARD_LIGHT) {
+		return hard_light(s, d);
+	} else if (mode == BLEND_SOFT_LIGHT) {
+		var lightened: vec3<f32> = select(sqrt(d), ((16.0 * d - 12.0) * d + 4.0) * d, d <= vec3<f32>(0.25));
+		return select(d + (2.0 * s - 1.0) * (lightened - d), d - (1.0 - 2.0 * s) * d * (1.0 - d), s <= vec3<f32>(0.5));
+	} else if (mode == BLEND_DIFFERENCE) {
+		return abs(s - d);
+	} else if (mode == BLEND_EXCLUSION) {
+		return s + d - 2.0 * s * d;
+	} else if (mode == BLEND_HUE) {
+		return with_luminosity(with_saturation(s, saturation(d)), luminosity(d));
+	} else if (mode == BLEND_SATURATION) {
+		return with_luminosity(with_saturation(d, saturation(s)), luminosity(d));
+	} else if (mode == BLEND_COLOR) {
+		return with_luminosity(s, luminosity(d));
+	}
+	return with_luminosity(d, luminosity(s));
+}
+
+// Composites the premultiplied src onto dst with a BlendMode, like BlendMode::blend. The modes from Multiply on are the
+// ones that mix colours.
+fn blend_colors(mode: u32, src: vec4<f32>, dst: vec4<f32>) -> vec4<f32> {
+	var sa: f32 = src.a;
+	var da: f32 = dst.a;
+	if (mode >= BLEND_MULTIPLY) {
+		var s: vec3<f32> = select(src.rgb / sa, vec3<f32>(0.0), sa <= 0.0);
+		var d: vec3<f32> = select(dst.rgb / da, vec3<f32>(0.0), da <= 0.0);
+		var rgb: vec3<f32> = src.rgb * (1.0 - da) + dst.rgb * (1.0 - sa) + sa * da * mix_colors(mode, s, d);
+		return vec4<f32>(rgb, sa + da - sa * da);
+	}
+
+	// the Porter-Duff operators' factors for src and dst
+	var f: vec2<f32> = vec2<f32>(0.0);
+	if (mode == BLEND_SRC) {
+		f = vec2<f32>(1.0, 0.0);
+	} else if (mode == BLEND_DST) {
+		f = vec2<f32>(0.0, 1.0);
+	} else if (mode == BLEND_SRC_OVER) {
+		f = vec2<f32>(1.0, 1.0 - sa);
+	} else if (mode == BLEND_DST_OVER) {
+		f = vec2<f32>(1.0 - da, 1.0);
+	} else if (mode == BLEND_SRC_IN) {
+		f = vec2<f32>(da, 0.0);
+	} else if (mode == BLEND_DST_IN) {
+		f = vec2<f32>(0.0, sa);
+	} else if (mode == BLEND_SRC_OUT) {
+		f = vec2<f32>(1.0 - da, 0.0);
+	} else if (mode == BLEND_DST_OUT) {
+		f = vec2<f32>(0.0, 1.0 - sa);
+	} else if (mode == BLEND_SRC_ATOP) {
+		f = vec2<f32>(da, 1.0 - sa);
+	} else if (mode == BLEND_DST_ATOP) {
+		f = vec2<f32>(1.0 - da, sa);
+	} else if (mode == BLEND_XOR) {
+		f = vec2<f32>(1.0 - da, 1.0 - sa);
+	} else if (mode == BLEND_PLUS) {
+		f = vec2<f32>(1.0, 1.0);
+	}
+	return min(src * f.x + dst * f.y, vec4<f32>(1.0));
+}
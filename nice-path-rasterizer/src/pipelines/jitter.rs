@@ -10,8 +10,8 @@ use wgpu::{
 
 use crate::{IntermediateBufs, PathBufs, JITTER_FORMAT};
 
-/// offset x, offset y, mode (0: fill, 1: quadratic)
-const JITTER: &[[f32; 3]] = &[
+/// offset x, offset y, mode (0: fill, 1: curve)
+pub(crate) const JITTER: &[[f32; 3]] = &[
 	// fill
 	[-1.0f32 / 12.0, -5.0 / 12.0, 0.0],
 	[1.0 / 12.0, 1.0 / 12.0, 0.0],
@@ -19,7 +19,7 @@ const JITTER: &[[f32; 3]] = &[
 	[5.0 / 12.0, 5.0 / 12.0, 0.0],
 	[7.0 / 12.0, -3.0 / 12.0, 0.0],
 	[9.0 / 12.0, 3.0 / 12.0, 0.0],
-	// quadratic
+	// curve
 	[-1.0f32 / 12.0, -5.0 / 12.0, 1.0],
 	[1.0 / 12.0, 1.0 / 12.0, 1.0],
 	[3.0 / 12.0, -1.0 / 12.0, 1.0],
@@ -71,6 +71,11 @@ impl JitterPipeline {
 				step_mode: VertexStepMode::Vertex,
 				attributes: &[VertexAttribute { format: VertexFormat::Float32x2, offset: 0, shader_location: 1 }],
 			},
+			VertexBufferLayout {
+				array_stride: size_of::<[f32; 4]>() as _,
+				step_mode: VertexStepMode::Vertex,
+				attributes: &[VertexAttribute { format: VertexFormat::Float32x4, offset: 0, shader_location: 2 }],
+			},
 		];
 
 		let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
@@ -143,11 +148,12 @@ impl JitterPipeline {
 			rpass.push_debug_group("jitter");
 			rpass.set_pipeline(&self.pipeline);
 			rpass.set_bind_group(0, &bind_group, &[]);
-			rpass.set_index_buffer(path_bufs.fill_idxs.slice(..), wgpu::IndexFormat::Uint16);
+			rpass.set_index_buffer(path_bufs.fill_idxs.slice(..), wgpu::IndexFormat::Uint32);
 			rpass.set_vertex_buffer(0, self.instances.slice(..));
 			rpass.set_vertex_buffer(1, path_bufs.verts.slice(..));
+			rpass.set_vertex_buffer(2, path_bufs.curve_coords.slice(..));
 			rpass.draw_indexed(0..path_bufs.fill_idx_len as u32, 0, 0..6);
-			rpass.set_index_buffer(path_bufs.quadratic_idxs.slice(..), wgpu::IndexFormat::Uint16);
+			rpass.set_index_buffer(path_bufs.quadratic_idxs.slice(..), wgpu::IndexFormat::Uint32);
 			rpass.draw_indexed(0..path_bufs.quadratic_idx_len as u32, 0, 6..12);
 			rpass.pop_debug_group();
 		}
@@ -0,0 +1,162 @@
+use crate::{IntermediateBufs, PathId, PickTarget, RasterBackend, PICK_FORMAT};
+use std::{borrow::Cow, mem::size_of};
+use wgpu::{
+	util::{BufferInitDescriptor, DeviceExt},
+	BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, Buffer, BufferAddress,
+	BufferBindingType, BufferSize, BufferUsages, ColorTargetState, ColorWrites, CommandBuffer, Device, FragmentState,
+	MultisampleState, PipelineLayoutDescriptor, PrimitiveState, RenderPipeline, RenderPipelineDescriptor,
+	ShaderModuleDescriptor, ShaderSource, ShaderStages, TextureSampleType, TextureViewDimension, VertexAttribute,
+	VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
+};
+
+/// Writes a path's id into a [`PickTarget`] wherever its coverage, less what the clip stack clips away, is at least
+/// half the pixel. Run it after the coverage passes for each path, in the same order the paths are rasterized, or
+/// use [`crate::PathPipelines::draw_pick`].
+pub struct PickPipeline {
+	pub tri: Buffer,
+	pub pipeline: RenderPipeline,
+	pub bind_group_layout: BindGroupLayout,
+}
+impl PickPipeline {
+	/// The coverage in the intermediate texture is read the way `backend` writes it.
+	pub fn new(device: &Device, backend: RasterBackend) -> Self {
+		let tri = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Triangle"),
+			contents: bytemuck::cast_slice(&[[0.0f32, 0.0], [2.0, 0.0], [0.0, 2.0]]),
+			usage: BufferUsages::VERTEX,
+		});
+
+		let shader = device.create_shader_module(&ShaderModuleDescriptor {
+			label: None,
+			source: ShaderSource::Wgsl(Cow::Borrowed(concat!(include_str!("common.wgsl"), include_str!("pick.wgsl")))),
+		});
+
+		let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+			label: None,
+			entries: &[
+				BindGroupLayoutEntry {
+					binding: 0,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Buffer {
+						ty: BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: BufferSize::new(4),
+					},
+					count: None,
+				},
+				BindGroupLayoutEntry {
+					binding: 1,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Texture {
+						multisampled: false,
+						sample_type: TextureSampleType::Float { filterable: false },
+						view_dimension: TextureViewDimension::D2,
+					},
+					count: None,
+				},
+				BindGroupLayoutEntry {
+					binding: 2,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Texture {
+						multisampled: false,
+						sample_type: TextureSampleType::Float { filterable: false },
+						view_dimension: TextureViewDimension::D2,
+					},
+					count: None,
+				},
+			],
+		});
+		let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+			label: None,
+			bind_group_layouts: &[&bind_group_layout],
+			push_constant_ranges: &[],
+		});
+
+		let vertex_buffers = [VertexBufferLayout {
+			array_stride: size_of::<[f32; 2]>() as BufferAddress,
+			step_mode: VertexStepMode::Vertex,
+			attributes: &[VertexAttribute { format: VertexFormat::Float32x2, offset: 0, shader_location: 0 }],
+		}];
+
+		let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+			label: None,
+			layout: Some(&pipeline_layout),
+			vertex: VertexState { module: &shader, entry_point: "vs_main", buffers: &vertex_buffers },
+			fragment: Some(FragmentState {
+				module: &shader,
+				entry_point: match backend {
+					RasterBackend::Jitter => "fs_main",
+					RasterBackend::StencilCover => "fs_resolved",
+				},
+				targets: &[ColorTargetState { format: PICK_FORMAT, blend: None, write_mask: ColorWrites::ALL }],
+			}),
+			primitive: PrimitiveState { cull_mode: None, ..Default::default() },
+			depth_stencil: None,
+			multisample: MultisampleState::default(),
+			multiview: None,
+		});
+
+		Self { tri, pipeline, bind_group_layout }
+	}
+
+	/// Clears every pixel of `pick_target` to "no path".
+	pub fn clear(&self, device: &Device, pick_target: &PickTarget) -> CommandBuffer {
+		let mut clear_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+		clear_cmds.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: None,
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: pick_target.pick_tex(),
+				resolve_target: None,
+				ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), store: true },
+			}],
+			depth_stencil_attachment: None,
+		});
+		clear_cmds.finish()
+	}
+
+	pub fn draw(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		pick_target: &PickTarget,
+		path_id: PathId,
+	) -> CommandBuffer {
+		let uniform = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Uniform Buffer"),
+			contents: bytemuck::cast_slice(&[path_id.0]),
+			usage: BufferUsages::UNIFORM,
+		});
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			layout: &self.bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry { binding: 0, resource: uniform.as_entire_binding() },
+				wgpu::BindGroupEntry {
+					binding: 1,
+					resource: wgpu::BindingResource::TextureView(target.intermediate_tex()),
+				},
+				wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(target.clip_mask()) },
+			],
+			label: None,
+		});
+
+		let mut pick_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+		{
+			let mut rpass = pick_cmds.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: None,
+				color_attachments: &[wgpu::RenderPassColorAttachment {
+					view: pick_target.pick_tex(),
+					resolve_target: None,
+					ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: true },
+				}],
+				depth_stencil_attachment: None,
+			});
+			rpass.push_debug_group("pick");
+			rpass.set_pipeline(&self.pipeline);
+			rpass.set_bind_group(0, &bind_group, &[]);
+			rpass.set_vertex_buffer(0, self.tri.slice(..));
+			rpass.draw(0..3, 0..1);
+			rpass.pop_debug_group();
+		}
+		pick_cmds.finish()
+	}
+}
@@ -0,0 +1,42 @@
+struct VertexOutput {
+	[[builtin(position)]] position: vec4<f32>;
+};
+
+struct PickInfo {
+	path_id: u32;
+};
+
+[[group(0), binding(0)]]
+var<uniform> pick_info: PickInfo;
+[[group(0), binding(1)]]
+var intermediate: texture_2d<f32>;
+// see IntermediateBufs::clip_mask
+[[group(0), binding(2)]]
+var clip_mask: texture_2d<f32>;
+
+[[stage(vertex)]]
+fn vs_main(
+	[[location(0)]] position: vec2<f32>,
+) -> VertexOutput {
+	var out: VertexOutput;
+	out.position = vec4<f32>((position * 2.0 - 1.0) * vec2<f32>(1.0, -1.0), 0.0, 1.0);
+	return out;
+}
+
+// The path owns the pixel if it covers at least half of what the clips leave of it
+fn pick(coverage: f32) -> u32 {
+	if (coverage < 0.5) {
+		discard;
+	}
+	return pick_info.path_id + 1u;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] u32 {
+	return pick(jitter_coverage(intermediate, in.position.xy) * unclipped(clip_mask, in.position.xy));
+}
+
+[[stage(fragment)]]
+fn fs_resolved(in: VertexOutput) -> [[location(0)]] u32 {
+	return pick(resolved_coverage(intermediate, in.position.xy) * unclipped(clip_mask, in.position.xy));
+}
@@ -1,23 +1,91 @@
-use crate::IntermediateBufs;
-use std::{borrow::Cow, mem::size_of};
+use crate::{paint_buf::PaintUniform, IntermediateBufs, PaintBuf, RasterBackend, CLIP_FORMAT};
+use bytemuck::{Pod, Zeroable};
+use nice_path_tessellator::{BlendMode, Color, Paint, Transform};
+use std::{borrow::Cow, mem::size_of, sync::OnceLock};
 use wgpu::{
 	util::{BufferInitDescriptor, DeviceExt},
-	BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, BlendComponent, BlendFactor,
-	BlendOperation, BlendState, Buffer, BufferAddress, BufferBindingType, BufferSize, BufferUsages, ColorTargetState,
-	ColorWrites, CommandBuffer, Device, FragmentState, MultisampleState, PipelineLayoutDescriptor, PrimitiveState,
-	RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages, TextureFormat,
-	TextureSampleType, TextureView, TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexFormat,
-	VertexState, VertexStepMode,
+	BindGroup, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, BlendComponent,
+	BlendFactor, BlendOperation, BlendState, Buffer, BufferAddress, BufferBindingType, BufferSize, BufferUsages,
+	ColorTargetState, ColorWrites, CommandBuffer, CommandEncoder, Device, Extent3d, FragmentState, ImageCopyTexture,
+	LoadOp, MultisampleState, Origin3d, PipelineLayout, PipelineLayoutDescriptor, PrimitiveState, RenderPipeline,
+	RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor, ShaderSource, ShaderStages, Texture, TextureAspect,
+	TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureView,
+	TextureViewDescriptor, TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexFormat, VertexState,
+	VertexStepMode,
 };
 
+/// The layout of `Blend` in `rasterize.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct BlendUniform {
+	/// the [`BlendMode`], numbered in the order they're declared
+	mode: u32,
+	in_shader: u32,
+	padding: [u32; 2],
+}
+
+/// The fixed-function blending for `mode`, if there is any, with the paint premultiplied and faded by its coverage.
+/// Fading the paint only fades the blend between it and what's under it when the factor for what's under it is 1 or
+/// 1 minus the paint's alpha, so the operators with other factors, like [`BlendMode::SrcIn`], are blended in the
+/// shader. [`BlendMode::SrcOver`] is normal alpha blending, with the paint's colour straight.
+pub fn fixed_function_blend(mode: BlendMode) -> Option<BlendState> {
+	let component = |src_factor, dst_factor| BlendComponent { src_factor, dst_factor, operation: BlendOperation::Add };
+	let porter_duff = |src_factor, dst_factor| {
+		let alpha = component(src_factor, dst_factor);
+		Some(BlendState { color: alpha, alpha })
+	};
+	match mode {
+		BlendMode::SrcOver => Some(BlendState::ALPHA_BLENDING),
+		BlendMode::Dst => porter_duff(BlendFactor::Zero, BlendFactor::One),
+		BlendMode::DstOver => porter_duff(BlendFactor::OneMinusDstAlpha, BlendFactor::One),
+		BlendMode::DstOut => porter_duff(BlendFactor::Zero, BlendFactor::OneMinusSrcAlpha),
+		BlendMode::SrcAtop => porter_duff(BlendFactor::DstAlpha, BlendFactor::OneMinusSrcAlpha),
+		BlendMode::Xor => porter_duff(BlendFactor::OneMinusDstAlpha, BlendFactor::OneMinusSrcAlpha),
+		BlendMode::Plus => porter_duff(BlendFactor::One, BlendFactor::One),
+		// the same as src + dst - src * dst, in every channel
+		BlendMode::Screen => Some(BlendState {
+			color: component(BlendFactor::One, BlendFactor::OneMinusSrc),
+			alpha: component(BlendFactor::One, BlendFactor::OneMinusSrcAlpha),
+		}),
+		_ => None,
+	}
+}
+
+/// The triangle that covers the view.
+fn vertex_buffers() -> [VertexBufferLayout<'static>; 1] {
+	[VertexBufferLayout {
+		array_stride: size_of::<[f32; 2]>() as BufferAddress,
+		step_mode: VertexStepMode::Vertex,
+		attributes: &[VertexAttribute { format: VertexFormat::Float32x2, offset: 0, shader_location: 0 }],
+	}]
+}
+
 pub struct RasterizePipeline {
 	pub tri: Buffer,
+	/// Paints with [`BlendMode::SrcOver`].
 	pub pipeline: RenderPipeline,
+	/// Writes coverage into a clip mask instead of painting it, see [`IntermediateBufs::push_clip`].
+	pub clip: RenderPipeline,
 	pub bind_group_layout: BindGroupLayout,
 	pub uniform: Buffer,
+	/// The solid white that [`RasterizePipeline::draw`] paints with.
+	pub white: PaintBuf,
+	/// What's under the path, copied from the view for the blend modes that are blended in the shader.
+	pub backdrop: Texture,
+	/// A [`BlendUniform`] for every [`BlendMode`], in order.
+	blend_uniforms: Vec<Buffer>,
+	/// The pipelines for the other blend modes, in order, made when they're first drawn with. All of the modes that
+	/// are blended in the shader share the first one of them.
+	blend_pipelines: Vec<OnceLock<RenderPipeline>>,
+	shader: ShaderModule,
+	pipeline_layout: PipelineLayout,
+	backend: RasterBackend,
+	view_format: TextureFormat,
+	view_size: [u32; 2],
 }
 impl RasterizePipeline {
-	pub fn new(device: &Device, view_format: TextureFormat, view_size: [u32; 2]) -> Self {
+	/// The coverage in the intermediate texture is read the way `backend` writes it.
+	pub fn new(device: &Device, view_format: TextureFormat, view_size: [u32; 2], backend: RasterBackend) -> Self {
 		let tri = device.create_buffer_init(&BufferInitDescriptor {
 			label: Some("Triangle"),
 			contents: bytemuck::cast_slice(&[[0.0f32, 0.0], [2.0, 0.0], [0.0, 2.0]]),
@@ -26,7 +94,10 @@ impl RasterizePipeline {
 
 		let shader = device.create_shader_module(&ShaderModuleDescriptor {
 			label: None,
-			source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("rasterize.wgsl"))),
+			source: ShaderSource::Wgsl(Cow::Borrowed(concat!(
+				include_str!("common.wgsl"),
+				include_str!("rasterize.wgsl")
+			))),
 		});
 
 		let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
@@ -52,6 +123,46 @@ impl RasterizePipeline {
 					},
 					count: None,
 				},
+				BindGroupLayoutEntry {
+					binding: 2,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Buffer {
+						ty: BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: BufferSize::new(size_of::<PaintUniform>() as _),
+					},
+					count: None,
+				},
+				BindGroupLayoutEntry {
+					binding: 3,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Texture {
+						multisampled: false,
+						sample_type: TextureSampleType::Float { filterable: false },
+						view_dimension: TextureViewDimension::D2,
+					},
+					count: None,
+				},
+				BindGroupLayoutEntry {
+					binding: 4,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Texture {
+						multisampled: false,
+						sample_type: TextureSampleType::Float { filterable: false },
+						view_dimension: TextureViewDimension::D2,
+					},
+					count: None,
+				},
+				BindGroupLayoutEntry {
+					binding: 5,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Buffer {
+						ty: BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: BufferSize::new(size_of::<BlendUniform>() as _),
+					},
+					count: None,
+				},
 			],
 		});
 		let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
@@ -60,11 +171,7 @@ impl RasterizePipeline {
 			push_constant_ranges: &[],
 		});
 
-		let vertex_buffers = [VertexBufferLayout {
-			array_stride: size_of::<[f32; 2]>() as BufferAddress,
-			step_mode: VertexStepMode::Vertex,
-			attributes: &[VertexAttribute { format: VertexFormat::Float32x2, offset: 0, shader_location: 0 }],
-		}];
+		let vertex_buffers = vertex_buffers();
 
 		let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
 			label: None,
@@ -72,7 +179,10 @@ impl RasterizePipeline {
 			vertex: VertexState { module: &shader, entry_point: "vs_main", buffers: &vertex_buffers },
 			fragment: Some(FragmentState {
 				module: &shader,
-				entry_point: "fs_main",
+				entry_point: match backend {
+					RasterBackend::Jitter => "fs_main",
+					RasterBackend::StencilCover => "fs_resolved",
+				},
 				targets: &[ColorTargetState {
 					format: view_format,
 					blend: Some(BlendState::ALPHA_BLENDING),
@@ -84,51 +194,252 @@ impl RasterizePipeline {
 			multisample: MultisampleState::default(),
 			multiview: None,
 		});
+		let clip = device.create_render_pipeline(&RenderPipelineDescriptor {
+			label: None,
+			layout: Some(&pipeline_layout),
+			vertex: VertexState { module: &shader, entry_point: "vs_main", buffers: &vertex_buffers },
+			fragment: Some(FragmentState {
+				module: &shader,
+				entry_point: match backend {
+					RasterBackend::Jitter => "fs_clip",
+					RasterBackend::StencilCover => "fs_clip_resolved",
+				},
+				targets: &[ColorTargetState { format: CLIP_FORMAT, blend: None, write_mask: ColorWrites::ALL }],
+			}),
+			primitive: PrimitiveState { cull_mode: None, ..Default::default() },
+			depth_stencil: None,
+			multisample: MultisampleState::default(),
+			multiview: None,
+		});
 
-		let view_size = [view_size[0] as f32, view_size[1] as f32];
 		let uniform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
 			label: Some("Uniform Buffer"),
-			contents: bytemuck::cast_slice(&view_size),
+			contents: bytemuck::cast_slice(&view_size.map(|size| size as f32)),
 			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
 		});
 
-		Self { tri, pipeline, bind_group_layout, uniform }
+		let white = PaintBuf::new(device, &Paint::Solid(Color::WHITE), 1.0, &Transform::IDENTITY, view_format);
+
+		let backdrop = device.create_texture(&TextureDescriptor {
+			label: Some("Backdrop Texture"),
+			size: Extent3d { width: view_size[0], height: view_size[1], depth_or_array_layers: 1 },
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: TextureDimension::D2,
+			format: view_format,
+			usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+		});
+		let blend_uniforms = BlendMode::ALL
+			.iter()
+			.map(|&mode| {
+				let blend = BlendUniform {
+					mode: mode as u32,
+					in_shader: fixed_function_blend(mode).is_none() as u32,
+					padding: [0; 2],
+				};
+				device.create_buffer_init(&BufferInitDescriptor {
+					label: Some("Blend Uniform Buffer"),
+					contents: bytemuck::bytes_of(&blend),
+					usage: BufferUsages::UNIFORM,
+				})
+			})
+			.collect();
+
+		Self {
+			tri,
+			pipeline,
+			clip,
+			bind_group_layout,
+			uniform,
+			white,
+			backdrop,
+			blend_uniforms,
+			blend_pipelines: BlendMode::ALL.iter().map(|_| OnceLock::new()).collect(),
+			shader,
+			pipeline_layout,
+			backend,
+			view_format,
+			view_size,
+		}
 	}
 
 	pub fn draw(&self, device: &Device, target: &IntermediateBufs, view: &TextureView) -> CommandBuffer {
-		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+		let background = wgpu::Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 };
+		self.draw_paint(device, target, view, &self.white, LoadOp::Clear(background))
+	}
+
+	/// Paints the coverage in `target` onto `view` with `paint`, clipped by `target`'s clip stack. `load` is what's
+	/// under it, which is the view's contents when drawing several paths on top of each other.
+	pub fn draw_paint(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		view: &TextureView,
+		paint: &PaintBuf,
+		load: LoadOp<wgpu::Color>,
+	) -> CommandBuffer {
+		let bind_group = self.bind_group(device, target, paint, target.clip_mask(), BlendMode::SrcOver);
+		self.draw_pass(device, &bind_group, &self.pipeline, view, load, "rasterize")
+	}
+
+	/// Like [`RasterizePipeline::draw_paint`], but composited with `blend_mode`. `view` has to be this pipeline's
+	/// size, and the modes without [`fixed_function_blend`]ing copy it into [`RasterizePipeline::backdrop`] first,
+	/// for which it needs [`TextureUsages::COPY_SRC`].
+	pub fn draw_blended(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		view: &Texture,
+		paint: &PaintBuf,
+		blend_mode: BlendMode,
+		load: LoadOp<wgpu::Color>,
+	) -> CommandBuffer {
+		let view_tex = view.create_view(&TextureViewDescriptor::default());
+		if blend_mode == BlendMode::SrcOver {
+			return self.draw_paint(device, target, &view_tex, paint, load);
+		}
+		let bind_group = self.bind_group(device, target, paint, target.clip_mask(), blend_mode);
+		let pipeline = self.blend_pipeline(device, blend_mode);
+		let mut rasterize_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+		let load = match fixed_function_blend(blend_mode) {
+			Some(_) => load,
+			None => {
+				// the backdrop has to be cleared before it's copied
+				if let LoadOp::Clear(_) = load {
+					self.pass(&mut rasterize_cmds, None, None, &view_tex, load, "clear");
+				}
+				rasterize_cmds.copy_texture_to_texture(
+					ImageCopyTexture {
+						texture: view,
+						mip_level: 0,
+						origin: Origin3d::ZERO,
+						aspect: TextureAspect::All,
+					},
+					ImageCopyTexture {
+						texture: &self.backdrop,
+						mip_level: 0,
+						origin: Origin3d::ZERO,
+						aspect: TextureAspect::All,
+					},
+					Extent3d { width: self.view_size[0], height: self.view_size[1], depth_or_array_layers: 1 },
+				);
+				LoadOp::Load
+			},
+		};
+		self.pass(&mut rasterize_cmds, Some(&bind_group), Some(pipeline), &view_tex, load, "blend");
+		rasterize_cmds.finish()
+	}
+
+	/// Multiplies the coverage in `target` by what `parent` leaves unclipped, into `clip`.
+	pub fn draw_clip(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		parent: &TextureView,
+		clip: &TextureView,
+	) -> CommandBuffer {
+		let bind_group = self.bind_group(device, target, &self.white, parent, BlendMode::SrcOver);
+		self.draw_pass(device, &bind_group, &self.clip, clip, LoadOp::Clear(wgpu::Color::TRANSPARENT), "clip")
+	}
+
+	fn bind_group(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		paint: &PaintBuf,
+		clip_mask: &TextureView,
+		blend_mode: BlendMode,
+	) -> BindGroup {
+		let backdrop = self.backdrop.create_view(&TextureViewDescriptor::default());
+		device.create_bind_group(&wgpu::BindGroupDescriptor {
 			layout: &self.bind_group_layout,
 			entries: &[
 				wgpu::BindGroupEntry { binding: 0, resource: self.uniform.as_entire_binding() },
 				wgpu::BindGroupEntry {
 					binding: 1,
-					resource: wgpu::BindingResource::TextureView(&target.intermediate_tex()),
+					resource: wgpu::BindingResource::TextureView(target.intermediate_tex()),
+				},
+				wgpu::BindGroupEntry { binding: 2, resource: paint.uniform.as_entire_binding() },
+				wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(clip_mask) },
+				wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&backdrop) },
+				wgpu::BindGroupEntry {
+					binding: 5,
+					resource: self.blend_uniforms[blend_mode as usize].as_entire_binding(),
 				},
 			],
 			label: None,
-		});
+		})
+	}
 
-		let mut rasterize_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-		{
-			let mut rpass = rasterize_cmds.begin_render_pass(&wgpu::RenderPassDescriptor {
+	/// The pipeline for a blend mode other than [`BlendMode::SrcOver`], made the first time it's needed.
+	fn blend_pipeline(&self, device: &Device, blend_mode: BlendMode) -> &RenderPipeline {
+		let blend = fixed_function_blend(blend_mode);
+		// the modes blended in the shader all share the first one's pipeline
+		let index = match blend {
+			Some(_) => blend_mode as usize,
+			None => BlendMode::ALL.iter().position(|&mode| fixed_function_blend(mode).is_none()).unwrap(),
+		};
+		self.blend_pipelines[index].get_or_init(|| {
+			device.create_render_pipeline(&RenderPipelineDescriptor {
 				label: None,
-				color_attachments: &[wgpu::RenderPassColorAttachment {
-					view,
-					resolve_target: None,
-					ops: wgpu::Operations {
-						load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 }),
-						store: true,
+				layout: Some(&self.pipeline_layout),
+				vertex: VertexState { module: &self.shader, entry_point: "vs_main", buffers: &vertex_buffers() },
+				fragment: Some(FragmentState {
+					module: &self.shader,
+					entry_point: match self.backend {
+						RasterBackend::Jitter => "fs_blend",
+						RasterBackend::StencilCover => "fs_blend_resolved",
 					},
-				}],
-				depth_stencil_attachment: None,
-			});
-			rpass.push_debug_group("rasterize");
-			rpass.set_pipeline(&self.pipeline);
-			rpass.set_bind_group(0, &bind_group, &[]);
+					targets: &[ColorTargetState { format: self.view_format, blend, write_mask: ColorWrites::ALL }],
+				}),
+				primitive: PrimitiveState { cull_mode: None, ..Default::default() },
+				depth_stencil: None,
+				multisample: MultisampleState::default(),
+				multiview: None,
+			})
+		})
+	}
+
+	fn draw_pass(
+		&self,
+		device: &Device,
+		bind_group: &BindGroup,
+		pipeline: &RenderPipeline,
+		view: &TextureView,
+		load: LoadOp<wgpu::Color>,
+		label: &str,
+	) -> CommandBuffer {
+		let mut rasterize_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+		self.pass(&mut rasterize_cmds, Some(bind_group), Some(pipeline), view, load, label);
+		rasterize_cmds.finish()
+	}
+
+	/// A pass that draws over the whole view, or only loads it without a pipeline.
+	fn pass(
+		&self,
+		encoder: &mut CommandEncoder,
+		bind_group: Option<&BindGroup>,
+		pipeline: Option<&RenderPipeline>,
+		view: &TextureView,
+		load: LoadOp<wgpu::Color>,
+		label: &str,
+	) {
+		let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: None,
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view,
+				resolve_target: None,
+				ops: wgpu::Operations { load, store: true },
+			}],
+			depth_stencil_attachment: None,
+		});
+		if let (Some(bind_group), Some(pipeline)) = (bind_group, pipeline) {
+			rpass.push_debug_group(label);
+			rpass.set_pipeline(pipeline);
+			rpass.set_bind_group(0, bind_group, &[]);
 			rpass.set_vertex_buffer(0, self.tri.slice(..));
 			rpass.draw(0..3, 0..1);
 			rpass.pop_debug_group();
 		}
-		rasterize_cmds.finish()
 	}
 }
@@ -11,6 +11,25 @@ struct ViewInfo {
 var<uniform> view_info: ViewInfo;
 [[group(0), binding(1)]]
 var intermediate: texture_2d<f32>;
+// see IntermediateBufs::clip_mask
+[[group(0), binding(3)]]
+var clip_mask: texture_2d<f32>;
+// a copy of the view, for the blend modes blended here
+[[group(0), binding(4)]]
+var backdrop: texture_2d<f32>;
+
+// see BlendUniform in rasterize.rs
+struct Blend {
+	mode: u32;
+	// whether the mode is blended here instead of by fixed-function blending
+	in_shader: u32;
+};
+[[group(0), binding(5)]]
+var<uniform> blend: Blend;
+
+// see PaintUniform in paint_buf.rs
+[[group(0), binding(2)]]
+var<uniform> paint: Paint;
 
 [[stage(vertex)]]
 fn vs_main(
@@ -24,26 +43,54 @@ fn vs_main(
 	return out;
 }
 
+// The jitter pass's texels line up with the intermediate texture's, which can be bigger than the view
+fn jitter_pixel(in: VertexOutput) -> vec2<f32> {
+	return in.tex_coord * vec2<f32>(textureDimensions(intermediate));
+}
+
+// The paint, premultiplied and faded by how much of it is covered and unclipped, for fixed-function blending.
+// Otherwise it's blended onto the backdrop here, fading between the blended colour and what was there.
+fn blended(in: VertexOutput, coverage: f32) -> vec4<f32> {
+	var color: vec4<f32> = shade(paint, in.position.xy, 1.0);
+	var src: vec4<f32> = vec4<f32>(color.rgb * color.a, color.a);
+	var fade: f32 = coverage * unclipped(clip_mask, in.position.xy);
+	if (blend.in_shader == 0u) {
+		return src * fade;
+	}
+	if (fade <= 0.0) {
+		discard;
+	}
+	var dst: vec4<f32> = textureLoad(backdrop, vec2<i32>(in.position.xy), 0);
+	return mix(dst, blend_colors(blend.mode, src, dst), fade);
+}
+
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-	var intermediate_size: vec2<f32> = vec2<f32>(textureDimensions(intermediate));
-
-	// Get samples for -2/3 and -1/3
-	var tex_coord1: vec2<f32> = in.tex_coord * intermediate_size - vec2<f32>(1.0, 0.0);
-	var value_l: vec2<f32> = textureLoad(intermediate, vec2<i32>(tex_coord1), 0).xy * 255.0;
-	var lower_l: vec2<f32> = value_l % 16.0;
-	var upper_l: vec2<f32> = (value_l - lower_l) / 16.0;
-	var alpha_l: vec2<f32> = (upper_l % 2.0 + lower_l % 2.0) / 2.0;
-
-	// Get samples for 0, +1/3, and +2/3
-	var tex_coord_r: vec2<f32> = in.tex_coord * intermediate_size;
-	var value_r: vec3<f32> = textureLoad(intermediate, vec2<i32>(tex_coord_r), 0).xyz * 255.0;
-	var lower_r: vec3<f32> = value_r % 16.0;
-	var upper_r: vec3<f32> = (value_r - lower_r) / 16.0;
-	var alpha_r: vec3<f32> = (upper_r % 2.0 + lower_r % 2.0) / 2.0;
-
-	// Average the energy over the pixels on either side
-	return vec4<f32>(1.0, 1.0, 1.0, (alpha_l.x + alpha_r.z + alpha_r.y) / 3.0);
-
-	// return rgba;
+	return shade(paint, in.position.xy, jitter_coverage(intermediate, jitter_pixel(in)) * unclipped(clip_mask, in.position.xy));
+}
+
+[[stage(fragment)]]
+fn fs_resolved(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+	return shade(paint, in.position.xy, resolved_coverage(intermediate, in.position.xy) * unclipped(clip_mask, in.position.xy));
+}
+
+[[stage(fragment)]]
+fn fs_blend(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+	return blended(in, jitter_coverage(intermediate, jitter_pixel(in)));
+}
+
+[[stage(fragment)]]
+fn fs_blend_resolved(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+	return blended(in, resolved_coverage(intermediate, in.position.xy));
+}
+
+// the clip passes write the next clip mask down the stack
+[[stage(fragment)]]
+fn fs_clip(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+	return vec4<f32>(1.0 - jitter_coverage(intermediate, jitter_pixel(in)) * unclipped(clip_mask, in.position.xy));
+}
+
+[[stage(fragment)]]
+fn fs_clip_resolved(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+	return vec4<f32>(1.0 - resolved_coverage(intermediate, in.position.xy) * unclipped(clip_mask, in.position.xy));
 }
@@ -0,0 +1,215 @@
+use std::{borrow::Cow, mem::size_of};
+use wgpu::{
+	util::{BufferInitDescriptor, DeviceExt},
+	BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType,
+	BufferSize, BufferUsages, ColorTargetState, ColorWrites, CommandBuffer, CompareFunction, DepthBiasState,
+	DepthStencilState, Device, Extent3d, FragmentState, MultisampleState, PipelineLayoutDescriptor, PrimitiveState,
+	RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPipeline, RenderPipelineDescriptor,
+	ShaderModuleDescriptor, ShaderSource, ShaderStages, StencilFaceState, StencilOperation, StencilState,
+	TextureDescriptor, TextureDimension, TextureUsages, TextureView, TextureViewDescriptor, VertexAttribute,
+	VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
+};
+
+use crate::{IntermediateBufs, PathBufs, JITTER_FORMAT, STENCIL_FORMAT, STENCIL_SAMPLE_COUNT};
+use nice_path_tessellator::FillRule;
+
+/// Computes coverage by winding a multisampled stencil buffer over the path's fan and curve triangles, then covering
+/// the view wherever the stencil says it's inside. The samples are resolved into the intermediate texture's red
+/// channel, which [`crate::RasterizePipeline`] reads when it's made for [`crate::RasterBackend::StencilCover`].
+pub struct StencilCoverPipeline {
+	pub bind_group_layout: BindGroupLayout,
+	/// flips the lowest bit of the stencil for every triangle over a sample
+	pub even_odd: RenderPipeline,
+	/// adds 1 for every triangle over a sample that's wound one way, and subtracts 1 for the other way
+	pub non_zero: RenderPipeline,
+	pub cover: RenderPipeline,
+	pub uniform: Buffer,
+	/// multisampled, and only used within a pass
+	pub coverage_tex: TextureView,
+	pub stencil_tex: TextureView,
+}
+impl StencilCoverPipeline {
+	pub fn new(device: &Device, view_size: [u32; 2]) -> Self {
+		let shader = device.create_shader_module(&ShaderModuleDescriptor {
+			label: None,
+			source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("stencil_cover.wgsl"))),
+		});
+
+		let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+			label: None,
+			entries: &[BindGroupLayoutEntry {
+				binding: 0,
+				visibility: ShaderStages::VERTEX,
+				ty: BindingType::Buffer {
+					ty: BufferBindingType::Uniform,
+					has_dynamic_offset: false,
+					min_binding_size: BufferSize::new(8),
+				},
+				count: None,
+			}],
+		});
+		let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+			label: None,
+			bind_group_layouts: &[&bind_group_layout],
+			push_constant_ranges: &[],
+		});
+
+		let vertex_buffers = [
+			VertexBufferLayout {
+				array_stride: size_of::<[f32; 2]>() as _,
+				step_mode: VertexStepMode::Vertex,
+				attributes: &[VertexAttribute { format: VertexFormat::Float32x2, offset: 0, shader_location: 0 }],
+			},
+			VertexBufferLayout {
+				array_stride: size_of::<[f32; 4]>() as _,
+				step_mode: VertexStepMode::Vertex,
+				attributes: &[VertexAttribute { format: VertexFormat::Float32x4, offset: 0, shader_location: 1 }],
+			},
+		];
+		let multisample = MultisampleState { count: STENCIL_SAMPLE_COUNT, ..Default::default() };
+		// WebGL 2 can't interpolate per sample, so curves are only tested at the centres of pixels there
+		let (vs_stencil, fs_stencil) = match cfg!(target_arch = "wasm32") {
+			true => ("vs_stencil", "fs_stencil"),
+			false => ("vs_stencil_sampled", "fs_stencil_sampled"),
+		};
+
+		let stencil_pipeline = |front: StencilOperation, back: StencilOperation, write_mask: u32| {
+			let face = |pass_op| StencilFaceState {
+				compare: CompareFunction::Always,
+				fail_op: StencilOperation::Keep,
+				depth_fail_op: StencilOperation::Keep,
+				pass_op,
+			};
+			device.create_render_pipeline(&RenderPipelineDescriptor {
+				label: None,
+				layout: Some(&pipeline_layout),
+				vertex: VertexState { module: &shader, entry_point: vs_stencil, buffers: &vertex_buffers },
+				fragment: Some(FragmentState {
+					module: &shader,
+					entry_point: fs_stencil,
+					targets: &[ColorTargetState {
+						format: JITTER_FORMAT,
+						blend: None,
+						write_mask: ColorWrites::empty(),
+					}],
+				}),
+				primitive: PrimitiveState { cull_mode: None, ..Default::default() },
+				depth_stencil: Some(DepthStencilState {
+					format: STENCIL_FORMAT,
+					depth_write_enabled: false,
+					depth_compare: CompareFunction::Always,
+					stencil: StencilState { front: face(front), back: face(back), read_mask: !0, write_mask },
+					bias: DepthBiasState::default(),
+				}),
+				multisample,
+				multiview: None,
+			})
+		};
+		let even_odd = stencil_pipeline(StencilOperation::Invert, StencilOperation::Invert, 1);
+		let non_zero = stencil_pipeline(StencilOperation::IncrementWrap, StencilOperation::DecrementWrap, !0);
+
+		let inside = StencilFaceState {
+			compare: CompareFunction::NotEqual,
+			fail_op: StencilOperation::Keep,
+			depth_fail_op: StencilOperation::Keep,
+			pass_op: StencilOperation::Keep,
+		};
+		let cover = device.create_render_pipeline(&RenderPipelineDescriptor {
+			label: None,
+			layout: Some(&pipeline_layout),
+			vertex: VertexState { module: &shader, entry_point: "vs_cover", buffers: &[] },
+			fragment: Some(FragmentState {
+				module: &shader,
+				entry_point: "fs_cover",
+				targets: &[ColorTargetState { format: JITTER_FORMAT, blend: None, write_mask: ColorWrites::ALL }],
+			}),
+			primitive: PrimitiveState { cull_mode: None, ..Default::default() },
+			depth_stencil: Some(DepthStencilState {
+				format: STENCIL_FORMAT,
+				depth_write_enabled: false,
+				depth_compare: CompareFunction::Always,
+				stencil: StencilState { front: inside, back: inside, read_mask: !0, write_mask: 0 },
+				bias: DepthBiasState::default(),
+			}),
+			multisample,
+			multiview: None,
+		});
+
+		let multisampled = |label, format| {
+			device
+				.create_texture(&TextureDescriptor {
+					label: Some(label),
+					size: Extent3d { width: view_size[0], height: view_size[1], depth_or_array_layers: 1 },
+					mip_level_count: 1,
+					sample_count: STENCIL_SAMPLE_COUNT,
+					dimension: TextureDimension::D2,
+					format,
+					usage: TextureUsages::RENDER_ATTACHMENT,
+				})
+				.create_view(&TextureViewDescriptor::default())
+		};
+		let coverage_tex = multisampled("Multisampled Coverage Texture", JITTER_FORMAT);
+		let stencil_tex = multisampled("Stencil Texture", STENCIL_FORMAT);
+
+		let view_size = [view_size[0] as f32, view_size[1] as f32];
+		let uniform = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Uniform Buffer"),
+			contents: bytemuck::cast_slice(&view_size),
+			usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+		});
+
+		Self { bind_group_layout, even_odd, non_zero, cover, uniform, coverage_tex, stencil_tex }
+	}
+
+	/// Resolves the coverage of `path_bufs` into `target`, filled with `fill_rule`.
+	pub fn draw(
+		&self,
+		device: &Device,
+		target: &IntermediateBufs,
+		path_bufs: &PathBufs,
+		fill_rule: FillRule,
+	) -> CommandBuffer {
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			layout: &self.bind_group_layout,
+			entries: &[wgpu::BindGroupEntry { binding: 0, resource: self.uniform.as_entire_binding() }],
+			label: None,
+		});
+
+		let mut stencil_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+		{
+			let mut rpass = stencil_cmds.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: None,
+				color_attachments: &[RenderPassColorAttachment {
+					view: &self.coverage_tex,
+					resolve_target: Some(target.intermediate_tex()),
+					ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), store: false },
+				}],
+				depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+					view: &self.stencil_tex,
+					depth_ops: None,
+					stencil_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(0), store: false }),
+				}),
+			});
+			rpass.push_debug_group("stencil");
+			rpass.set_pipeline(match fill_rule {
+				FillRule::EvenOdd => &self.even_odd,
+				FillRule::NonZero => &self.non_zero,
+			});
+			rpass.set_bind_group(0, &bind_group, &[]);
+			rpass.set_stencil_reference(0);
+			rpass.set_vertex_buffer(0, path_bufs.verts.slice(..));
+			rpass.set_vertex_buffer(1, path_bufs.curve_coords.slice(..));
+			rpass.set_index_buffer(path_bufs.fan_idxs.slice(..), wgpu::IndexFormat::Uint32);
+			rpass.draw_indexed(0..path_bufs.fan_idx_len as u32, 0, 0..1);
+			rpass.set_index_buffer(path_bufs.quadratic_idxs.slice(..), wgpu::IndexFormat::Uint32);
+			rpass.draw_indexed(0..path_bufs.quadratic_idx_len as u32, 0, 0..1);
+			rpass.pop_debug_group();
+
+			rpass.push_debug_group("cover");
+			rpass.set_pipeline(&self.cover);
+			rpass.draw(0..3, 0..1);
+			rpass.pop_debug_group();
+		}
+		stencil_cmds.finish()
+	}
+}
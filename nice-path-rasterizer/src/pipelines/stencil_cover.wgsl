@@ -0,0 +1,75 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+	// barycentric coordinates in the curve triangle, and the conic weight
+	[[location(0)]] curve: vec4<f32>;
+};
+
+// the same, but interpolated at each sample, so curves are as smooth as straight edges
+struct SampledOutput {
+    [[builtin(position)]] position: vec4<f32>;
+	[[location(0), interpolate(perspective, sample)]] curve: vec4<f32>;
+};
+
+struct Locals {
+    view_size: vec2<f32>;
+};
+[[group(0), binding(0)]]
+var<uniform> r_locals: Locals;
+
+[[stage(vertex)]]
+fn vs_stencil(
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] curve: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>((position / r_locals.view_size * 2.0 - 1.0) * vec2<f32>(1.0, -1.0), 0.0, 1.0);
+    out.curve = curve;
+    return out;
+}
+
+[[stage(vertex)]]
+fn vs_stencil_sampled(
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] curve: vec4<f32>,
+) -> SampledOutput {
+    var out: SampledOutput;
+    out.position = vec4<f32>((position / r_locals.view_size * 2.0 - 1.0) * vec2<f32>(1.0, -1.0), 0.0, 1.0);
+    out.curve = curve;
+    return out;
+}
+
+// conic curve, with the control point on the outside. Fan vertices have a weight of 0, so they're never outside.
+fn outside_curve(curve: vec4<f32>) -> bool {
+	var w: f32 = curve.w;
+	return curve.y * curve.y - 4.0 * w * w * curve.x * curve.z > 0.0;
+}
+
+// only the stencil is written
+[[stage(fragment)]]
+fn fs_stencil(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+	if (outside_curve(in.curve)) {
+		discard;
+	}
+	return vec4<f32>(0.0);
+}
+
+// runs for each sample, since its input is interpolated per sample
+[[stage(fragment)]]
+fn fs_stencil_sampled(in: SampledOutput) -> [[location(0)]] vec4<f32> {
+	if (outside_curve(in.curve)) {
+		discard;
+	}
+	return vec4<f32>(0.0);
+}
+
+// a triangle over the whole view
+[[stage(vertex)]]
+fn vs_cover([[builtin(vertex_index)]] vertex_index: u32) -> [[builtin(position)]] vec4<f32> {
+	var corner: vec2<f32> = vec2<f32>(f32(vertex_index % 2u), f32(vertex_index / 2u));
+	return vec4<f32>(corner * 4.0 - 1.0, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_cover() -> [[location(0)]] vec4<f32> {
+	return vec4<f32>(1.0);
+}
@@ -0,0 +1,213 @@
+use bytemuck::{Pod, Zeroable};
+use std::{borrow::Cow, mem::size_of};
+use wgpu::{
+	util::{BufferInitDescriptor, DeviceExt},
+	BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType,
+	BufferDescriptor, BufferSize, BufferUsages, Color, ColorTargetState, ColorWrites, CommandBuffer, ComputePipeline,
+	ComputePipelineDescriptor, Device, FragmentState, LoadOp, MultisampleState, PipelineLayoutDescriptor,
+	PrimitiveState, RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages,
+	TextureFormat, TextureView, VertexState,
+};
+
+use crate::{TileSceneBufs, JITTER, TILE_SIZE};
+
+/// The layout of `Config` in `tile.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct TileConfig {
+	view_size: [u32; 2],
+	tiles: [u32; 2],
+	background: [f32; 4],
+	jitter: [[f32; 4]; 6],
+	/// 0 for views that store colours exactly, 1 for 8 bit ones, and 2 for 8 bit sRGB ones
+	stored_as: u32,
+	padding: [u32; 3],
+}
+
+/// Draws a whole scene in one compute pass, instead of two render passes per path, which is faster for scenes with a
+/// lot of paths. Each workgroup takes a tile of [`crate::TILE_SIZE`] pixels, and each invocation works out the winding
+/// numbers at a pixel's jitter samples from the pieces binned into its tile, then paints and blends every draw in
+/// the tile in order. The results are copied onto the view by a render pass.
+///
+/// It's meant to look exactly the same as the jitter backend of [`crate::PathPipelines`], so it samples the same
+/// points and rounds colours between draws the way 8 bit views do. It needs compute shaders and storage buffers,
+/// which WebGL doesn't have, so it's behind the `compute` feature.
+pub struct TilePipeline {
+	pub bind_group_layout: BindGroupLayout,
+	pub pipeline: ComputePipeline,
+	pub composite_bind_group_layout: BindGroupLayout,
+	pub composite: RenderPipeline,
+	/// the blended colour of every pixel, row by row
+	pub colors: Buffer,
+	view_format: TextureFormat,
+	view_size: [u32; 2],
+}
+impl TilePipeline {
+	pub fn new(device: &Device, view_format: TextureFormat, view_size: [u32; 2]) -> Self {
+		let storage = |binding, visibility, read_only| BindGroupLayoutEntry {
+			binding,
+			visibility,
+			ty: BindingType::Buffer {
+				ty: BufferBindingType::Storage { read_only },
+				has_dynamic_offset: false,
+				min_binding_size: None,
+			},
+			count: None,
+		};
+		let config = |visibility| BindGroupLayoutEntry {
+			binding: 0,
+			visibility,
+			ty: BindingType::Buffer {
+				ty: BufferBindingType::Uniform,
+				has_dynamic_offset: false,
+				min_binding_size: BufferSize::new(size_of::<TileConfig>() as _),
+			},
+			count: None,
+		};
+
+		let shader = device.create_shader_module(&ShaderModuleDescriptor {
+			label: None,
+			source: ShaderSource::Wgsl(Cow::Borrowed(concat!(include_str!("common.wgsl"), include_str!("tile.wgsl")))),
+		});
+		let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+			label: None,
+			entries: &[
+				config(ShaderStages::COMPUTE),
+				storage(1, ShaderStages::COMPUTE, true),
+				storage(2, ShaderStages::COMPUTE, true),
+				storage(3, ShaderStages::COMPUTE, true),
+				storage(4, ShaderStages::COMPUTE, false),
+			],
+		});
+		let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+			label: None,
+			bind_group_layouts: &[&bind_group_layout],
+			push_constant_ranges: &[],
+		});
+		let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+			label: None,
+			layout: Some(&pipeline_layout),
+			module: &shader,
+			entry_point: "cs_main",
+		});
+
+		let composite_shader = device.create_shader_module(&ShaderModuleDescriptor {
+			label: None,
+			source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("tile_composite.wgsl"))),
+		});
+		let composite_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+			label: None,
+			entries: &[config(ShaderStages::FRAGMENT), storage(1, ShaderStages::FRAGMENT, true)],
+		});
+		let composite_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+			label: None,
+			bind_group_layouts: &[&composite_bind_group_layout],
+			push_constant_ranges: &[],
+		});
+		let composite = device.create_render_pipeline(&RenderPipelineDescriptor {
+			label: None,
+			layout: Some(&composite_layout),
+			vertex: VertexState { module: &composite_shader, entry_point: "vs_main", buffers: &[] },
+			fragment: Some(FragmentState {
+				module: &composite_shader,
+				entry_point: "fs_main",
+				targets: &[ColorTargetState { format: view_format, blend: None, write_mask: ColorWrites::ALL }],
+			}),
+			primitive: PrimitiveState::default(),
+			depth_stencil: None,
+			multisample: MultisampleState::default(),
+			multiview: None,
+		});
+
+		let colors = device.create_buffer(&BufferDescriptor {
+			label: Some("Tile Color Buffer"),
+			size: (view_size[0] * view_size[1]).max(1) as u64 * size_of::<[f32; 4]>() as u64,
+			usage: BufferUsages::STORAGE,
+			mapped_at_creation: false,
+		});
+
+		Self { bind_group_layout, pipeline, composite_bind_group_layout, composite, colors, view_format, view_size }
+	}
+
+	/// Draws every path in `scene` onto `view` over `background`. `scene` has to be binned for this pipeline's view
+	/// size.
+	pub fn draw_scene(
+		&self,
+		device: &Device,
+		view: &TextureView,
+		scene: &TileSceneBufs,
+		background: Color,
+	) -> CommandBuffer {
+		let tiles = self.view_size.map(|size| size.div_ceil(TILE_SIZE));
+		assert_eq!(scene.tiles, tiles, "the scene is binned for a different view size");
+
+		let mut jitter = [[0.0; 4]; 6];
+		for (jitter, offset) in jitter.iter_mut().zip(JITTER) {
+			*jitter = [offset[0], offset[1], 0.0, 0.0];
+		}
+		let config = TileConfig {
+			view_size: self.view_size,
+			tiles,
+			background: [background.r, background.g, background.b, background.a].map(|c| c as f32),
+			jitter,
+			stored_as: match self.view_format {
+				TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm => 1,
+				TextureFormat::Rgba8UnormSrgb | TextureFormat::Bgra8UnormSrgb => 2,
+				_ => 0,
+			},
+			padding: [0; 3],
+		};
+		let config = device.create_buffer_init(&BufferInitDescriptor {
+			label: Some("Tile Config Buffer"),
+			contents: bytemuck::bytes_of(&config),
+			usage: BufferUsages::UNIFORM,
+		});
+
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			layout: &self.bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry { binding: 0, resource: config.as_entire_binding() },
+				wgpu::BindGroupEntry { binding: 1, resource: scene.pieces.as_entire_binding() },
+				wgpu::BindGroupEntry { binding: 2, resource: scene.bins.as_entire_binding() },
+				wgpu::BindGroupEntry { binding: 3, resource: scene.paints.as_entire_binding() },
+				wgpu::BindGroupEntry { binding: 4, resource: self.colors.as_entire_binding() },
+			],
+			label: None,
+		});
+		let composite_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			layout: &self.composite_bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry { binding: 0, resource: config.as_entire_binding() },
+				wgpu::BindGroupEntry { binding: 1, resource: self.colors.as_entire_binding() },
+			],
+			label: None,
+		});
+
+		let mut tile_cmds = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+		{
+			let mut cpass = tile_cmds.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None });
+			cpass.push_debug_group("tile");
+			cpass.set_pipeline(&self.pipeline);
+			cpass.set_bind_group(0, &bind_group, &[]);
+			cpass.dispatch(tiles[0], tiles[1], 1);
+			cpass.pop_debug_group();
+		}
+		{
+			let mut rpass = tile_cmds.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: None,
+				color_attachments: &[wgpu::RenderPassColorAttachment {
+					view,
+					resolve_target: None,
+					ops: wgpu::Operations { load: LoadOp::Clear(background), store: true },
+				}],
+				depth_stencil_attachment: None,
+			});
+			rpass.push_debug_group("composite");
+			rpass.set_pipeline(&self.composite);
+			rpass.set_bind_group(0, &composite_bind_group, &[]);
+			rpass.draw(0..3, 0..1);
+			rpass.pop_debug_group();
+		}
+		tile_cmds.finish()
+	}
+}
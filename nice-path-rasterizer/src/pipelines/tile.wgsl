@@ -0,0 +1,219 @@
+// see TileConfig in tile.rs
+struct Config {
+	view_size: vec2<u32>;
+	tiles: vec2<u32>;
+	background: vec4<f32>;
+	// the points sampled around each pixel, in the order the jitter pass draws them
+	jitter: array<vec4<f32>, 6>;
+	// how the view stores colours, which blending rounds to after every draw (0: exactly, 1: 8 bits, 2: 8 bit sRGB)
+	stored_as: u32;
+};
+[[group(0), binding(0)]]
+var<uniform> config: Config;
+
+// see TilePiece in tile_scene_bufs.rs
+struct Piece {
+	from: vec2<f32>;
+	ctrl: vec2<f32>;
+	to: vec2<f32>;
+	line: u32;
+	weight: f32;
+};
+struct Pieces {
+	pieces: array<Piece>;
+};
+[[group(0), binding(1)]]
+var<storage, read> pieces: Pieces;
+
+// see TileSceneBufs in tile_scene_bufs.rs
+struct Bins {
+	data: array<u32>;
+};
+[[group(0), binding(2)]]
+var<storage, read> bins: Bins;
+
+struct Paints {
+	paints: array<Paint>;
+};
+[[group(0), binding(3)]]
+var<storage, read> paints: Paints;
+
+struct Colors {
+	colors: array<vec4<f32>>;
+};
+[[group(0), binding(4)]]
+var<storage, read_write> colors: Colors;
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+	var low: vec3<f32> = c * 12.92;
+	var high: vec3<f32> = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055;
+	return select(high, low, c <= vec3<f32>(0.0031308));
+}
+
+// what the view keeps of a colour between draws
+fn store(color: vec4<f32>) -> vec4<f32> {
+	var c: vec4<f32> = clamp(color, vec4<f32>(0.0), vec4<f32>(1.0));
+	if (config.stored_as == 1u) {
+		return round(c * 255.0) / 255.0;
+	}
+	if (config.stored_as == 2u) {
+		var rgb: vec3<f32> = srgb_to_linear(round(linear_to_srgb(c.rgb) * 255.0) / 255.0);
+		return vec4<f32>(rgb, round(c.a * 255.0) / 255.0);
+	}
+	return c;
+}
+
+// where a piece that's monotonic in y crosses the height y, which has to be within it. That's where the curve's height
+// above y times the conic's denominator is zero, and quadratics are conics with a weight of 1.
+fn x_at(piece: Piece, y: f32) -> f32 {
+	if (piece.line == 1u) {
+		return mix(piece.from.x, piece.to.x, (y - piece.from.y) / (piece.to.y - piece.from.y));
+	}
+	var w: f32 = piece.weight;
+	var a: f32 = piece.from.y - 2.0 * w * piece.ctrl.y + piece.to.y + 2.0 * (w - 1.0) * y;
+	var b: f32 = 2.0 * (w * piece.ctrl.y - piece.from.y) - 2.0 * (w - 1.0) * y;
+	var c: f32 = piece.from.y - y;
+	var t: f32;
+	if (abs(a) <= 1e-6 * (abs(b) + abs(c))) {
+		t = -c / b;
+	} else {
+		// the numerically stable roots, of which the one in the piece is wanted
+		var q: f32 = -0.5 * (b + select(-1.0, 1.0, b >= 0.0) * sqrt(max(b * b - 4.0 * a * c, 0.0)));
+		var t0: f32 = q / a;
+		var t1: f32 = select(c / q, 0.0, q == 0.0);
+		t = select(t1, t0, abs(t0 - 0.5) < abs(t1 - 0.5));
+	}
+	t = clamp(t, 0.0, 1.0);
+	var s: f32 = 1.0 - t;
+	var b1: f32 = 2.0 * w * s * t;
+	return (s * s * piece.from.x + b1 * piece.ctrl.x + t * t * piece.to.x) / (s * s + b1 + t * t);
+}
+
+// How a piece changes the winding number at a point in its tile, counting crossings of a ray towards -x. Clamping the
+// path onto the tile's edges doesn't change any winding inside the tile, and then everything left of it is a line
+// along the left edge. A piece crossing the left edge adds that line from the crossing up to the top edge, pieces
+// crossing it below the tile add all of it, which is the backdrop, and pieces crossing above it add nothing.
+// Points right on a piece count as right of it, like the top-left rule GPUs rasterize triangles with.
+fn winding(piece: Piece, crosses_left: bool, point: vec2<f32>) -> i32 {
+	var down: i32 = i32(sign(piece.to.y - piece.from.y));
+	var y_min: f32 = min(piece.from.y, piece.to.y);
+	var y_max: f32 = max(piece.from.y, piece.to.y);
+	var within: bool = point.y >= y_min && point.y < y_max;
+
+	var w: i32 = 0;
+	if (within && x_at(piece, point.y) <= point.x) {
+		w = down;
+	}
+	if (crosses_left) {
+		var right: i32 = i32(sign(piece.to.x - piece.from.x));
+		if (within) {
+			w = w + (right - down) / 2;
+		} else if (point.y < y_min) {
+			w = w + right;
+		}
+	}
+	return w;
+}
+
+[[stage(compute), workgroup_size(16, 16)]]
+fn cs_main(
+	[[builtin(workgroup_id)]] tile_id: vec3<u32>,
+	[[builtin(global_invocation_id)]] pixel: vec3<u32>,
+) {
+	if (pixel.x >= config.view_size.x || pixel.y >= config.view_size.y) {
+		return;
+	}
+
+	// the first two samples are in the pixel to the left, like the rasterize pass reads them
+	var samples: array<vec2<f32>, 6>;
+	var i: u32 = 0u;
+	loop {
+		if (i >= 6u) {
+			break;
+		}
+		var column: u32 = pixel.x;
+		if (i < 2u) {
+			column = max(pixel.x, 1u) - 1u;
+		}
+		samples[i] = vec2<f32>(f32(column), f32(pixel.y)) + 0.5 - config.jitter[i].xy;
+		i = i + 1u;
+	}
+
+	var color: vec4<f32> = store(config.background);
+	var tile: u32 = tile_id.y * config.tiles.x + tile_id.x;
+	var first_draw: u32 = bins.data[tile * 2u];
+	var end_draw: u32 = first_draw + bins.data[tile * 2u + 1u] * 4u;
+	var draw: u32 = first_draw;
+	loop {
+		if (draw >= end_draw) {
+			break;
+		}
+		// the index of the paint with the fill rule in the top bit, the backdrop, and the pieces in the tile
+		var paint_index: u32 = bins.data[draw];
+		var backdrop: i32 = bitcast<i32>(bins.data[draw + 1u]);
+		var first_piece: u32 = bins.data[draw + 2u];
+		var end_piece: u32 = first_piece + bins.data[draw + 3u];
+		draw = draw + 4u;
+
+		var windings: array<i32, 6> = array<i32, 6>(backdrop, backdrop, backdrop, backdrop, backdrop, backdrop);
+		var p: u32 = first_piece;
+		loop {
+			if (p >= end_piece) {
+				break;
+			}
+			var piece_ref: u32 = bins.data[p];
+			var piece: Piece = pieces.pieces[piece_ref & 0x7fffffffu];
+			var crosses_left: bool = piece_ref >= 0x80000000u;
+			i = 0u;
+			loop {
+				if (i >= 6u) {
+					break;
+				}
+				windings[i] = windings[i] + winding(piece, crosses_left, samples[i]);
+				i = i + 1u;
+			}
+			p = p + 1u;
+		}
+
+		var even_odd: bool = paint_index >= 0x80000000u;
+		var covered: u32 = 0u;
+		i = 0u;
+		loop {
+			if (i >= 6u) {
+				break;
+			}
+			if ((even_odd && (windings[i] & 1) != 0) || (!even_odd && windings[i] != 0)) {
+				covered = covered + 1u;
+			}
+			i = i + 1u;
+		}
+		if (covered == 0u) {
+			continue;
+		}
+
+		var paint: Paint = paints.paints[paint_index & 0xffffffu];
+		var mode: u32 = (paint_index >> 24u) & 0x7fu;
+		var coverage: f32 = f32(covered) / 6.0;
+		if (mode != BLEND_SRC_OVER) {
+			// faded between the blended colour and what was there, like the rasterize pass
+			var straight: vec4<f32> = shade(paint, vec2<f32>(pixel.xy) + 0.5, 1.0);
+			var src: vec4<f32> = vec4<f32>(straight.rgb * straight.a, straight.a);
+			if (mode == BLEND_PLUS) {
+				// plus adds the faded paint, like fixed-function blending
+				color = store(blend_colors(mode, src * coverage, color));
+			} else {
+				color = store(mix(color, blend_colors(mode, src, color), coverage));
+			}
+			continue;
+		}
+
+		// blended like BlendState::ALPHA_BLENDING
+		var src: vec4<f32> = shade(paint, vec2<f32>(pixel.xy) + 0.5, coverage);
+		// 8 bit views blend in 8 bits
+		if (config.stored_as == 1u) {
+			src = round(clamp(src, vec4<f32>(0.0), vec4<f32>(1.0)) * 255.0) / 255.0;
+		}
+		color = store(vec4<f32>(src.rgb * src.a + color.rgb * (1.0 - src.a), src.a + color.a * (1.0 - src.a)));
+	}
+	colors.colors[pixel.y * config.view_size.x + pixel.x] = color;
+}
@@ -0,0 +1,25 @@
+struct Config {
+	view_size: vec2<u32>;
+};
+[[group(0), binding(0)]]
+var<uniform> config: Config;
+
+struct Colors {
+	colors: array<vec4<f32>>;
+};
+[[group(0), binding(1)]]
+var<storage, read> colors: Colors;
+
+// a triangle over the whole view
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> [[builtin(position)]] vec4<f32> {
+	var corner: vec2<f32> = vec2<f32>(f32(vertex_index % 2u), f32(vertex_index / 2u));
+	return vec4<f32>(corner * 4.0 - 1.0, 0.0, 1.0);
+}
+
+// the tile pass has blended every draw already, so its colours are copied as they are
+[[stage(fragment)]]
+fn fs_main([[builtin(position)]] position: vec4<f32>) -> [[location(0)]] vec4<f32> {
+	var pixel: vec2<u32> = vec2<u32>(position.xy);
+	return colors.colors[pixel.y * config.view_size.x + pixel.x];
+}
@@ -1,14 +1,20 @@
 use std::num::NonZeroU32;
 
+use nice_path_tessellator::FillRule;
 use wgpu::{
-	Device, Extent3d, TextureAspect, TextureDescriptor, TextureDimension, TextureUsages, TextureView,
+	CommandBuffer, Device, Extent3d, TextureAspect, TextureDescriptor, TextureDimension, TextureUsages, TextureView,
 	TextureViewDescriptor, TextureViewDimension,
 };
 
-use crate::JITTER_FORMAT;
+use crate::{PathBufs, PathPipelines, CLIP_FORMAT, JITTER_FORMAT};
 
 pub struct IntermediateBufs {
 	intermediate_tex: TextureView,
+	/// How much of each pixel every level of the clip stack clips away, starting with a level that clips nothing. They
+	/// hold what's clipped away rather than what's left so that new ones, which are cleared to zero, clip nothing.
+	clip_masks: Vec<TextureView>,
+	clip_depth: usize,
+	size: [u32; 2],
 }
 impl IntermediateBufs {
 	pub fn new(device: &Device, width: u32, height: u32) -> Self {
@@ -32,10 +38,63 @@ impl IntermediateBufs {
 			array_layer_count: NonZeroU32::new(1),
 		});
 
-		IntermediateBufs { intermediate_tex }
+		let clip_masks = vec![clip_mask(device, [width, height])];
+
+		IntermediateBufs { intermediate_tex, clip_masks, clip_depth: 0, size: [width, height] }
 	}
 
 	pub fn intermediate_tex(&self) -> &TextureView {
 		&self.intermediate_tex
 	}
+
+	/// The top of the clip stack, which [`crate::RasterizePipeline::draw_paint`] multiplies coverage by.
+	pub fn clip_mask(&self) -> &TextureView {
+		&self.clip_masks[self.clip_depth]
+	}
+
+	/// How many clips are pushed.
+	pub fn clip_depth(&self) -> usize {
+		self.clip_depth
+	}
+
+	/// Clips everything drawn into this target to `path_bufs` as well as the clips already pushed, until
+	/// [`IntermediateBufs::pop_clip`]. The clip's coverage comes from `pipelines`' coverage passes, so its edges are
+	/// anti-aliased like any path's. Overwrites the coverage of the last path drawn.
+	pub fn push_clip(
+		&mut self,
+		device: &Device,
+		pipelines: &PathPipelines,
+		path_bufs: &PathBufs,
+		fill_rule: FillRule,
+	) -> Vec<CommandBuffer> {
+		if self.clip_masks.len() == self.clip_depth + 1 {
+			self.clip_masks.push(clip_mask(device, self.size));
+		}
+		let (parent, clip) = (&self.clip_masks[self.clip_depth], &self.clip_masks[self.clip_depth + 1]);
+		let cmds = vec![
+			pipelines.draw_coverage(device, self, path_bufs, fill_rule),
+			pipelines.rasterize.draw_clip(device, self, parent, clip),
+		];
+		self.clip_depth += 1;
+		cmds
+	}
+
+	/// Undoes the last [`IntermediateBufs::push_clip`]. Panics if there aren't any clips.
+	pub fn pop_clip(&mut self) {
+		assert!(self.clip_depth > 0, "there's no clip to pop");
+		self.clip_depth -= 1;
+	}
+}
+
+fn clip_mask(device: &Device, [width, height]: [u32; 2]) -> TextureView {
+	let texture = device.create_texture(&TextureDescriptor {
+		label: Some("Clip Mask Texture"),
+		size: Extent3d { width, height, depth_or_array_layers: 1 },
+		mip_level_count: 1,
+		sample_count: 1,
+		dimension: TextureDimension::D2,
+		format: CLIP_FORMAT,
+		usage: TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT,
+	});
+	texture.create_view(&TextureViewDescriptor::default())
 }
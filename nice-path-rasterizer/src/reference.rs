@@ -0,0 +1,208 @@
+use nice_path_tessellator::{FillRule, Path};
+
+use crate::JITTER;
+
+/// How far [`CoverageSampling::Exact`] lets flattened curves stray from the exact ones, in pixels.
+pub const REFERENCE_TOLERANCE: f32 = 0.005;
+
+/// How a [`CoverageMask`] decides how much of each pixel is covered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoverageSampling {
+	/// The exact area of the pixel that's inside the path. Curves are flattened to within [`REFERENCE_TOLERANCE`], but
+	/// nothing else is approximated, so this is the ground truth that anti-aliasing aims for.
+	Exact,
+	/// What the jitter and rasterize passes compute: the fill rule at the same 6 sample points, two of which are in the
+	/// pixel to the left, averaged the same way. The GPU should match this up to rounding.
+	Jitter,
+	/// What the stencil and cover passes compute: the fill rule at the 4 sample points of a 4x multisampled pixel,
+	/// which WebGPU fixes, averaged. The GPU should match this up to rounding.
+	Multisample,
+}
+
+/// Where the samples of a 4x multisampled pixel are, from its top left corner.
+const MULTISAMPLE_POSITIONS: [[f32; 2]; 4] = [[0.375, 0.125], [0.875, 0.375], [0.125, 0.625], [0.625, 0.875]];
+
+/// How much of each pixel a path covers, between 0 and 1, computed on the CPU as a reference for the GPU passes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverageMask {
+	pub width: u32,
+	pub height: u32,
+	/// Row by row from the top.
+	pub data: Vec<f32>,
+}
+impl CoverageMask {
+	/// Rasterizes `path`, which is in pixels, into a `width` by `height` mask.
+	pub fn rasterize(path: &Path, fill_rule: FillRule, width: u32, height: u32, sampling: CoverageSampling) -> Self {
+		let data = match sampling {
+			CoverageSampling::Exact => exact_coverage(path, fill_rule, width, height),
+			CoverageSampling::Jitter => jitter_coverage(path, fill_rule, width, height),
+			CoverageSampling::Multisample => multisample_coverage(path, fill_rule, width, height),
+		};
+		Self { width, height, data }
+	}
+
+	pub fn get(&self, x: u32, y: u32) -> f32 {
+		self.data[(y * self.width + x) as usize]
+	}
+}
+
+/// Where the jitter pass samples a path for the pixel at `x`, `y`, in the order it draws them. The rasterize pass reads
+/// the first two samples from the pixel to the left, or the pixel itself in the first column, since its texel
+/// coordinate rounds towards zero.
+pub fn jitter_samples(x: u32, y: u32) -> [[f32; 2]; 6] {
+	let mut samples = [[0.0; 2]; 6];
+	for (i, (sample, jitter)) in samples.iter_mut().zip(JITTER).enumerate() {
+		let column = match i < 2 {
+			true => x.saturating_sub(1),
+			false => x,
+		};
+		*sample = [column as f32 + 0.5 - jitter[0], y as f32 + 0.5 - jitter[1]];
+	}
+	samples
+}
+
+fn jitter_coverage(path: &Path, fill_rule: FillRule, width: u32, height: u32) -> Vec<f32> {
+	let index = path.hit_test_index();
+	let mut data = Vec::with_capacity((width * height) as usize);
+	for y in 0..height {
+		for x in 0..width {
+			let covered = jitter_samples(x, y).into_iter().filter(|&sample| index.contains(sample, fill_rule)).count();
+			data.push(covered as f32 / 6.0);
+		}
+	}
+	data
+}
+
+fn multisample_coverage(path: &Path, fill_rule: FillRule, width: u32, height: u32) -> Vec<f32> {
+	let index = path.hit_test_index();
+	let mut data = Vec::with_capacity((width * height) as usize);
+	for y in 0..height {
+		for x in 0..width {
+			let covered = MULTISAMPLE_POSITIONS
+				.iter()
+				.filter(|position| index.contains([x as f32 + position[0], y as f32 + position[1]], fill_rule))
+				.count();
+			data.push(covered as f32 / 4.0);
+		}
+	}
+	data
+}
+
+/// An edge of the flattened path, going down.
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+	top: [f64; 2],
+	bottom: [f64; 2],
+	/// +1 if the path goes down along it, -1 if up
+	winding: i32,
+}
+impl Edge {
+	fn x_at(&self, y: f64) -> f64 {
+		let t = (y - self.top[1]) / (self.bottom[1] - self.top[1]);
+		self.top[0] + (self.bottom[0] - self.top[0]) * t
+	}
+
+	/// Where the edges cross, if they do between `min_y` and `max_y`.
+	fn intersect_y(&self, other: &Edge, min_y: f64, max_y: f64) -> Option<f64> {
+		let min_y = min_y.max(self.top[1]).max(other.top[1]);
+		let max_y = max_y.min(self.bottom[1]).min(other.bottom[1]);
+		if min_y >= max_y {
+			return None;
+		}
+		// x difference is linear in y, so the edges cross where it changes sign
+		let d0 = self.x_at(min_y) - other.x_at(min_y);
+		let d1 = self.x_at(max_y) - other.x_at(max_y);
+		if (d0 < 0.0) == (d1 < 0.0) || d0 == d1 {
+			return None;
+		}
+		Some(min_y + (max_y - min_y) * d0 / (d0 - d1))
+	}
+}
+
+/// Splits every row into strips where no edges cross or end, so inside each strip the edges are in the same order
+/// from left to right all the way down. Then the area between two edges is a trapezoid, which is clipped to each pixel
+/// exactly.
+fn exact_coverage(path: &Path, fill_rule: FillRule, width: u32, height: u32) -> Vec<f32> {
+	let mut rows: Vec<Vec<Edge>> = vec![vec![]; height as usize];
+	for polyline in path.flatten(REFERENCE_TOLERANCE) {
+		for (i, &from) in polyline.iter().enumerate() {
+			let to = polyline[(i + 1) % polyline.len()];
+			let [from, to] = [from, to].map(|p| p.map(|c| c as f64));
+			if from[1] == to[1] {
+				continue;
+			}
+			let edge = match from[1] < to[1] {
+				true => Edge { top: from, bottom: to, winding: 1 },
+				false => Edge { top: to, bottom: from, winding: -1 },
+			};
+			let first_row = edge.top[1].floor().max(0.0) as usize;
+			let end_row = (edge.bottom[1].ceil().max(0.0) as usize).min(height as usize);
+			for row in &mut rows[first_row.min(end_row)..end_row] {
+				row.push(edge);
+			}
+		}
+	}
+
+	let mut data = vec![0.0; (width * height) as usize];
+	for (y, edges) in rows.iter().enumerate() {
+		let (row_top, row_bottom) = (y as f64, y as f64 + 1.0);
+		let mut splits = vec![row_top, row_bottom];
+		for (i, edge) in edges.iter().enumerate() {
+			splits.extend([edge.top[1], edge.bottom[1]].into_iter().filter(|&y| y > row_top && y < row_bottom));
+			splits.extend(edges[i + 1..].iter().filter_map(|other| edge.intersect_y(other, row_top, row_bottom)));
+		}
+		splits.sort_by(f64::total_cmp);
+		splits.dedup();
+
+		let row = &mut data[y * width as usize..(y + 1) * width as usize];
+		let mut crossings = vec![];
+		for strip in splits.windows(2) {
+			let (top, bottom) = (strip[0], strip[1]);
+			let middle = (top + bottom) / 2.0;
+			crossings.clear();
+			crossings.extend(
+				edges
+					.iter()
+					.filter(|edge| edge.top[1] <= top && edge.bottom[1] >= bottom)
+					.map(|edge| ([edge.x_at(top), edge.x_at(bottom)], edge.x_at(middle), edge.winding)),
+			);
+			crossings.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+			let mut winding = 0;
+			for pair in crossings.windows(2) {
+				winding += pair[0].2;
+				if fill_rule.is_inside(winding) {
+					add_trapezoid(row, pair[0].0, pair[1].0, bottom - top);
+				}
+			}
+		}
+	}
+	data
+}
+
+/// Adds the area between the left and right edges, which go from `[x at the top, x at the bottom]` over `height`, to
+/// the pixels of `row`.
+fn add_trapezoid(row: &mut [f32], left: [f64; 2], right: [f64; 2], height: f64) {
+	let first = left[0].min(left[1]).floor().max(0.0) as usize;
+	let end = (right[0].max(right[1]).ceil().max(0.0) as usize).min(row.len());
+	for (column, coverage) in row.iter_mut().enumerate().take(end).skip(first) {
+		let column = column as f64;
+		let area = column_area(right, column) - column_area(left, column);
+		*coverage += (area * height) as f32;
+	}
+}
+
+/// The average width of the part of the column starting at `column` that's left of the edge.
+fn column_area(edge: [f64; 2], column: f64) -> f64 {
+	// the integral of clamp(x, 0, 1)
+	let integral = |x: f64| match x {
+		x if x <= 0.0 => 0.0,
+		x if x <= 1.0 => x * x / 2.0,
+		x => x - 0.5,
+	};
+	let [a, b] = edge.map(|x| x - column);
+	match (b - a).abs() < 1e-9 {
+		true => ((a + b) / 2.0).clamp(0.0, 1.0),
+		false => (integral(b) - integral(a)) / (b - a),
+	}
+}
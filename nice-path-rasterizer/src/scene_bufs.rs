@@ -0,0 +1,47 @@
+use nice_path_tessellator::{BlendMode, FillRule, Scene, Transform};
+use wgpu::{Device, TextureFormat};
+
+use crate::{PaintBuf, PathBufs, RasterBackend};
+
+/// The buffers for every path in a [`Scene`], for [`crate::PathPipelines::draw_scene`].
+pub struct SceneBufs {
+	/// In drawing order, with the rule they're filled with and how they're blended. Paths that cover nothing are left
+	/// out.
+	pub draws: Vec<(PathBufs, FillRule, PaintBuf, BlendMode)>,
+}
+impl SceneBufs {
+	/// `to_view` maps the scene to the pixels of a view in `view_format`. The paths are made for the default backend.
+	pub fn new(device: &Device, scene: &Scene, to_view: &Transform, view_format: TextureFormat) -> Self {
+		Self::with_backend(device, scene, to_view, view_format, RasterBackend::default())
+	}
+
+	/// The paths are made for `backend`. The jitter backend only fills with the even-odd rule, so nonzero paths have
+	/// their overlaps resolved on the CPU with [`nice_path_tessellator::Draw::outline`] first, which any backend can
+	/// draw. The stencil backend winds them as they are.
+	pub fn with_backend(
+		device: &Device,
+		scene: &Scene,
+		to_view: &Transform,
+		view_format: TextureFormat,
+		backend: RasterBackend,
+	) -> Self {
+		let draws = scene
+			.draws
+			.iter()
+			.filter_map(|draw| {
+				let (path, fill_rule) = match backend {
+					RasterBackend::Jitter => (draw.outline(to_view), FillRule::EvenOdd),
+					RasterBackend::StencilCover => (draw.path.transform(&draw.transform.then(to_view)), draw.fill_rule),
+				};
+				let path_bufs = PathBufs::new(device, &path);
+				if path_bufs.fill_idx_len == 0 && path_bufs.quadratic_idx_len == 0 {
+					return None;
+				}
+				let to_view = draw.transform.then(to_view);
+				let paint = PaintBuf::new(device, &draw.paint, draw.opacity, &to_view, view_format);
+				Some((path_bufs, fill_rule, paint, draw.blend_mode))
+			})
+			.collect();
+		Self { draws }
+	}
+}
@@ -0,0 +1,287 @@
+use bytemuck::{Pod, Zeroable};
+use nice_path_tessellator::{FillRule, Scene, Segment, Transform};
+use wgpu::{
+	util::{BufferInitDescriptor, DeviceExt},
+	Buffer, BufferUsages, Device, TextureFormat,
+};
+
+use crate::paint_buf::PaintUniform;
+
+/// Tiles are this many pixels on a side, which is also the size of the tile pass's workgroups.
+pub const TILE_SIZE: u32 = 16;
+
+/// Set on a piece's index in a bin when it crosses the tile's left edge, and on a paint's index when it's filled with
+/// the even-odd rule.
+const FLAG: u32 = 1 << 31;
+/// Where a draw's blend mode is kept above its paint's index.
+const BLEND_MODE_SHIFT: u32 = 24;
+
+/// The layout of `Piece` in `tile.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub(crate) struct TilePiece {
+	from: [f32; 2],
+	/// the same as `from` for lines
+	ctrl: [f32; 2],
+	to: [f32; 2],
+	line: u32,
+	/// 1 for quadratics
+	weight: f32,
+}
+
+/// Every path in a [`Scene`] cut into pieces and binned into tiles, for [`crate::TilePipeline::draw_scene`].
+///
+/// The tile pass counts winding numbers at the same points the jitter pass samples, which reach from 7/12 of a pixel
+/// left of a pixel to 1/4 of a pixel right of it. So that they're all inside their tile, the geometry binned into a
+/// column of tiles is shifted 5/8 of a pixel left of its pixels, which also keeps samples off the tiles' edges.
+pub struct TileSceneBufs {
+	/// The [`nice_path_tessellator::Path::monotonic_pieces`] of every path, in pixels.
+	pub pieces: Buffer,
+	/// `u32`s, starting with the offset of the first draw in each tile, row by row, and how many draws there are. A draw
+	/// is the index of its paint with its [`nice_path_tessellator::BlendMode`] in bits 24 to 30, flagged for the
+	/// even-odd rule, the winding number at the tile's bottom left corner, and the offset and count of the indices of
+	/// its pieces in the tile. Piece indices are flagged when they cross the tile's left edge.
+	pub bins: Buffer,
+	/// The paints, in the layout of `Paint` in `common.wgsl`.
+	pub paints: Buffer,
+	/// Columns and rows.
+	pub tiles: [u32; 2],
+}
+impl TileSceneBufs {
+	/// `to_view` maps the scene to the pixels of a view in `view_format`, which is `view_size` pixels. Unlike
+	/// [`crate::SceneBufs`], nonzero paths don't have to be resolved first, since the tiles count winding numbers.
+	pub fn new(
+		device: &Device,
+		scene: &Scene,
+		to_view: &Transform,
+		view_format: TextureFormat,
+		view_size: [u32; 2],
+	) -> Self {
+		let TileBins { pieces, bins, draws, tiles } = TileBins::new(scene, to_view, view_size);
+		let mut pieces: Vec<TilePiece> = pieces
+			.iter()
+			.map(|piece| match *piece {
+				Segment::Line(line) => {
+					TilePiece { from: line.from, ctrl: line.from, to: line.to, line: 1, weight: 1.0 }
+				},
+				Segment::Quadratic(quad) => {
+					TilePiece { from: quad.from, ctrl: quad.ctrl, to: quad.to, line: 0, weight: 1.0 }
+				},
+				Segment::Conic(conic) => {
+					TilePiece { from: conic.from, ctrl: conic.ctrl, to: conic.to, line: 0, weight: conic.weight }
+				},
+			})
+			.collect();
+		let mut paints: Vec<PaintUniform> = draws
+			.iter()
+			.map(|&i| {
+				let draw = &scene.draws[i];
+				PaintUniform::new(&draw.paint, draw.opacity, &draw.transform.then(to_view), view_format)
+			})
+			.collect();
+
+		// storage buffers can't be empty
+		if pieces.is_empty() {
+			pieces.push(TilePiece::zeroed());
+		}
+		if paints.is_empty() {
+			paints.push(PaintUniform::zeroed());
+		}
+		let storage = |label, contents: &[u8]| {
+			device.create_buffer_init(&BufferInitDescriptor {
+				label: Some(label),
+				contents,
+				usage: BufferUsages::STORAGE,
+			})
+		};
+		Self {
+			pieces: storage("Tile Piece Buffer", bytemuck::cast_slice(&pieces)),
+			bins: storage("Tile Bin Buffer", bytemuck::cast_slice(&bins)),
+			paints: storage("Tile Paint Buffer", bytemuck::cast_slice(&paints)),
+			tiles,
+		}
+	}
+}
+
+/// What [`TileSceneBufs`] uploads, worked out on the CPU.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileBins {
+	/// The monotonic pieces of every drawn path, in pixels.
+	pub pieces: Vec<Segment>,
+	/// Laid out like [`TileSceneBufs::bins`], indexing `pieces` and `draws`.
+	pub bins: Vec<u32>,
+	/// The index in [`Scene::draws`] of each paint. Draws that aren't in any tile don't have one.
+	pub draws: Vec<usize>,
+	/// Columns and rows.
+	pub tiles: [u32; 2],
+}
+impl TileBins {
+	/// Bins `scene` for a view of `view_size` pixels, which `to_view` maps it to.
+	pub fn new(scene: &Scene, to_view: &Transform, view_size: [u32; 2]) -> Self {
+		let tiles = view_size.map(|size| size.div_ceil(TILE_SIZE));
+		let mut pieces = vec![];
+		let mut draws = vec![];
+		let mut draw_bins = vec![];
+		for (i, draw) in scene.draws.iter().enumerate() {
+			let path_pieces = draw.path.transform(&draw.transform.then(to_view)).monotonic_pieces();
+			let paint = draws.len() as u32
+				| (draw.blend_mode as u32) << BLEND_MODE_SHIFT
+				| match draw.fill_rule {
+					FillRule::EvenOdd => FLAG,
+					FillRule::NonZero => 0,
+				};
+			let bins_before = draw_bins.len();
+			bin_pieces(&path_pieces, pieces.len() as u32, draw.fill_rule, tiles, |tile, backdrop, piece_refs| {
+				draw_bins.push((tile, paint, backdrop, piece_refs))
+			});
+			if draw_bins.len() == bins_before {
+				continue;
+			}
+			pieces.extend(path_pieces);
+			draws.push(i);
+		}
+
+		// draws stay in order within each tile
+		draw_bins.sort_by_key(|&(tile, ..)| tile);
+		let tile_count = (tiles[0] * tiles[1]) as usize;
+		let first_draw = 2 * tile_count;
+		let mut bins = vec![0; first_draw + 4 * draw_bins.len()];
+		for (i, (tile, paint, backdrop, piece_refs)) in draw_bins.into_iter().enumerate() {
+			let tile = tile as usize;
+			if bins[2 * tile + 1] == 0 {
+				bins[2 * tile] = (first_draw + 4 * i) as u32;
+			}
+			bins[2 * tile + 1] += 1;
+			let (draw, first_piece) = (first_draw + 4 * i, bins.len() as u32);
+			bins[draw..draw + 4].copy_from_slice(&[paint, backdrop as u32, first_piece, piece_refs.len() as u32]);
+			bins.extend(piece_refs);
+		}
+		Self { pieces, bins, draws, tiles }
+	}
+}
+
+/// Calls `f` with every tile that `pieces` cover any of, with the backdrop and the flagged indices of the pieces in
+/// it, which start at `first_piece`. Tiles are row by row.
+fn bin_pieces(
+	pieces: &[Segment],
+	first_piece: u32,
+	fill_rule: FillRule,
+	tiles: [u32; 2],
+	mut f: impl FnMut(u32, i32, Vec<u32>),
+) {
+	let size = TILE_SIZE as f32;
+	let column_of = |x: f32| ((x + 0.625) / size).floor() as i64;
+	let row_of = |y: f32| (y / size).floor() as i64;
+	let left_edge = |column: i64| column as f32 * size - 0.625;
+
+	let mut bounds = [f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY];
+	for piece in pieces {
+		for p in [piece.from(), piece.to()] {
+			bounds = [bounds[0].min(p[0]), bounds[1].min(p[1]), bounds[2].max(p[0]), bounds[3].max(p[1])];
+		}
+	}
+	// the pieces are monotonic, so their ends bound them
+	let [columns, rows] = tiles.map(|count| count as i64);
+	let (first_column, last_column) = (column_of(bounds[0]).max(0), column_of(bounds[2]).min(columns - 1));
+	let (first_row, last_row) = (row_of(bounds[1]).max(0), row_of(bounds[3]).min(rows - 1));
+	if first_column > last_column || first_row > last_row {
+		return;
+	}
+	let width = (last_column - first_column + 1) as usize;
+	let tile_index = |column: i64, row: i64| (row * columns + column) as u32;
+
+	// the pieces in each tile, and the crossings of each tile's left edge, noted in the row above so that adding up the
+	// rows from the bottom gives the backdrops
+	let mut piece_refs = vec![];
+	let mut crossings = vec![0; width * (last_row - first_row + 1) as usize];
+	for (i, piece) in pieces.iter().enumerate() {
+		let piece_ref = first_piece + i as u32;
+		let (from, to) = (piece.from(), piece.to());
+		let (top, bottom) = (from[1].min(to[1]), from[1].max(to[1]));
+		if top < bottom {
+			for row in row_of(top).max(first_row)..=row_of(bottom).min(last_row) {
+				let band = [(row as f32 * size).max(top), ((row + 1) as f32 * size).min(bottom)];
+				let [a, b] = band.map(|y| point_at(piece, 1, y)[0]);
+				for column in column_of(a.min(b)).max(first_column)..=column_of(a.max(b)).min(last_column) {
+					piece_refs.push((tile_index(column, row), piece_ref));
+				}
+			}
+		}
+
+		let (left, right) = (from[0].min(to[0]), from[0].max(to[0]));
+		for column in (column_of(left) + 1).max(first_column)..=column_of(right).min(last_column) {
+			let crossing_row = row_of(point_at(piece, 0, left_edge(column))[1]);
+			if (first_row..=last_row).contains(&crossing_row) {
+				piece_refs.push((tile_index(column, crossing_row), piece_ref | FLAG));
+			}
+			if crossing_row > first_row {
+				let row = (crossing_row - 1).min(last_row) - first_row;
+				crossings[row as usize * width + (column - first_column) as usize] +=
+					if to[0] > from[0] { 1 } else { -1 };
+			}
+		}
+	}
+	// a piece crossing a tile's left edge is only in it once, flagged
+	piece_refs.sort_by_key(|&(tile, piece_ref)| (tile, piece_ref & !FLAG, piece_ref & FLAG == 0));
+	piece_refs.dedup_by_key(|&mut (tile, piece_ref)| (tile, piece_ref & !FLAG));
+
+	let mut backdrops = vec![0; width];
+	for row in (first_row..=last_row).rev() {
+		for (backdrop, crossings) in backdrops.iter_mut().zip(&crossings[(row - first_row) as usize * width..]) {
+			*backdrop += crossings;
+		}
+	}
+	let mut piece_refs = piece_refs.into_iter().peekable();
+	for row in first_row..=last_row {
+		let row_crossings = &crossings[(row - first_row) as usize * width..][..width];
+		for (column, backdrop) in (first_column..=last_column).zip(&mut backdrops) {
+			let tile = tile_index(column, row);
+			let mut tile_refs = vec![];
+			while let Some((_, piece_ref)) = piece_refs.next_if(|&(piece_tile, _)| piece_tile == tile) {
+				tile_refs.push(piece_ref);
+			}
+			if !tile_refs.is_empty() || fill_rule.is_inside(*backdrop) {
+				f(tile, *backdrop, tile_refs);
+			}
+		}
+		// crossings only count for the rows above them
+		for (backdrop, crossings) in backdrops.iter_mut().zip(row_crossings) {
+			*backdrop -= crossings;
+		}
+	}
+}
+
+/// Where a monotonic piece reaches `value` along `axis`, which it has to reach.
+fn point_at(piece: &Segment, axis: usize, value: f32) -> [f32; 2] {
+	let (hull, w) = match *piece {
+		Segment::Line(line) => {
+			let delta = line.to[axis] - line.from[axis];
+			let t = if delta == 0.0 { 0.0 } else { ((value - line.from[axis]) / delta).clamp(0.0, 1.0) };
+			return line.eval(t);
+		},
+		Segment::Quadratic(quad) => (quad.hull(), 1.0),
+		Segment::Conic(conic) => (conic.hull(), conic.weight as f64),
+	};
+	// where the curve's distance from `value` times the conic's denominator is zero, like `x_at` in tile.wgsl
+	let [from, ctrl, to] = hull.map(|p| p[axis] as f64);
+	let value = value as f64;
+	let a = from - 2.0 * w * ctrl + to + 2.0 * (w - 1.0) * value;
+	let b = 2.0 * (w * ctrl - from) - 2.0 * (w - 1.0) * value;
+	let c = from - value;
+	let t = if a.abs() <= 1e-12 * (b.abs() + c.abs()) {
+		if b == 0.0 {
+			0.0
+		} else {
+			-c / b
+		}
+	} else {
+		// the numerically stable roots, of which the one in the piece is wanted
+		let q = -0.5 * (b + b.signum() * (b * b - 4.0 * a * c).max(0.0).sqrt());
+		let roots = [q / a, if q == 0.0 { 0.0 } else { c / q }];
+		match (roots[0] - 0.5).abs() < (roots[1] - 0.5).abs() {
+			true => roots[0],
+			false => roots[1],
+		}
+	};
+	piece.eval(t.clamp(0.0, 1.0) as f32)
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use nice_path_rasterizer::{
+	BlendMode, Color, CoverageMask, CoverageSampling, Draw, FillRule, OffscreenTarget, Paint, PaintBuf, PathBufs,
+	PathPipelines, RasterBackend, RgbaImage, Scene, SceneBufs, Transform,
+};
+use wgpu::{Device, LoadOp, Maintain, Queue, TextureFormat};
+
+const FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+const SIZE: [u32; 2] = [40, 36];
+/// What's under the draws, premultiplied.
+const BACKGROUND: wgpu::Color = wgpu::Color { r: 0.1, g: 0.3, b: 0.2, a: 0.5 };
+
+fn render(
+	device: &Device,
+	queue: &Queue,
+	pipelines: &PathPipelines,
+	target: &OffscreenTarget,
+	scene: &Scene,
+) -> RgbaImage {
+	let scene_bufs = SceneBufs::with_backend(device, scene, &Transform::IDENTITY, FORMAT, pipelines.backend);
+	target.render(device, queue, pipelines, &scene_bufs, BACKGROUND);
+	let image = target.read_rgba(device, queue);
+	device.poll(Maintain::Wait);
+	pollster::block_on(image).unwrap()
+}
+
+#[test]
+fn matches_cpu_blending() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let [width, height] = SIZE;
+	let target = OffscreenTarget::new(&device, width, height, FORMAT);
+	// an opaque backdrop over some of a translucent background, with its edges between pixels so they're sharp
+	let mut scene = Scene::new([width as f32, height as f32]);
+	let rect = common::rect(0.0, 0.0, 24.0, 36.0);
+	scene.push(Draw::new(rect, Paint::Solid(Color::new(0.8, 0.7, 0.2, 1.0))));
+	let paint = Color::new(0.9, 0.3, 0.1, 0.7);
+
+	for (backend, sampling) in [
+		(RasterBackend::Jitter, CoverageSampling::Jitter),
+		(RasterBackend::StencilCover, CoverageSampling::Multisample),
+	] {
+		let pipelines = PathPipelines::with_backend(&device, FORMAT, SIZE, backend);
+		let mask =
+			CoverageMask::rasterize(&common::star([19.25, 18.5], 17.0), FillRule::NonZero, width, height, sampling);
+		let backdrop = render(&device, &queue, &pipelines, &target, &scene);
+		for mode in BlendMode::ALL {
+			let mut scene = scene.clone();
+			let mut draw = Draw::new(common::star([19.25, 18.5], 17.0), Paint::Solid(paint));
+			draw.blend_mode = mode;
+			scene.push(draw);
+			let image = render(&device, &queue, &pipelines, &target, &scene);
+
+			for y in 0..height {
+				for x in 0..width {
+					// other backends only roughly cover the edges the same
+					let coverage = mask.get(x, y);
+					if backend != RasterBackend::Jitter && coverage != 0.0 && coverage != 1.0 {
+						continue;
+					}
+					// the view keeps colours premultiplied
+					let dst = backdrop.pixel(x, y).map(|c| c as f32 / 255.0);
+					let src = paint.premultiplied();
+					let expected: Vec<f32> = match mode {
+						// the faded paint is added, which only differs where it's clamped
+						BlendMode::Plus => mode.blend(src.map(|c| c * coverage), dst).to_vec(),
+						_ => dst.iter().zip(mode.blend(src, dst)).map(|(d, b)| d + (b - d) * coverage).collect(),
+					};
+					let actual = image.pixel(x, y).map(|c| c as f32 / 255.0);
+					assert!(
+						actual.iter().zip(&expected).all(|(a, e)| (a - e).abs() <= 3.0 / 255.0),
+						"{:?} {:?} at {}, {}: {:?} != {:?}",
+						backend,
+						mode,
+						x,
+						y,
+						actual,
+						expected
+					);
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn clipped() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let [width, height] = SIZE;
+	let mut target = OffscreenTarget::new(&device, width, height, FORMAT);
+	let pipelines = PathPipelines::new(&device, FORMAT, SIZE);
+	let clip = PathBufs::new(&device, &common::rect(8.0, 8.0, 24.0, 20.0));
+	let everything = PathBufs::new(&device, &common::rect(0.0, 0.0, width as f32, height as f32));
+	let paint =
+		PaintBuf::new(&device, &Paint::Solid(Color::new(1.0, 0.0, 0.0, 1.0)), 1.0, &Transform::IDENTITY, FORMAT);
+
+	// only what's within the clip is replaced, even though SrcIn would clear the rest
+	queue.submit(target.intermediate_bufs_mut().push_clip(&device, &pipelines, &clip, FillRule::EvenOdd));
+	queue.submit([
+		pipelines.draw_coverage(&device, target.intermediate_bufs(), &everything, FillRule::EvenOdd),
+		pipelines.rasterize.draw_blended(
+			&device,
+			target.intermediate_bufs(),
+			target.texture(),
+			&paint,
+			BlendMode::SrcIn,
+			LoadOp::Clear(BACKGROUND),
+		),
+	]);
+	let image = target.read_rgba(&device, &queue);
+	device.poll(Maintain::Wait);
+	let image = pollster::block_on(image).unwrap();
+	for y in 0..height {
+		for x in 0..width {
+			// jitter samples reach into the pixels either side of the clip's edges
+			let expected = match ((7..=24).contains(&x), (8..20).contains(&y)) {
+				(true, true) if (9..23).contains(&x) => [128, 0, 0, 128],
+				(true, true) => continue,
+				_ => [26, 76, 51, 128],
+			};
+			let actual = image.pixel(x, y);
+			assert!(actual.iter().zip(expected).all(|(a, e)| a.abs_diff(e) <= 1), "at {}, {}: {:?}", x, y, actual);
+		}
+	}
+}
+
+#[test]
+fn shaders_number_modes_like_rust() {
+	let shader = include_str!("../src/pipelines/common.wgsl");
+	for mode in BlendMode::ALL {
+		let mut name = String::new();
+		for c in format!("{:?}", mode).chars() {
+			if c.is_uppercase() && !name.is_empty() {
+				name.push('_');
+			}
+			name.push(c.to_ascii_uppercase());
+		}
+		let constant = format!("let BLEND_{}: u32 = {}u;", name, mode as u32);
+		assert!(shader.contains(&constant), "common.wgsl doesn't have {}", constant);
+	}
+}
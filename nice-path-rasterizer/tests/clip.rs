@@ -0,0 +1,93 @@
+mod common;
+
+use nice_path_rasterizer::{
+	Color, CoverageMask, CoverageSampling, FillRule, OffscreenTarget, Paint, PaintBuf, PathBufs, PathPipelines,
+	RasterBackend, RgbaImage, Transform,
+};
+use wgpu::{Device, LoadOp, Maintain, Queue, TextureFormat};
+
+const FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+const SIZE: [u32; 2] = [40, 36];
+
+fn read(device: &Device, queue: &Queue, target: &OffscreenTarget) -> RgbaImage {
+	let image = target.read_rgba(device, queue);
+	device.poll(Maintain::Wait);
+	pollster::block_on(image).unwrap()
+}
+
+/// Fills the whole target with `color`, as clipped.
+fn fill(
+	device: &Device,
+	queue: &Queue,
+	pipelines: &PathPipelines,
+	target: &OffscreenTarget,
+	color: Color,
+	load: LoadOp<wgpu::Color>,
+) {
+	let everything = PathBufs::new(device, &common::rect(-1.0, -1.0, SIZE[0] as f32 + 1.0, SIZE[1] as f32 + 1.0));
+	let paint = PaintBuf::new(device, &Paint::Solid(color), 1.0, &Transform::IDENTITY, FORMAT);
+	queue.submit([
+		pipelines.draw_coverage(device, target.intermediate_bufs(), &everything, FillRule::EvenOdd),
+		pipelines.rasterize.draw_paint(device, target.intermediate_bufs(), target.view(), &paint, load),
+	]);
+}
+
+/// `expected` has to be sampled the same way as the backend.
+fn assert_coverage(image: &RgbaImage, channel: usize, expected: impl Fn(u32, u32) -> f32, name: &str) {
+	for y in 0..image.height {
+		for x in 0..image.width {
+			let expected = expected(x, y);
+			let actual = image.pixel(x, y)[channel] as f32 / 255.0;
+			// the masks are 8 bit
+			assert!((actual - expected).abs() <= 2.0 / 255.0, "{} at {}, {}: {} != {}", name, x, y, actual, expected);
+		}
+	}
+}
+
+#[test]
+fn nested_clips() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let [width, height] = SIZE;
+	// edges between pixels, so they're anti-aliased
+	let outer = common::rect(4.5, 3.25, 30.75, 33.5);
+	let inner = common::star([19.25, 18.5], 17.0);
+
+	for (backend, sampling) in [
+		(RasterBackend::Jitter, CoverageSampling::Jitter),
+		(RasterBackend::StencilCover, CoverageSampling::Multisample),
+	] {
+		let outer_mask = CoverageMask::rasterize(&outer, FillRule::EvenOdd, width, height, sampling);
+		let inner_mask = CoverageMask::rasterize(&inner, FillRule::EvenOdd, width, height, sampling);
+		let mut target = OffscreenTarget::new(&device, width, height, FORMAT);
+		let pipelines = PathPipelines::with_backend(&device, FORMAT, SIZE, backend);
+		let (outer_bufs, inner_bufs) = (PathBufs::new(&device, &outer), PathBufs::new(&device, &inner));
+
+		// red within both clips, green within the outer one, then blue everywhere
+		queue.submit(target.intermediate_bufs_mut().push_clip(&device, &pipelines, &outer_bufs, FillRule::EvenOdd));
+		queue.submit(target.intermediate_bufs_mut().push_clip(&device, &pipelines, &inner_bufs, FillRule::EvenOdd));
+		assert_eq!(target.intermediate_bufs().clip_depth(), 2);
+		fill(&device, &queue, &pipelines, &target, Color::new(1.0, 0.0, 0.0, 1.0), LoadOp::Clear(wgpu::Color::BLACK));
+		let image = read(&device, &queue, &target);
+		let both = |x, y| outer_mask.get(x, y) * inner_mask.get(x, y);
+		assert_coverage(&image, 0, both, &format!("{:?} within both", backend));
+
+		target.intermediate_bufs_mut().pop_clip();
+		fill(&device, &queue, &pipelines, &target, Color::new(0.0, 1.0, 0.0, 1.0), LoadOp::Clear(wgpu::Color::BLACK));
+		let image = read(&device, &queue, &target);
+		assert_coverage(&image, 1, |x, y| outer_mask.get(x, y), &format!("{:?} within the outer clip", backend));
+
+		target.intermediate_bufs_mut().pop_clip();
+		assert_eq!(target.intermediate_bufs().clip_depth(), 0);
+		fill(&device, &queue, &pipelines, &target, Color::new(0.0, 0.0, 1.0, 1.0), LoadOp::Load);
+		let image = read(&device, &queue, &target);
+		assert!(image.data.chunks(4).all(|pixel| pixel == [0, 0, 255, 255]), "{:?} is still clipped", backend);
+
+		// pushing again reuses the masks, which have to start over
+		queue.submit(target.intermediate_bufs_mut().push_clip(&device, &pipelines, &inner_bufs, FillRule::EvenOdd));
+		fill(&device, &queue, &pipelines, &target, Color::WHITE, LoadOp::Clear(wgpu::Color::BLACK));
+		let image = read(&device, &queue, &target);
+		assert_coverage(&image, 0, |x, y| inner_mask.get(x, y), &format!("{:?} within the star again", backend));
+	}
+}
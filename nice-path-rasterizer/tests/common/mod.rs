@@ -0,0 +1,36 @@
+//! Shared by every test file, though not every one of them uses all of it.
+#![allow(dead_code)]
+
+use std::{env, f32::consts::PI};
+
+use nice_path_rasterizer::{request_headless_device, Path};
+use wgpu::{Device, Queue};
+
+/// The device for tests that render, or `None` to skip them on machines without an adapter. Setting `REQUIRE_GPU`
+/// makes them fail instead, so CI can't pass without having rendered anything.
+pub fn device() -> Option<(Device, Queue)> {
+	let device = pollster::block_on(request_headless_device());
+	if device.is_none() {
+		assert!(env::var_os("REQUIRE_GPU").is_none(), "REQUIRE_GPU is set, but there's no adapter");
+		eprintln!("no adapter, skipping");
+	}
+	device
+}
+
+/// A five-pointed star drawn in one stroke, so it crosses itself and its middle is only filled with the nonzero rule.
+pub fn star(center: [f32; 2], radius: f32) -> Path {
+	let mut star = Path::start();
+	for i in 0..5 {
+		let angle = i as f32 * 4.0 * PI / 5.0;
+		let point = [center[0] + radius * angle.sin(), center[1] - radius * angle.cos()];
+		star = match i {
+			0 => star.move_to(point),
+			_ => star.line_to(point),
+		};
+	}
+	star
+}
+
+pub fn rect(left: f32, top: f32, right: f32, bottom: f32) -> Path {
+	Path::start().move_to([left, top]).line_to([right, top]).line_to([right, bottom]).line_to([left, bottom])
+}
@@ -0,0 +1,126 @@
+//! Renders every SVG in `tests/golden` and compares it to the PNG next to it. When a rendering change is intended, run
+//! with `UPDATE_GOLDEN=1` to write new references, and check them in after looking at them. The references in the repo
+//! were rendered on llvmpipe, see the README.
+
+mod common;
+
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+};
+
+use nice_path_rasterizer::{DiffTolerance, OffscreenTarget, PathPipelines, RgbaImage, Scene, SceneBufs, Transform};
+use wgpu::{Device, Maintain, Queue, TextureFormat};
+
+/// A few pixels on the edges can differ between GPUs, and rendering regressions change many more than that.
+const TOLERANCE: DiffTolerance = DiffTolerance { threshold: 0.1, max_mismatched: 0.002 };
+const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+fn corpus_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn render(device: &Device, queue: &Queue, svg: &Path) -> RgbaImage {
+	let import = Scene::from_svg(&fs::read_to_string(svg).unwrap()).unwrap();
+	assert!(import.unsupported.is_empty(), "{}: {:?}", svg.display(), import.unsupported);
+	let [width, height] = import.scene.size.map(|length| length.ceil() as u32);
+
+	let target = OffscreenTarget::new(device, width, height, FORMAT);
+	let pipelines = PathPipelines::new(device, FORMAT, [width, height]);
+	let scene = SceneBufs::new(device, &import.scene, &Transform::IDENTITY, FORMAT);
+	target.render(device, queue, &pipelines, &scene, wgpu::Color::WHITE);
+	let image = target.read_rgba(device, queue);
+	device.poll(Maintain::Wait);
+	pollster::block_on(image).unwrap()
+}
+
+#[test]
+fn golden_images() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let update = env::var_os("UPDATE_GOLDEN").is_some();
+	let diff_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+	fs::create_dir_all(&diff_dir).unwrap();
+
+	let mut svgs: Vec<PathBuf> = fs::read_dir(corpus_dir())
+		.unwrap()
+		.map(|entry| entry.unwrap().path())
+		.filter(|path| path.extension().is_some_and(|extension| extension == "svg"))
+		.collect();
+	svgs.sort();
+	assert!(!svgs.is_empty());
+
+	let mut failures = vec![];
+	for svg in &svgs {
+		let name = svg.file_stem().unwrap().to_str().unwrap();
+		let reference = svg.with_extension("png");
+		let actual = render(&device, &queue, svg);
+		if update {
+			actual.save_png(&reference).unwrap();
+			continue;
+		}
+
+		let expected = match RgbaImage::load_png(&reference) {
+			Ok(expected) => expected,
+			Err(err) => {
+				failures.push(format!("{}: can't read {}: {}", name, reference.display(), err));
+				continue;
+			},
+		};
+		if (actual.width, actual.height) != (expected.width, expected.height) {
+			failures.push(format!(
+				"{}: rendered {}x{}, but the reference is {}x{}",
+				name, actual.width, actual.height, expected.width, expected.height
+			));
+			continue;
+		}
+		let diff = actual.diff(&expected, &TOLERANCE);
+		if !diff.is_within(&TOLERANCE) {
+			let actual_path = diff_dir.join(format!("{}.actual.png", name));
+			let diff_path = diff_dir.join(format!("{}.diff.png", name));
+			actual.save_png(&actual_path).unwrap();
+			diff.image.save_png(&diff_path).unwrap();
+			failures.push(format!(
+				"{}: {} pixels mismatch, by up to {:.3}; see {} and {}",
+				name,
+				diff.mismatched,
+				diff.max_difference,
+				actual_path.display(),
+				diff_path.display()
+			));
+		}
+	}
+	assert!(
+		failures.is_empty(),
+		"golden images differ, run with UPDATE_GOLDEN=1 if that's intended:\n{}",
+		failures.join("\n")
+	);
+}
+
+#[test]
+fn diff_metric() {
+	let image = |pixels: &[[u8; 4]]| RgbaImage { width: pixels.len() as u32, height: 1, data: pixels.concat() };
+	let expected = image(&[[0, 0, 0, 255], [255, 255, 255, 255], [0, 0, 0, 0], [200, 0, 0, 255]]);
+
+	// transparent is white once premultiplied pixels are put over white
+	let actual = image(&[[0, 0, 0, 255], [0, 0, 0, 0], [255, 255, 255, 255], [200, 0, 0, 255]]);
+	let diff = actual.diff(&expected, &DiffTolerance::default());
+	assert_eq!((diff.mismatched, diff.max_difference), (0, 0.0));
+	assert!(diff.is_within(&DiffTolerance::default()));
+
+	// black and white are nearly as different as it gets, and a slightly different red is within the threshold
+	let actual = image(&[[255, 255, 255, 255], [255, 255, 255, 255], [0, 0, 0, 0], [204, 0, 0, 255]]);
+	let diff = actual.diff(&expected, &DiffTolerance::default());
+	assert_eq!(diff.mismatched, 1);
+	assert!((diff.max_difference - 0.966).abs() < 1e-3, "{}", diff.max_difference);
+	assert!(!diff.is_within(&DiffTolerance::default()));
+	assert!(diff.is_within(&DiffTolerance { threshold: 0.1, max_mismatched: 0.25 }));
+	assert_eq!(diff.image.pixel(0, 0), [255, 0, 0, 255]);
+	assert_eq!(diff.image.pixel(3, 0), [255, 255, 0, 255]);
+
+	// references round trip through PNGs
+	let mut png = vec![];
+	expected.write_png(&mut png).unwrap();
+	assert_eq!(RgbaImage::read_png(png.as_slice()).unwrap(), expected);
+}
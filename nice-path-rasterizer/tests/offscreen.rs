@@ -0,0 +1,56 @@
+mod common;
+
+use nice_path_rasterizer::{
+	Color, Draw, OffscreenTarget, Paint, Path, PathPipelines, RgbaImage, Scene, SceneBufs, Transform,
+};
+use wgpu::{Maintain, TextureFormat};
+
+fn render(format: TextureFormat) -> Option<RgbaImage> {
+	let (device, queue) = common::device()?;
+	// not a multiple of the row alignment, so rows are padded in the copy
+	let (width, height) = (70, 30);
+	let mut scene = Scene::new([width as f32, height as f32]);
+	let square = Path::start().move_to([0.0, 0.0]).line_to([20.0, 0.0]).line_to([20.0, 20.0]).line_to([0.0, 20.0]);
+	scene.push(Draw::new(square.clone(), Paint::Solid(Color::new(1.0, 0.0, 0.0, 1.0))));
+	let mut blue = Draw::new(square, Paint::Solid(Color::new(0.0, 0.0, 1.0, 1.0)));
+	blue.transform = Transform::translate(40.0, 5.0);
+	scene.push(blue);
+
+	let target = OffscreenTarget::new(&device, width, height, format);
+	let pipelines = PathPipelines::new(&device, format, [width, height]);
+	let scene = SceneBufs::new(&device, &scene, &Transform::IDENTITY, format);
+	target.render(&device, &queue, &pipelines, &scene, wgpu::Color::WHITE);
+	let image = target.read_rgba(&device, &queue);
+	device.poll(Maintain::Wait);
+	Some(pollster::block_on(image).unwrap())
+}
+
+#[test]
+fn renders_and_reads_back() {
+	for format in [TextureFormat::Rgba8UnormSrgb, TextureFormat::Bgra8Unorm] {
+		let Some(image) = render(format) else { return };
+		assert_eq!((image.width, image.height, image.data.len()), (70, 30, 70 * 30 * 4));
+		assert_eq!(image.pixel(10, 10), [255, 0, 0, 255]);
+		assert_eq!(image.pixel(50, 15), [0, 0, 255, 255]);
+		assert_eq!(image.pixel(30, 10), [255, 255, 255, 255]);
+		assert_eq!(image.pixel(69, 29), [255, 255, 255, 255]);
+		// some of the samples of the pixel on the right edge are inside
+		let edge = image.pixel(20, 10);
+		assert!(edge[0] == 255 && edge[1] > 0 && edge[1] < 255, "{:?}", edge);
+	}
+}
+
+#[test]
+fn image_files() {
+	let image = RgbaImage { width: 2, height: 1, data: vec![1, 2, 3, 4, 5, 6, 7, 8] };
+	let mut ppm = vec![];
+	image.write_ppm(&mut ppm).unwrap();
+	assert_eq!(ppm, b"P6\n2 1\n255\n\x01\x02\x03\x05\x06\x07");
+
+	let mut png = vec![];
+	image.write_png(&mut png).unwrap();
+	let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+	let mut data = vec![0; reader.output_buffer_size()];
+	reader.next_frame(&mut data).unwrap();
+	assert_eq!(data, image.data);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use nice_path_rasterizer::{
+	CoverageMask, CoverageSampling, FillRule, IntermediateBufs, PathBufs, PathId, PathPipelines, PickTarget,
+	RasterBackend,
+};
+use wgpu::{Maintain, TextureFormat};
+
+const SIZE: [u32; 2] = [40, 36];
+
+#[test]
+fn picks_topmost_path() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let [width, height] = SIZE;
+	// back to front, with the star's sloped edges cutting across the square and a hole in the middle
+	let paths = [
+		(common::rect(2.0, 3.0, 22.0, 23.0), FillRule::NonZero),
+		(common::star([19.25, 18.5], 17.0), FillRule::EvenOdd),
+	];
+	let clip = common::rect(0.0, 0.0, 32.0, 36.0);
+	for (backend, sampling) in [
+		(RasterBackend::Jitter, CoverageSampling::Jitter),
+		(RasterBackend::StencilCover, CoverageSampling::Multisample),
+	] {
+		let masks: Vec<_> =
+			paths.iter().map(|(path, rule)| CoverageMask::rasterize(path, *rule, width, height, sampling)).collect();
+		let clip_mask = CoverageMask::rasterize(&clip, FillRule::NonZero, width, height, sampling);
+		let pipelines = PathPipelines::with_backend(&device, TextureFormat::Rgba8Unorm, SIZE, backend);
+		let mut target = IntermediateBufs::new(&device, width, height);
+		let pick_target = PickTarget::new(&device, width, height);
+
+		queue.submit(target.push_clip(&device, &pipelines, &PathBufs::new(&device, &clip), FillRule::NonZero));
+		queue.submit(Some(pipelines.pick.clear(&device, &pick_target)));
+		for (i, (path, fill_rule)) in paths.iter().enumerate() {
+			let path_bufs = PathBufs::new(&device, path);
+			queue.submit(pipelines.draw_pick(&device, &target, &pick_target, &path_bufs, *fill_rule, PathId(i as u32)));
+		}
+
+		// every pick is submitted before the device is polled once
+		let picks: Vec<_> = (0..height)
+			.flat_map(|y| (0..width).map(move |x| (x, y)))
+			.map(|(x, y)| pick_target.pick(&device, &queue, x, y))
+			.collect();
+		let outside = pick_target.pick(&device, &queue, width, 0);
+		device.poll(Maintain::Wait);
+
+		let mut picked = 0;
+		for (i, pick) in picks.into_iter().enumerate() {
+			let (x, y) = (i as u32 % width, i as u32 / width);
+			let coverages: Vec<f32> = masks.iter().map(|mask| mask.get(x, y) * clip_mask.get(x, y)).collect();
+			// the coverage is rounded to 8 bits on the way, so exactly half covered pixels could go either way
+			if coverages.iter().any(|c| (c - 0.5).abs() < 0.01) {
+				continue;
+			}
+			let expected = coverages.iter().rposition(|c| *c >= 0.5).map(|i| PathId(i as u32));
+			let actual = pollster::block_on(pick).unwrap();
+			assert_eq!(actual, expected, "{:?} at {}, {}", backend, x, y);
+			picked += actual.is_some() as u32;
+		}
+		assert!(picked > 0);
+		assert_eq!(pollster::block_on(outside).unwrap(), None);
+
+		// the corners are empty, the square shows around and through the star, and the clip cuts off the star's right arm
+		let spots = [((0, 0), None), ((3, 21), Some(0)), ((19, 20), Some(0)), ((19, 5), Some(1)), ((34, 13), None)];
+		for ((x, y), expected) in spots {
+			let pick = pick_target.pick(&device, &queue, x, y);
+			device.poll(Maintain::Wait);
+			assert_eq!(pollster::block_on(pick).unwrap(), expected.map(PathId), "{:?} at {}, {}", backend, x, y);
+		}
+	}
+}
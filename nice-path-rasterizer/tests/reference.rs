@@ -0,0 +1,138 @@
+mod common;
+
+use std::f32::consts::PI;
+
+use nice_path_rasterizer::{
+	Color, CoverageMask, CoverageSampling, Draw, FillRule, OffscreenTarget, Paint, Path, PathPipelines, Scene,
+	SceneBufs, Transform,
+};
+use wgpu::{Maintain, TextureFormat};
+
+fn assert_close(a: f32, b: f32, tolerance: f32) {
+	assert!((a - b).abs() < tolerance, "{} != {}", a, b);
+}
+
+fn total(mask: &CoverageMask) -> f32 {
+	mask.data.iter().sum()
+}
+
+#[test]
+fn exact_coverage() {
+	let mask =
+		CoverageMask::rasterize(&common::rect(0.25, 0.5, 2.75, 2.5), FillRule::NonZero, 4, 3, CoverageSampling::Exact);
+	let expected = [[0.375, 0.5, 0.375, 0.0], [0.75, 1.0, 0.75, 0.0], [0.375, 0.5, 0.375, 0.0]];
+	for (y, row) in expected.iter().enumerate() {
+		for (x, &coverage) in row.iter().enumerate() {
+			assert_close(mask.get(x as u32, y as u32), coverage, 1e-6);
+		}
+	}
+
+	// a diagonal halves the pixels it crosses
+	let triangle = Path::start().move_to([0.0, 0.0]).line_to([4.0, 0.0]).line_to([4.0, 4.0]);
+	let mask = CoverageMask::rasterize(&triangle, FillRule::NonZero, 4, 4, CoverageSampling::Exact);
+	for i in 0..4 {
+		assert_close(mask.get(i, i), 0.5, 1e-6);
+	}
+	assert_close(mask.get(3, 0), 1.0, 1e-6);
+	assert_close(mask.get(0, 3), 0.0, 1e-6);
+
+	// curves
+	let circle = Path::from_svg_path_data("M2.5 10 A8 8 0 0 1 18.5 10 A8 8 0 0 1 2.5 10 Z").unwrap();
+	let mask = CoverageMask::rasterize(&circle, FillRule::NonZero, 20, 20, CoverageSampling::Exact);
+	assert_close(total(&mask) / (PI * 64.0), 1.0, 2e-3);
+	assert!(mask.data.iter().all(|&coverage| (-1e-6..=1.0 + 1e-6).contains(&coverage)));
+
+	// anything outside the mask is cut off
+	let mask =
+		CoverageMask::rasterize(&common::rect(-3.0, -2.5, 2.5, 1.5), FillRule::NonZero, 4, 4, CoverageSampling::Exact);
+	assert_close(total(&mask), 2.5 * 1.5, 1e-5);
+}
+
+#[test]
+fn fill_rules() {
+	let star = common::star([16.0, 16.0], 14.0);
+	let even_odd = CoverageMask::rasterize(&star, FillRule::EvenOdd, 32, 32, CoverageSampling::Exact);
+	let non_zero = CoverageMask::rasterize(&star, FillRule::NonZero, 32, 32, CoverageSampling::Exact);
+	assert_close(even_odd.get(16, 16), 0.0, 1e-6);
+	assert_close(non_zero.get(16, 16), 1.0, 1e-6);
+
+	// the areas agree with resolving the fill rule geometrically
+	for (mask, fill_rule) in [(&even_odd, FillRule::EvenOdd), (&non_zero, FillRule::NonZero)] {
+		let area = star.union(&Path::start(), fill_rule).area().abs();
+		assert_close(total(mask) / area, 1.0, 1e-4);
+	}
+
+	// overlapping subpaths going the same way cover pixels once
+	let overlapping = common::rect(0.0, 0.0, 3.0, 1.0)
+		.move_to([1.5, 0.0])
+		.line_to([4.0, 0.0])
+		.line_to([4.0, 1.0])
+		.line_to([1.5, 1.0]);
+	let mask = CoverageMask::rasterize(&overlapping, FillRule::NonZero, 4, 1, CoverageSampling::Exact);
+	assert_eq!(mask.data, [1.0; 4]);
+}
+
+#[test]
+fn jitter_coverage() {
+	// left of x = 10, the pixel on the edge has 4 of its 6 samples inside
+	let mask = CoverageMask::rasterize(
+		&common::rect(-5.0, 0.0, 10.0, 4.0),
+		FillRule::NonZero,
+		12,
+		4,
+		CoverageSampling::Jitter,
+	);
+	for y in 0..3 {
+		assert_eq!([0, 9, 10, 11].map(|x| mask.get(x, y)), [1.0, 1.0, 4.0 / 6.0, 0.0]);
+	}
+
+	// it's close to the exact coverage on the whole
+	let star = common::star([16.0, 16.0], 14.0);
+	let jitter = CoverageMask::rasterize(&star, FillRule::EvenOdd, 32, 32, CoverageSampling::Jitter);
+	let exact = CoverageMask::rasterize(&star, FillRule::EvenOdd, 32, 32, CoverageSampling::Exact);
+	assert_close(total(&jitter) / total(&exact), 1.0, 0.02);
+}
+
+#[test]
+fn matches_gpu() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let (width, height) = (40, 36);
+	let paths = [
+		(common::star([16.0, 16.0], 14.0).transform(&Transform::translate(3.25, 1.5)), FillRule::EvenOdd),
+		(common::star([16.0, 16.0], 14.0).transform(&Transform::translate(3.25, 1.5)), FillRule::NonZero),
+		(Path::from_svg_path_data("M2 3 Q30 -10 37 20 C20 40 10 10 2 33 Z").unwrap(), FillRule::EvenOdd),
+	];
+	let format = TextureFormat::Rgba8Unorm;
+	let target = OffscreenTarget::new(&device, width, height, format);
+	let pipelines = PathPipelines::new(&device, format, [width, height]);
+	for (path, fill_rule) in paths {
+		let mut scene = Scene::new([width as f32, height as f32]);
+		let mut draw = Draw::new(path.clone(), Paint::Solid(Color::WHITE));
+		draw.fill_rule = fill_rule;
+		scene.push(draw);
+		let scene = SceneBufs::new(&device, &scene, &Transform::IDENTITY, format);
+		target.render(&device, &queue, &pipelines, &scene, wgpu::Color::BLACK);
+		let image = target.read_rgba(&device, &queue);
+		device.poll(Maintain::Wait);
+		let image = pollster::block_on(image).unwrap();
+
+		let mask = CoverageMask::rasterize(&path, fill_rule, width, height, CoverageSampling::Jitter);
+		for y in 0..height {
+			for x in 0..width {
+				let expected = (mask.get(x, y) * 255.0).round();
+				let actual = image.pixel(x, y)[0] as f32;
+				assert!(
+					(actual - expected).abs() <= 1.0,
+					"{:?} at {}, {}: {} != {}",
+					fill_rule,
+					x,
+					y,
+					actual,
+					expected
+				);
+			}
+		}
+	}
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use nice_path_rasterizer::{
+	Color, CoverageMask, CoverageSampling, Draw, FillRule, OffscreenTarget, Paint, Path, PathBufs, PathPipelines,
+	RasterBackend, RgbaImage, Scene, SceneBufs, Transform,
+};
+use wgpu::{Device, LoadOp, Maintain, Queue, TextureFormat};
+
+const FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+fn read(device: &Device, queue: &Queue, target: &OffscreenTarget) -> RgbaImage {
+	let image = target.read_rgba(device, queue);
+	device.poll(Maintain::Wait);
+	pollster::block_on(image).unwrap()
+}
+
+#[test]
+fn fill_rules() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let (width, height) = (40, 36);
+	let target = OffscreenTarget::new(&device, width, height, FORMAT);
+	let pipelines = PathPipelines::with_backend(&device, FORMAT, [width, height], RasterBackend::StencilCover);
+	let paths = [
+		common::star([19.25, 17.5], 14.0),
+		Path::from_svg_path_data("M2 3 Q30 -10 37 20 C20 40 10 10 2 33 Z M10 10 L30 12 L20 30 Z").unwrap(),
+	];
+	for path in paths {
+		// the paths go straight to the stencil without being resolved, so the pipelines do the fill rule
+		let path_bufs = PathBufs::new(&device, &path);
+		for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+			queue.submit([
+				pipelines.draw_coverage(&device, target.intermediate_bufs(), &path_bufs, fill_rule),
+				pipelines.rasterize.draw_paint(
+					&device,
+					target.intermediate_bufs(),
+					target.view(),
+					&pipelines.rasterize.white,
+					LoadOp::Clear(wgpu::Color::BLACK),
+				),
+			]);
+			let image = read(&device, &queue, &target);
+
+			// curves are tested at every sample, like straight edges
+			let mask = CoverageMask::rasterize(&path, fill_rule, width, height, CoverageSampling::Multisample);
+			let mut total = [0.0, 0.0];
+			for y in 0..height {
+				for x in 0..width {
+					let expected = mask.get(x, y);
+					let actual = image.pixel(x, y)[0] as f32 / 255.0;
+					assert!(
+						(actual - expected).abs() <= 1.0 / 255.0,
+						"{:?} at {}, {}: {} != {}",
+						fill_rule,
+						x,
+						y,
+						actual,
+						expected
+					);
+					total[0] += actual;
+					total[1] += expected;
+				}
+			}
+			assert!((total[0] / total[1] - 1.0).abs() < 0.02, "{:?}: {:?}", fill_rule, total);
+		}
+	}
+}
+
+#[test]
+fn scene() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let (width, height) = (40, 36);
+	let mut scene = Scene::new([width as f32, height as f32]);
+	scene.push(Draw::new(common::star([19.25, 17.5], 14.0), Paint::Solid(Color::new(1.0, 0.0, 0.0, 1.0))));
+	let mut blue = Draw::new(common::star([19.25, 17.5], 14.0), Paint::Solid(Color::new(0.0, 0.0, 1.0, 0.5)));
+	blue.fill_rule = FillRule::EvenOdd;
+	blue.transform = Transform::translate(10.0, 0.0);
+	scene.push(blue);
+
+	// both backends agree away from the edges, though only the jitter one needs the red star resolved
+	let images = [RasterBackend::Jitter, RasterBackend::StencilCover].map(|backend| {
+		let scene = SceneBufs::with_backend(&device, &scene, &Transform::IDENTITY, FORMAT, backend);
+		let fill_rules: Vec<_> = scene.draws.iter().map(|(_, fill_rule, ..)| *fill_rule).collect();
+		let expected = match backend {
+			RasterBackend::Jitter => [FillRule::EvenOdd; 2],
+			RasterBackend::StencilCover => [FillRule::NonZero, FillRule::EvenOdd],
+		};
+		assert_eq!(fill_rules, expected);
+
+		let target = OffscreenTarget::new(&device, width, height, FORMAT);
+		let pipelines = PathPipelines::with_backend(&device, FORMAT, [width, height], backend);
+		target.render(&device, &queue, &pipelines, &scene, wgpu::Color::WHITE);
+		read(&device, &queue, &target)
+	});
+	let [jitter, stencil_cover] = &images;
+	// the red star's middle, which the blue one leaves out, and the blue star's point
+	assert_eq!(stencil_cover.pixel(19, 17), [255, 0, 0, 255]);
+	let [r, g, b, a] = stencil_cover.pixel(35, 25);
+	assert!((127..=128).contains(&r) && r == g && [b, a] == [255, 255], "{:?}", [r, g, b, a]);
+	let mismatched = jitter.data.chunks(4).zip(stencil_cover.data.chunks(4)).filter(|(a, b)| a != b).count();
+	assert!(mismatched < (width * height / 5) as usize, "{} pixels differ", mismatched);
+}
@@ -0,0 +1,153 @@
+//! Run with `--features compute`.
+#![cfg(feature = "compute")]
+
+mod common;
+
+use std::{fs, path::Path as FsPath};
+
+use nice_path_rasterizer::{
+	BlendMode, Color, CoverageMask, CoverageSampling, DiffTolerance, Draw, FillRule, GradientStop, LinearGradient,
+	OffscreenTarget, Paint, Path, PathPipelines, RgbaImage, Scene, SceneBufs, SpreadMethod, TilePipeline,
+	TileSceneBufs, Transform,
+};
+use wgpu::{Device, Maintain, Queue, TextureFormat};
+
+/// The same as the golden images get, since samples that land right on an edge can go either way between backends as
+/// well as between GPUs.
+const TOLERANCE: DiffTolerance = DiffTolerance { threshold: 0.1, max_mismatched: 0.002 };
+
+fn read(device: &Device, queue: &Queue, target: &OffscreenTarget) -> RgbaImage {
+	let image = target.read_rgba(device, queue);
+	device.poll(Maintain::Wait);
+	pollster::block_on(image).unwrap()
+}
+
+/// Renders `scene` with the jitter backend and with the tile pipeline.
+fn render_both(device: &Device, queue: &Queue, scene: &Scene, format: TextureFormat) -> [RgbaImage; 2] {
+	let [width, height] = scene.size.map(|length| length.ceil() as u32);
+	let target = OffscreenTarget::new(device, width, height, format);
+
+	let pipelines = PathPipelines::new(device, format, [width, height]);
+	let scene_bufs = SceneBufs::new(device, scene, &Transform::IDENTITY, format);
+	target.render(device, queue, &pipelines, &scene_bufs, wgpu::Color::WHITE);
+	let jitter = read(device, queue, &target);
+
+	let pipeline = TilePipeline::new(device, format, [width, height]);
+	let scene_bufs = TileSceneBufs::new(device, scene, &Transform::IDENTITY, format, [width, height]);
+	target.render_tiles(device, queue, &pipeline, &scene_bufs, wgpu::Color::WHITE);
+	[jitter, read(device, queue, &target)]
+}
+
+/// Paths cut by the view's edges and by many tiles, overlapping, with both fill rules, a gradient, opacity and blend
+/// modes, both fixed-function and not.
+fn edge_cases() -> Scene {
+	let mut scene = Scene::new([75.0, 53.0]);
+	scene.push(Draw::new(common::star([10.0, 40.0], 30.0), Paint::Solid(Color::new(0.2, 0.4, 0.8, 1.0))));
+	let mut even_odd = Draw::new(common::star([50.0, 20.0], 35.0), Paint::Solid(Color::new(0.9, 0.1, 0.1, 0.6)));
+	even_odd.fill_rule = FillRule::EvenOdd;
+	scene.push(even_odd);
+	let gradient = LinearGradient {
+		start: [0.0, 0.0],
+		end: [75.0, 0.0],
+		stops: vec![
+			GradientStop { offset: 0.0, color: Color::new(0.0, 0.5, 0.0, 1.0) },
+			GradientStop { offset: 1.0, color: Color::new(1.0, 1.0, 0.0, 0.5) },
+		],
+		spread: SpreadMethod::Reflect,
+		transform: Transform::IDENTITY,
+	};
+	let ring = Path::from_svg_path_data(
+		"M20 26 A18 18 0 0 1 56 26 A18 18 0 0 1 20 26 Z M26 26 A12 12 0 0 0 50 26 A12 12 0 0 0 26 26 Z",
+	)
+	.unwrap();
+	let mut ring = Draw::new(ring, Paint::LinearGradient(gradient));
+	ring.opacity = 0.8;
+	ring.blend_mode = BlendMode::Multiply;
+	scene.push(ring);
+	// thinner than a pixel, and along tile edges
+	let sliver = Path::start().move_to([15.0, 0.0]).line_to([15.3, 0.0]).line_to([31.0, 53.0]).line_to([30.0, 53.0]);
+	let mut sliver = Draw::new(sliver, Paint::Solid(Color::BLACK));
+	sliver.blend_mode = BlendMode::Xor;
+	scene.push(sliver);
+	let rect = common::rect(-1.0, 16.0, 47.0, 32.0);
+	scene.push(Draw::new(rect, Paint::Solid(Color::new(0.0, 0.0, 0.0, 0.25))));
+	for (center, blend_mode) in [([30.0, 40.0], BlendMode::Hue), ([60.0, 42.0], BlendMode::Screen)] {
+		let mut draw = Draw::new(common::star(center, 12.0), Paint::Solid(Color::new(0.1, 0.9, 0.6, 0.7)));
+		draw.blend_mode = blend_mode;
+		scene.push(draw);
+	}
+	scene
+}
+
+#[test]
+fn matches_jitter_backend() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	for format in [TextureFormat::Rgba8Unorm, TextureFormat::Rgba8UnormSrgb] {
+		let [jitter, tile] = render_both(&device, &queue, &edge_cases(), format);
+		// other than samples right on an edge, only rounding differs
+		let pixels = tile.data.chunks(4).zip(jitter.data.chunks(4));
+		let mismatched = pixels.filter(|(a, b)| a.iter().zip(*b).any(|(a, b)| a.abs_diff(*b) > 2)).count();
+		assert!(
+			mismatched as f32 <= TOLERANCE.max_mismatched * (tile.data.len() / 4) as f32,
+			"{:?}: {} pixels mismatch",
+			format,
+			mismatched
+		);
+	}
+
+	let corpus = FsPath::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+	for entry in fs::read_dir(corpus).unwrap() {
+		let svg = entry.unwrap().path();
+		if svg.extension().is_some_and(|extension| extension == "svg") {
+			let scene = Scene::from_svg(&fs::read_to_string(&svg).unwrap()).unwrap().scene;
+			let [jitter, tile] = render_both(&device, &queue, &scene, TextureFormat::Rgba8UnormSrgb);
+			let diff = tile.diff(&jitter, &TOLERANCE);
+			assert!(diff.is_within(&TOLERANCE), "{}: {} pixels mismatch", svg.display(), diff.mismatched);
+		}
+	}
+}
+
+#[test]
+fn matches_reference() {
+	let Some((device, queue)) = common::device() else {
+		return;
+	};
+	let (width, height) = (70, 45);
+	let format = TextureFormat::Rgba8Unorm;
+	let target = OffscreenTarget::new(&device, width, height, format);
+	let pipeline = TilePipeline::new(&device, format, [width, height]);
+	let paths = [
+		common::star([30.0, 25.0], 40.0),
+		Path::from_svg_path_data("M-5 3 Q60 -30 67 20 C20 60 10 10 2 43 Z M10 10 L30 12 L20 30 Z").unwrap(),
+	];
+	for path in paths {
+		for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+			let mut scene = Scene::new([width as f32, height as f32]);
+			let mut draw = Draw::new(path.clone(), Paint::Solid(Color::WHITE));
+			draw.fill_rule = fill_rule;
+			scene.push(draw);
+			let scene = TileSceneBufs::new(&device, &scene, &Transform::IDENTITY, format, [width, height]);
+			target.render_tiles(&device, &queue, &pipeline, &scene, wgpu::Color::BLACK);
+			let image = read(&device, &queue, &target);
+
+			let mask = CoverageMask::rasterize(&path, fill_rule, width, height, CoverageSampling::Jitter);
+			for y in 0..height {
+				for x in 0..width {
+					let expected = (mask.get(x, y) * 255.0).round();
+					let actual = image.pixel(x, y)[0] as f32;
+					assert!(
+						(actual - expected).abs() <= 1.0,
+						"{:?} at {}, {}: {} != {}",
+						fill_rule,
+						x,
+						y,
+						actual,
+						expected
+					);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,160 @@
+//! Run with `--features compute`. Checks the bins on the CPU, so it doesn't need an adapter.
+#![cfg(feature = "compute")]
+
+mod common;
+
+use std::f32::consts::PI;
+
+use nice_path_rasterizer::{
+	jitter_samples, Color, Draw, FillRule, Paint, Path, Scene, Segment, TileBins, Transform, TILE_SIZE,
+};
+
+const FLAG: u32 = 1 << 31;
+
+/// The sign of `value` like WGSL's `sign`, which is 0 for 0.
+fn sign(value: f32) -> i32 {
+	(value > 0.0) as i32 - (value < 0.0) as i32
+}
+
+/// Like `x_at` in tile.wgsl.
+fn x_at(piece: &Segment, y: f32) -> f32 {
+	let (from, ctrl, to, w) = match *piece {
+		Segment::Line(line) => {
+			let t = (y - line.from[1]) / (line.to[1] - line.from[1]);
+			return line.from[0] + (line.to[0] - line.from[0]) * t;
+		},
+		Segment::Quadratic(quad) => (quad.from, quad.ctrl, quad.to, 1.0),
+		Segment::Conic(conic) => (conic.from, conic.ctrl, conic.to, conic.weight),
+	};
+	let a = from[1] - 2.0 * w * ctrl[1] + to[1] + 2.0 * (w - 1.0) * y;
+	let b = 2.0 * (w * ctrl[1] - from[1]) - 2.0 * (w - 1.0) * y;
+	let c = from[1] - y;
+	let t = if a.abs() <= 1e-6 * (b.abs() + c.abs()) {
+		-c / b
+	} else {
+		let q = -0.5 * (b + if b >= 0.0 { 1.0 } else { -1.0 } * (b * b - 4.0 * a * c).max(0.0).sqrt());
+		let (t0, t1) = (q / a, if q == 0.0 { 0.0 } else { c / q });
+		if (t0 - 0.5).abs() < (t1 - 0.5).abs() {
+			t0
+		} else {
+			t1
+		}
+	};
+	let t = t.clamp(0.0, 1.0);
+	let s = 1.0 - t;
+	let b1 = 2.0 * w * s * t;
+	(s * s * from[0] + b1 * ctrl[0] + t * t * to[0]) / (s * s + b1 + t * t)
+}
+
+/// Like `winding` in tile.wgsl.
+fn winding(piece: &Segment, crosses_left: bool, point: [f32; 2]) -> i32 {
+	let (from, to) = (piece.from(), piece.to());
+	let down = sign(to[1] - from[1]);
+	let (y_min, y_max) = (from[1].min(to[1]), from[1].max(to[1]));
+	let within = point[1] >= y_min && point[1] < y_max;
+	let mut w = 0;
+	if within && x_at(piece, point[1]) <= point[0] {
+		w = down;
+	}
+	if crosses_left {
+		let right = sign(to[0] - from[0]);
+		if within {
+			w += (right - down) / 2;
+		} else if point[1] < y_min {
+			w += right;
+		}
+	}
+	w
+}
+
+/// Paths that cross tiles from above, below and either side, run off every edge of the view, fill whole tiles without
+/// any pieces in them, and have conics, quadratics and a transform.
+fn scene() -> Scene {
+	let mut scene = Scene::new([75.0, 53.0]);
+	let paint = Paint::Solid(Color::new(0.2, 0.4, 0.8, 1.0));
+	scene.push(Draw::new(common::star([37.0, 30.0], 36.0), paint.clone()));
+	let mut even_odd = Draw::new(common::star([60.0, 10.0], 25.0), paint.clone());
+	even_odd.fill_rule = FillRule::EvenOdd;
+	scene.push(even_odd);
+	let ring = Path::from_svg_path_data(
+		"M-15 45 A20 20 0 0 1 25 45 A20 20 0 0 1 -15 45 Z M-5 45 A10 10 0 0 0 15 45 A10 10 0 0 0 -5 45 Z",
+	)
+	.unwrap();
+	scene.push(Draw::new(ring, paint.clone()));
+	let curved = Path::from_svg_path_data("M-10 -8 Q40 60 85 5 Q60 30 20 50 Z").unwrap();
+	let mut curved = Draw::new(curved, paint.clone());
+	curved.transform = Transform::rotate(PI / 12.0).then(&Transform::translate(4.0, -3.0));
+	scene.push(curved);
+	scene.push(Draw::new(common::rect(3.0, 10.0, 70.0, 50.0), paint.clone()));
+	// with edges just left of tiles, between them and the first samples of their pixels
+	scene.push(Draw::new(common::rect(15.7, 3.0, 47.8, 60.0), paint.clone()));
+	// off the view, so it isn't binned at all
+	scene.push(Draw::new(common::rect(80.0, 0.0, 90.0, 10.0), paint));
+	scene
+}
+
+#[test]
+fn bins_wind_like_the_paths() {
+	let scene = scene();
+	let [width, height] = [75, 53];
+	let bins = TileBins::new(&scene, &Transform::IDENTITY, [width, height]);
+	assert_eq!(bins.tiles, [5, 4]);
+	assert_eq!(bins.draws, [0, 1, 2, 3, 4, 5]);
+
+	let (mut flagged, mut backdrop_only, mut checked) = (0, 0, 0);
+	for (draw_index, draw) in scene.draws.iter().enumerate() {
+		let path = draw.path.transform(&draw.transform);
+		let (index, outline) = (path.hit_test_index(), path.monotonic_pieces());
+		let paint = bins.draws.iter().position(|&i| i == draw_index);
+		for y in 0..height {
+			for x in 0..width {
+				let tile = (y / TILE_SIZE * bins.tiles[0] + x / TILE_SIZE) as usize;
+				let [first_draw, draw_count] = [bins.bins[2 * tile], bins.bins[2 * tile + 1]].map(|i| i as usize);
+				let tile_draw = (first_draw..first_draw + 4 * draw_count)
+					.step_by(4)
+					.find(|&i| Some((bins.bins[i] & 0xffffff) as usize) == paint);
+				for sample in jitter_samples(x, y) {
+					// points right on the outline can go either way
+					if outline.iter().any(|piece| {
+						let p = piece.nearest_point(sample);
+						(p[0] - sample[0]).hypot(p[1] - sample[1]) < 1e-3
+					}) {
+						continue;
+					}
+					let inside = match tile_draw {
+						Some(i) => {
+							let fill_rule = match bins.bins[i] & FLAG {
+								0 => FillRule::NonZero,
+								_ => FillRule::EvenOdd,
+							};
+							assert_eq!(fill_rule, draw.fill_rule);
+							let (backdrop, first_piece, piece_count) =
+								(bins.bins[i + 1] as i32, bins.bins[i + 2] as usize, bins.bins[i + 3] as usize);
+							let piece_refs = &bins.bins[first_piece..first_piece + piece_count];
+							let w = piece_refs.iter().fold(backdrop, |w, &piece_ref| {
+								let piece = &bins.pieces[(piece_ref & !FLAG) as usize];
+								w + winding(piece, piece_ref & FLAG != 0, sample)
+							});
+							fill_rule.is_inside(w)
+						},
+						None => false,
+					};
+					assert_eq!(inside, index.contains(sample, draw.fill_rule), "draw {} at {:?}", draw_index, sample);
+					checked += 1;
+				}
+			}
+		}
+	}
+	// every case the bins have was checked
+	for tile in 0..(bins.tiles[0] * bins.tiles[1]) as usize {
+		let (first_draw, draw_count) = (bins.bins[2 * tile] as usize, bins.bins[2 * tile + 1] as usize);
+		for i in (first_draw..first_draw + 4 * draw_count).step_by(4) {
+			let piece_refs = &bins.bins[bins.bins[i + 2] as usize..][..bins.bins[i + 3] as usize];
+			flagged += piece_refs.iter().filter(|&&piece_ref| piece_ref & FLAG != 0).count();
+			backdrop_only += piece_refs.is_empty() as usize;
+		}
+	}
+	assert!(flagged > 0 && backdrop_only > 0 && checked > 0);
+	assert!(bins.pieces.iter().any(|piece| matches!(piece, Segment::Conic(_))));
+	assert!(bins.pieces.iter().any(|piece| matches!(piece, Segment::Quadratic(_))));
+}
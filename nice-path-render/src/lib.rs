@@ -0,0 +1,221 @@
+use std::{fs, path::PathBuf};
+
+use nice_path_rasterizer::{
+	request_headless_device, Color, Draw, OffscreenTarget, Paint, Path, PathPipelines, RasterBackend, RgbaImage, Scene,
+	SceneBufs, Transform,
+};
+use wgpu::{Maintain, TextureFormat};
+
+pub const HELP: &str = "\
+Renders an SVG document, or SVG path data, to a PNG.
+
+USAGE:
+  nice-path-render [OPTIONS] <INPUT> -o <OUTPUT>
+
+ARGS:
+  <INPUT>                An SVG file, or path data with --path
+
+OPTIONS:
+  -o, --output <FILE>    Where to write the PNG
+  --path                 Read <INPUT> as the `d` attribute of a <path>, filled and framed by its bounds
+  --fill <COLOR>         The fill of --path [default: black]
+  -w, --width <PIXELS>   Fits the image to this width, keeping the aspect ratio unless --height is given too
+  -h, --height <PIXELS>  Fits the image to this height, keeping the aspect ratio unless --width is given too
+  -s, --scale <FACTOR>   Scales the image, after fitting it to --width or --height [default: 1]
+  -b, --background <COLOR>
+                         A CSS colour to draw on [default: transparent]
+  -q, --quality <1-4>    Supersamples every pixel this many times along each axis, on top of the rasterizer's own
+                         6 samples [default: 1]
+  --backend <BACKEND>    How coverage is computed, `jitter` or `stencil-cover` [default: jitter]
+  --help                 Prints this
+";
+
+/// The format the image is rendered in. Blending happens in linear light, and the pixels are read back premultiplied.
+const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+const MAX_QUALITY: u32 = 4;
+
+#[derive(Debug)]
+pub struct Args {
+	pub input: String,
+	pub output: PathBuf,
+	pub path_data: bool,
+	pub fill: Color,
+	pub width: Option<u32>,
+	pub height: Option<u32>,
+	pub scale: f32,
+	pub background: Color,
+	pub quality: u32,
+	pub backend: RasterBackend,
+}
+
+/// `None` if the help was asked for.
+pub fn parse_args(mut pico: pico_args::Arguments) -> Result<Option<Args>, String> {
+	if pico.contains("--help") {
+		return Ok(None);
+	}
+	let color = |text: &str| Color::from_css(text).ok_or_else(|| format!("invalid colour {:?}", text));
+	let args = Args {
+		output: pico.value_from_str(["-o", "--output"]).map_err(|err| err.to_string())?,
+		path_data: pico.contains("--path"),
+		fill: pico.opt_value_from_fn("--fill", color).map_err(|err| err.to_string())?.unwrap_or(Color::BLACK),
+		width: pico.opt_value_from_str(["-w", "--width"]).map_err(|err| err.to_string())?,
+		height: pico.opt_value_from_str(["-h", "--height"]).map_err(|err| err.to_string())?,
+		scale: pico.opt_value_from_str(["-s", "--scale"]).map_err(|err| err.to_string())?.unwrap_or(1.0),
+		background: pico
+			.opt_value_from_fn(["-b", "--background"], color)
+			.map_err(|err| err.to_string())?
+			.unwrap_or(Color::TRANSPARENT),
+		quality: pico.opt_value_from_str(["-q", "--quality"]).map_err(|err| err.to_string())?.unwrap_or(1),
+		backend: pico.opt_value_from_fn("--backend", backend).map_err(|err| err.to_string())?.unwrap_or_default(),
+		input: pico.free_from_str().map_err(|err| err.to_string())?,
+	};
+	let remaining = pico.finish();
+	if !remaining.is_empty() {
+		return Err(format!("unexpected arguments {:?}", remaining));
+	}
+
+	if !(1..=MAX_QUALITY).contains(&args.quality) {
+		return Err(format!("the quality must be between 1 and {}", MAX_QUALITY));
+	}
+	if !(args.scale > 0.0 && args.scale.is_finite()) {
+		return Err("the scale must be positive".to_string());
+	}
+	if args.width == Some(0) || args.height == Some(0) {
+		return Err("the size must be positive".to_string());
+	}
+	Ok(Some(args))
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+	let scene = match args.path_data {
+		true => path_scene(&args.input, args.fill)?,
+		false => {
+			let text = fs::read_to_string(&args.input).map_err(|err| format!("can't read {}: {}", args.input, err))?;
+			let import = Scene::from_svg(&text).map_err(|err| err.to_string())?;
+			for unsupported in &import.unsupported {
+				eprintln!("warning: unsupported {}", unsupported);
+			}
+			import.scene
+		},
+	};
+
+	let render_size = render_size(scene.size, args)?;
+	let size = render_size.map(|length| length / args.quality);
+
+	let (device, queue) = pollster::block_on(request_headless_device()).ok_or("no graphics adapter")?;
+	let max_size = device.limits().max_texture_dimension_2d;
+	if render_size[0] > max_size || render_size[1] > max_size {
+		return Err(format!(
+			"{}x{} pixels at quality {} is more than the {} pixels the adapter can render along each axis",
+			size[0], size[1], args.quality, max_size
+		));
+	}
+
+	let to_view = Transform::scale(render_size[0] as f32 / scene.size[0], render_size[1] as f32 / scene.size[1]);
+	let target = OffscreenTarget::new(&device, render_size[0], render_size[1], FORMAT);
+	let pipelines = PathPipelines::with_backend(&device, FORMAT, render_size, args.backend);
+	let scene_bufs = SceneBufs::with_backend(&device, &scene, &to_view, FORMAT, args.backend);
+	target.render(&device, &queue, &pipelines, &scene_bufs, clear_color(args.background));
+	let image = target.read_rgba(&device, &queue);
+	device.poll(Maintain::Wait);
+	let image = pollster::block_on(image).map_err(|err| format!("can't read the image back: {}", err))?;
+
+	resolve(&image, args.quality)
+		.save_png(&args.output)
+		.map_err(|err| format!("can't write {}: {}", args.output.display(), err))
+}
+
+/// The size `scene_size` is rendered at: fitted to the requested size, scaled, then supersampled.
+pub fn render_size(scene_size: [f32; 2], args: &Args) -> Result<[u32; 2], String> {
+	let [scene_width, scene_height] = scene_size;
+	let fitted = match (args.width, args.height) {
+		(Some(width), Some(height)) => [width as f32, height as f32],
+		(Some(width), None) => [width as f32, width as f32 * scene_height / scene_width],
+		(None, Some(height)) => [height as f32 * scene_width / scene_height, height as f32],
+		(None, None) => scene_size,
+	};
+	// a scene with no width or height can't be fitted or framed
+	if !fitted.iter().all(|length| length.is_finite() && *length > 0.0) {
+		return Err(format!("can't fit the {}x{} scene to a visible size", scene_width, scene_height));
+	}
+	let too_big =
+		|| format!("{}x{} at scale {} and quality {} is too big", fitted[0], fitted[1], args.scale, args.quality);
+	let mut render_size = [0; 2];
+	for (render_length, length) in render_size.iter_mut().zip(fitted) {
+		let scaled = (length * args.scale).ceil().max(1.0);
+		if scaled > u32::MAX as f32 {
+			return Err(too_big());
+		}
+		*render_length = (scaled as u32).checked_mul(args.quality).ok_or_else(too_big)?;
+	}
+	Ok(render_size)
+}
+
+fn backend(name: &str) -> Result<RasterBackend, String> {
+	match name {
+		"jitter" => Ok(RasterBackend::Jitter),
+		"stencil-cover" => Ok(RasterBackend::StencilCover),
+		_ => Err(format!("unknown backend {:?}", name)),
+	}
+}
+
+/// A scene that fills the path, framed by its bounds.
+fn path_scene(d: &str, fill: Color) -> Result<Scene, String> {
+	let path = Path::from_svg_path_data(d).map_err(|err| err.to_string())?;
+	let bounds = path.bounds().ok_or("the path is empty")?;
+	let mut scene =
+		Scene::new([bounds.right_bottom[0] - bounds.left_top[0], bounds.right_bottom[1] - bounds.left_top[1]]);
+	let mut draw = Draw::new(path, Paint::Solid(fill));
+	draw.transform = Transform::translate(-bounds.left_top[0], -bounds.left_top[1]);
+	scene.push(draw);
+	Ok(scene)
+}
+
+/// Clear colours are linear and, since the target is blended premultiplied, premultiplied.
+fn clear_color(color: Color) -> wgpu::Color {
+	let [r, g, b] = [color.r, color.g, color.b].map(|c| srgb_to_linear(c) as f64 * color.a as f64);
+	wgpu::Color { r, g, b, a: color.a as f64 }
+}
+
+/// Averages every `quality` by `quality` block of pixels in linear light, and undoes the premultiplication, since PNGs
+/// have straight alpha. Pixels left over past the last whole block are dropped.
+pub fn resolve(image: &RgbaImage, quality: u32) -> RgbaImage {
+	let (width, height) = (image.width / quality, image.height / quality);
+	let mut data = Vec::with_capacity((width * height * 4) as usize);
+	let samples = (quality * quality) as f32;
+	for y in 0..height {
+		for x in 0..width {
+			let mut sum = [0.0f32; 4];
+			for sy in 0..quality {
+				for sx in 0..quality {
+					let pixel = image.pixel(x * quality + sx, y * quality + sy);
+					for c in 0..3 {
+						sum[c] += srgb_to_linear(pixel[c] as f32 / 255.0);
+					}
+					sum[3] += pixel[3] as f32 / 255.0;
+				}
+			}
+			let alpha = sum[3] / samples;
+			let rgb = [0, 1, 2].map(|c| match alpha > 0.0 {
+				true => linear_to_srgb((sum[c] / samples / alpha).min(1.0)),
+				false => 0.0,
+			});
+			data.extend([rgb[0], rgb[1], rgb[2], alpha].map(|c| (c * 255.0).round() as u8));
+		}
+	}
+	RgbaImage { width, height, data }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+	match c <= 0.04045 {
+		true => c / 12.92,
+		false => ((c + 0.055) / 1.055).powf(2.4),
+	}
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+	match c <= 0.0031308 {
+		true => c * 12.92,
+		false => 1.055 * c.powf(1.0 / 2.4) - 0.055,
+	}
+}
@@ -0,0 +1,21 @@
+use std::process::exit;
+
+use nice_path_render::{parse_args, run, HELP};
+
+fn main() {
+	let args = match parse_args(pico_args::Arguments::from_env()) {
+		Ok(Some(args)) => args,
+		Ok(None) => {
+			print!("{}", HELP);
+			return;
+		},
+		Err(err) => {
+			eprintln!("error: {}\n\n{}", err, HELP);
+			exit(2);
+		},
+	};
+	if let Err(err) = run(&args) {
+		eprintln!("error: {}", err);
+		exit(1);
+	}
+}
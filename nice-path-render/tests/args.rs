@@ -0,0 +1,119 @@
+use nice_path_rasterizer::{Color, RasterBackend, RgbaImage};
+use nice_path_render::{parse_args, render_size, resolve, Args};
+
+fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+	parse_args(pico_args::Arguments::from_vec(args.iter().map(Into::into).collect()))
+}
+
+fn args(args: &[&str]) -> Args {
+	parse(args).unwrap().unwrap()
+}
+
+#[test]
+fn parses_arguments() {
+	let parsed = args(&[
+		"--path",
+		"M0 0 L10 10 L0 10",
+		"-o",
+		"out.png",
+		"--fill",
+		"red",
+		"-w",
+		"30",
+		"-s",
+		"2",
+		"-b",
+		"#00f",
+		"-q",
+		"3",
+		"--backend",
+		"stencil-cover",
+	]);
+	assert_eq!(parsed.input, "M0 0 L10 10 L0 10");
+	assert_eq!(parsed.output.to_str(), Some("out.png"));
+	assert!(parsed.path_data);
+	assert_eq!(parsed.fill, Color::new(1.0, 0.0, 0.0, 1.0));
+	assert_eq!((parsed.width, parsed.height), (Some(30), None));
+	assert_eq!(parsed.scale, 2.0);
+	assert_eq!(parsed.background, Color::new(0.0, 0.0, 1.0, 1.0));
+	assert_eq!(parsed.quality, 3);
+	assert_eq!(parsed.backend, RasterBackend::StencilCover);
+
+	let defaults = args(&["in.svg", "--output", "out.png"]);
+	assert!(!defaults.path_data);
+	assert_eq!(defaults.fill, Color::BLACK);
+	assert_eq!((defaults.width, defaults.height, defaults.scale, defaults.quality), (None, None, 1.0, 1));
+	assert_eq!(defaults.background, Color::TRANSPARENT);
+	assert_eq!(defaults.backend, RasterBackend::Jitter);
+
+	assert!(parse(&["--help"]).unwrap().is_none());
+}
+
+#[test]
+fn rejects_bad_arguments() {
+	for bad in [
+		&["in.svg"][..],
+		&["-o", "out.png"],
+		&["in.svg", "-o", "out.png", "extra"],
+		&["in.svg", "-o", "out.png", "--fill", "not a colour"],
+		&["in.svg", "-o", "out.png", "-q", "0"],
+		&["in.svg", "-o", "out.png", "-q", "5"],
+		&["in.svg", "-o", "out.png", "-s", "0"],
+		&["in.svg", "-o", "out.png", "-s", "inf"],
+		&["in.svg", "-o", "out.png", "-s", "NaN"],
+		&["in.svg", "-o", "out.png", "-w", "0"],
+		&["in.svg", "-o", "out.png", "--backend", "tiles"],
+	] {
+		assert!(parse(bad).is_err(), "{:?}", bad);
+	}
+}
+
+#[test]
+fn fits_the_render_size() {
+	let size = |scene_size: [f32; 2], extra: &[&str]| {
+		let mut all = vec!["in.svg", "-o", "out.png"];
+		all.extend(extra);
+		render_size(scene_size, &args(&all))
+	};
+	assert_eq!(size([10.5, 20.0], &[]), Ok([11, 20]));
+	assert_eq!(size([10.0, 20.0], &["-w", "30"]), Ok([30, 60]));
+	assert_eq!(size([10.0, 20.0], &["-h", "30"]), Ok([15, 30]));
+	assert_eq!(size([10.0, 20.0], &["-w", "30", "-h", "30"]), Ok([30, 30]));
+	assert_eq!(size([10.0, 20.0], &["-h", "30", "-s", "0.5", "-q", "4"]), Ok([32, 60]));
+	// a sliver still gets a pixel
+	assert_eq!(size([10.0, 0.01], &[]), Ok([10, 1]));
+
+	// flat scenes have no aspect ratio or size to fit
+	assert!(size([10.0, 0.0], &["-h", "10"]).is_err());
+	assert!(size([10.0, 0.0], &["-w", "10"]).is_err());
+	assert!(size([10.0, 0.0], &[]).is_err());
+	// too big to count the pixels of, before or after supersampling
+	assert!(size([10.0, 10.0], &["-s", "1e9"]).is_err());
+	assert!(size([10.0, 10.0], &["-s", "3e8", "-q", "4"]).is_err());
+	assert!(size([10.0, 10.0], &["-s", "1e38", "-q", "2"]).is_err());
+}
+
+#[test]
+fn resolves_supersamples() {
+	// opaque white, opaque black, and transparent pixels, premultiplied
+	let image = RgbaImage {
+		width: 3,
+		height: 2,
+		data: vec![
+			255, 255, 255, 255, 0, 0, 0, 255, 9, 9, 9, 9, //
+			0, 0, 0, 0, 0, 0, 0, 0, 9, 9, 9, 9,
+		],
+	};
+	let resolved = resolve(&image, 2);
+	// the leftover column is dropped
+	assert_eq!((resolved.width, resolved.height), (1, 1));
+	// half covered, and half of that is white, which is about 0.735 in sRGB
+	assert_eq!(resolved.pixel(0, 0), [188, 188, 188, 128]);
+
+	let unchanged = resolve(&image, 1);
+	assert_eq!((unchanged.width, unchanged.height), (3, 2));
+	assert_eq!(unchanged.pixel(0, 0), [255, 255, 255, 255]);
+	assert_eq!(unchanged.pixel(1, 0), [0, 0, 0, 255]);
+	// nothing covered comes out transparent black
+	assert_eq!(unchanged.pixel(0, 1), [0, 0, 0, 0]);
+}
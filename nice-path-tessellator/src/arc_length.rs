@@ -0,0 +1,133 @@
+use crate::{
+	geom::{length, scale, sub, Segment},
+	offset::push_segments,
+	Path,
+};
+
+impl Path {
+	/// The point `distance` along the path, measured like [`Path::length`]. Distances outside `0..=length` are clamped
+	/// to the ends. `None` if the path has no segments.
+	///
+	/// This measures the whole path on every call. For repeated queries, build an [`ArcLengthTable`] with
+	/// [`Path::arc_length_table`] once and query that instead.
+	pub fn point_at_length(&self, distance: f32) -> Option<[f32; 2]> {
+		self.arc_length_table().point_at_length(distance)
+	}
+
+	/// The unit direction of travel `distance` along the path. Where two segments meet, this is the direction the
+	/// earlier one arrives in. Like [`Path::point_at_length`], this measures the whole path each time.
+	pub fn tangent_at_length(&self, distance: f32) -> Option<[f32; 2]> {
+		self.arc_length_table().tangent_at_length(distance)
+	}
+
+	/// Cuts the path in two, `distance` along it. The subpath that's cut ends the first path and starts the second, and
+	/// quadratics and conics that are cut stay quadratics and conics. Like [`Path::point_at_length`], this measures the whole path each time.
+	pub fn split_at_length(&self, distance: f32) -> (Path, Path) {
+		self.arc_length_table().split_at_length(distance)
+	}
+
+	/// Builds an [`ArcLengthTable`] for answering many arc length queries against this path, without measuring it again
+	/// for each one.
+	pub fn arc_length_table(&self) -> ArcLengthTable {
+		ArcLengthTable::new(self)
+	}
+}
+
+/// The path's segments with their cumulative arc lengths, so queries only have to search for the right segment
+/// instead of measuring every segment before it. Worth building when walking along a long path, e.g. to animate
+/// something along it.
+#[derive(Clone, Debug)]
+pub struct ArcLengthTable {
+	/// every explicit segment, in order
+	segments: Vec<Segment>,
+	/// the distance along the path at the end of each segment
+	ends: Vec<f32>,
+	/// the index of the first segment of each subpath
+	contour_starts: Vec<usize>,
+}
+impl ArcLengthTable {
+	pub fn new(path: &Path) -> Self {
+		let mut segments = vec![];
+		let mut ends = vec![];
+		let mut contour_starts = vec![];
+		let mut total = 0.0;
+		for contour in path.contours() {
+			contour_starts.push(segments.len());
+			for seg in contour.segments {
+				total += seg.length();
+				segments.push(seg);
+				ends.push(total);
+			}
+		}
+		Self { segments, ends, contour_starts }
+	}
+
+	/// The same as [`Path::length`].
+	pub fn length(&self) -> f32 {
+		self.ends.last().copied().unwrap_or(0.0)
+	}
+
+	/// See [`Path::point_at_length`].
+	pub fn point_at_length(&self, distance: f32) -> Option<[f32; 2]> {
+		let (i, t) = self.locate(distance)?;
+		Some(self.segments[i].eval(t))
+	}
+
+	/// See [`Path::tangent_at_length`].
+	pub fn tangent_at_length(&self, distance: f32) -> Option<[f32; 2]> {
+		let (i, t) = self.locate(distance)?;
+		let seg = &self.segments[i];
+		let mut d = seg.derivative(t);
+		if d == [0.0, 0.0] {
+			// a curve whose control point is on an endpoint stops there, but still has a direction
+			d = sub(seg.to(), seg.from());
+		}
+		let len = length(d);
+		Some(if len == 0.0 { [0.0, 0.0] } else { scale(d, 1.0 / len) })
+	}
+
+	/// See [`Path::split_at_length`].
+	pub fn split_at_length(&self, distance: f32) -> (Path, Path) {
+		let (cut, t) = match self.locate(distance) {
+			Some(location) => location,
+			None => return (Path::start(), Path::start()),
+		};
+		let (head, tail) = self.segments[cut].split(t);
+
+		let mut first = Path::start();
+		let mut second = Path::start();
+		for (c, &start) in self.contour_starts.iter().enumerate() {
+			let end = self.contour_starts.get(c + 1).copied().unwrap_or(self.segments.len());
+			let segs = &self.segments[start..end];
+			if end <= cut {
+				first = push_segments(first.move_to(segs[0].from()), segs);
+			} else if start > cut {
+				second = push_segments(second.move_to(segs[0].from()), segs);
+			} else {
+				let local = cut - start;
+				first = push_segments(first.move_to(segs[0].from()), &segs[..local]);
+				// cutting right at a vertex would leave a piece without length
+				if head.from() != head.to() {
+					first = push_segments(first, &[head]);
+				}
+				second = second.move_to(tail.from());
+				if tail.from() != tail.to() {
+					second = push_segments(second, &[tail]);
+				}
+				second = push_segments(second, &segs[local + 1..]);
+			}
+		}
+		(first, second)
+	}
+
+	/// The segment `distance` along the path, and the parameter on it.
+	fn locate(&self, distance: f32) -> Option<(usize, f32)> {
+		if self.segments.is_empty() {
+			return None;
+		}
+		let distance = distance.clamp(0.0, self.length());
+		let i = self.ends.partition_point(|&end| end < distance).min(self.segments.len() - 1);
+		let start = if i == 0 { 0.0 } else { self.ends[i - 1] };
+		Some((i, self.segments[i].t_at_length(distance - start)))
+	}
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use crate::{
+	geom::{intersect_segments, length, sub, Segment},
+	Bounds, FillRule, Path,
+};
+
+/// How two shapes are combined by [`Path::boolean`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+	Union,
+	Intersection,
+	/// The first shape minus the second.
+	Difference,
+	Xor,
+}
+impl BooleanOp {
+	fn apply(self, a: bool, b: bool) -> bool {
+		match self {
+			BooleanOp::Union => a || b,
+			BooleanOp::Intersection => a && b,
+			BooleanOp::Difference => a && !b,
+			BooleanOp::Xor => a != b,
+		}
+	}
+}
+
+impl Path {
+	/// Everything inside either path. Both paths are read with `fill_rule`.
+	pub fn union(&self, other: &Path, fill_rule: FillRule) -> Path {
+		self.boolean(other, BooleanOp::Union, [fill_rule; 2])
+	}
+
+	/// Everything inside both paths. Both paths are read with `fill_rule`.
+	pub fn intersect(&self, other: &Path, fill_rule: FillRule) -> Path {
+		self.boolean(other, BooleanOp::Intersection, [fill_rule; 2])
+	}
+
+	/// Everything inside this path but not `other`. Both paths are read with `fill_rule`.
+	pub fn difference(&self, other: &Path, fill_rule: FillRule) -> Path {
+		self.boolean(other, BooleanOp::Difference, [fill_rule; 2])
+	}
+
+	/// Everything inside exactly one of the paths. Both paths are read with `fill_rule`.
+	pub fn xor(&self, other: &Path, fill_rule: FillRule) -> Path {
+		self.boolean(other, BooleanOp::Xor, [fill_rule; 2])
+	}
+
+	/// Combines this path with `other`, reading each with its own fill rule. Lines, quadratics and conics are split at
+	/// their intersections and kept as they are, so nothing is flattened or approximated.
+	///
+	/// The result has no self-intersections, outlines wind clockwise and holes counter-clockwise, so it fills the same
+	/// with either fill rule.
+	pub fn boolean(&self, other: &Path, op: BooleanOp, fill_rules: [FillRule; 2]) -> Path {
+		let indexes = [self.hit_test_index(), other.hit_test_index()];
+		resolve(&[self, other], |point| {
+			let a = fill_rules[0].is_inside(indexes[0].winding_number(point));
+			let b = fill_rules[1].is_inside(indexes[1].winding_number(point));
+			op.apply(a, b)
+		})
+	}
+
+	/// Whether the path is at most one contour that doesn't cross or touch itself, so its winding number is never more
+	/// than 1 either way and it fills the same with either fill rule.
+	pub(crate) fn is_simple(&self) -> bool {
+		let contours = self.contours();
+		let contour = match contours.as_slice() {
+			[] => return true,
+			[contour] => contour,
+			_ => return false,
+		};
+		let eps = match self.control_bounds() {
+			Some(path_bounds) => snap_tolerance(&path_bounds),
+			None => return true,
+		};
+		// like `resolve`, which also leaves out the rounding errors that keep some contours from quite closing
+		let segs: Vec<Segment> = contour.closed_segments().filter(|seg| seg.length() > eps).collect();
+		let bounds: Vec<Bounds> = segs.iter().map(|seg| seg.control_bounds()).collect();
+
+		let mut order: Vec<usize> = (0..segs.len()).collect();
+		order.sort_by(|&a, &b| bounds[a].left_top[0].total_cmp(&bounds[b].left_top[0]));
+		for (i, &a) in order.iter().enumerate() {
+			for &b in &order[i + 1..] {
+				if bounds[b].left_top[0] > bounds[a].right_bottom[0] + eps {
+					break;
+				}
+				if !bounds[a].overlaps(&bounds[b], eps) {
+					continue;
+				}
+				// consecutive segments meet where one ends and the next starts, which isn't a crossing
+				let (first, second) = (a.min(b), a.max(b));
+				let mut shared = vec![];
+				if second == first + 1 {
+					shared.push(segs[second].from());
+				}
+				if first == 0 && second == segs.len() - 1 {
+					shared.push(segs[first].from());
+				}
+				let crosses = intersect_segments(&segs[a], &segs[b], eps)
+					.into_iter()
+					.any(|hit| shared.iter().all(|&vertex| length(sub(hit.point, vertex)) > eps));
+				if crosses {
+					return false;
+				}
+			}
+		}
+		true
+	}
+}
+
+/// How close points have to be to be snapped together. f32 only has about 7 significant digits, so it has to scale
+/// with the coordinates.
+fn snap_tolerance(bounds: &Bounds) -> f32 {
+	let extent = [bounds.left_top, bounds.right_bottom].iter().flatten().fold(0.0f32, |max, v| max.max(v.abs()));
+	(extent * 1e-5).max(1e-6)
+}
+
+/// A segment piece between two snapped vertices.
+struct Piece {
+	seg: Segment,
+	from: usize,
+	to: usize,
+}
+
+/// Rebuilds the outline of the region where `inside` holds, out of the segments of `paths`. `inside` must only change
+/// across those segments.
+///
+/// Every segment is split where it meets another, the pieces that have `inside` on exactly one side are kept and
+/// oriented with the inside on their right (clockwise on screen), and then they're chained back into contours.
+pub(crate) fn resolve(paths: &[&Path], inside: impl Fn([f32; 2]) -> bool) -> Path {
+	let mut segs: Vec<Segment> = vec![];
+	let mut bounds: Option<Bounds> = None;
+	for path in paths {
+		for contour in path.contours() {
+			for seg in contour.closed_segments() {
+				let seg_bounds = seg.control_bounds();
+				match &mut bounds {
+					Some(bounds) => {
+						bounds.include(seg_bounds.left_top);
+						bounds.include(seg_bounds.right_bottom);
+					},
+					None => bounds = Some(seg_bounds),
+				}
+				segs.push(seg);
+			}
+		}
+	}
+	let bounds = match bounds {
+		Some(bounds) => bounds,
+		None => return Path::start(),
+	};
+	let eps = snap_tolerance(&bounds);
+	segs.retain(|seg| seg.length() > eps);
+
+	// find where every segment needs to be split
+	let mut splits: Vec<Vec<(f32, [f32; 2])>> = vec![vec![]; segs.len()];
+	let seg_bounds: Vec<Bounds> = segs.iter().map(|seg| seg.control_bounds()).collect();
+	let mut order: Vec<usize> = (0..segs.len()).collect();
+	order.sort_by(|&a, &b| seg_bounds[a].left_top[0].total_cmp(&seg_bounds[b].left_top[0]));
+	for (i, &a) in order.iter().enumerate() {
+		for &b in &order[i + 1..] {
+			if seg_bounds[b].left_top[0] > seg_bounds[a].right_bottom[0] + eps {
+				break;
+			}
+			if !seg_bounds[a].overlaps(&seg_bounds[b], eps) {
+				continue;
+			}
+			for hit in intersect_segments(&segs[a], &segs[b], eps) {
+				splits[a].push((hit.t_a, hit.point));
+				splits[b].push((hit.t_b, hit.point));
+			}
+		}
+	}
+
+	// split them, snapping the endpoints of the pieces onto shared vertices
+	let mut verts = VertexMap::new(eps);
+	let mut pieces = vec![];
+	for (seg, mut seg_splits) in segs.into_iter().zip(splits) {
+		seg_splits.sort_by(|a, b| a.0.total_cmp(&b.0));
+		let (mut from, mut from_t) = (seg.from(), 0.0);
+		for (t, point) in seg_splits {
+			if length(sub(point, from)) <= eps || length(sub(point, seg.to())) <= eps {
+				continue;
+			}
+			pieces.push(seg.slice(from_t, t).with_endpoints(from, point));
+			from = point;
+			from_t = t;
+		}
+		pieces.push(seg.slice(from_t, 1.0).with_endpoints(from, seg.to()));
+	}
+	let mut pieces: Vec<Piece> = pieces
+		.into_iter()
+		.filter_map(|seg| {
+			let from = verts.insert(seg.from());
+			let to = verts.insert(seg.to());
+			(from != to).then(|| Piece { seg: seg.with_endpoints(verts.points[from], verts.points[to]), from, to })
+		})
+		.collect();
+
+	// keep the pieces on the boundary of the result, with the inside on their right
+	let offset = eps * 2.0;
+	pieces.retain_mut(|piece| {
+		let mid = piece.seg.eval(0.5);
+		let d = piece.seg.derivative(0.5);
+		let len = length(d);
+		if len == 0.0 {
+			return false;
+		}
+		let normal = [-d[1] / len * offset, d[0] / len * offset];
+		let right = inside([mid[0] + normal[0], mid[1] + normal[1]]);
+		let left = inside([mid[0] - normal[0], mid[1] - normal[1]]);
+		if right == left {
+			return false;
+		}
+		if left {
+			piece.seg = piece.seg.reversed();
+			std::mem::swap(&mut piece.from, &mut piece.to);
+		}
+		true
+	});
+
+	// coincident segments from different inputs are classified the same way, so only keep one of them
+	let mut seen: HashMap<(usize, usize), Vec<[f32; 2]>> = HashMap::new();
+	pieces.retain(|piece| {
+		let mid = piece.seg.eval(0.5);
+		let mids = seen.entry((piece.from, piece.to)).or_default();
+		let duplicate = mids.iter().any(|&seen_mid| length(sub(seen_mid, mid)) <= eps * 4.0);
+		if !duplicate {
+			mids.push(mid);
+		}
+		!duplicate
+	});
+
+	// chain the pieces into contours
+	let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+	for (i, piece) in pieces.iter().enumerate() {
+		outgoing.entry(piece.from).or_default().push(i);
+	}
+	let mut used = vec![false; pieces.len()];
+	let mut path = Path::start();
+	for first in 0..pieces.len() {
+		if used[first] {
+			continue;
+		}
+		path = path.move_to(verts.points[pieces[first].from]);
+		let mut current = first;
+		loop {
+			used[current] = true;
+			let piece = &pieces[current];
+			path = match piece.seg {
+				Segment::Line(line) => path.line_to(line.to),
+				Segment::Quadratic(quad) => path.quadratic_curve_to(quad.ctrl, quad.to),
+				Segment::Conic(conic) => path.conic_to(conic.ctrl, conic.to, conic.weight),
+			};
+			if piece.to == pieces[first].from {
+				break;
+			}
+			match outgoing.get(&piece.to).and_then(|next| next.iter().copied().find(|&i| !used[i])) {
+				Some(next) => current = next,
+				// only reachable through numerical trouble, and the implicit close is the best we can do then
+				None => break,
+			}
+		}
+	}
+	path
+}
+
+/// Merges points that are within `eps` of each other into shared vertices.
+struct VertexMap {
+	eps: f32,
+	cells: HashMap<(i64, i64), Vec<usize>>,
+	points: Vec<[f32; 2]>,
+}
+impl VertexMap {
+	fn new(eps: f32) -> Self {
+		Self { eps, cells: HashMap::new(), points: vec![] }
+	}
+
+	fn insert(&mut self, point: [f32; 2]) -> usize {
+		let cell = ((point[0] / self.eps).floor() as i64, (point[1] / self.eps).floor() as i64);
+		for dx in -1..=1 {
+			for dy in -1..=1 {
+				if let Some(candidates) = self.cells.get(&(cell.0 + dx, cell.1 + dy)) {
+					for &i in candidates {
+						if length(sub(self.points[i], point)) <= self.eps {
+							return i;
+						}
+					}
+				}
+			}
+		}
+		self.points.push(point);
+		self.cells.entry(cell).or_default().push(self.points.len() - 1);
+		self.points.len() - 1
+	}
+}
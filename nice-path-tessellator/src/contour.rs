@@ -0,0 +1,58 @@
+use crate::{
+	geom::{Conic, LineSegment, QuadraticBezier, Segment},
+	Path, PathCommands,
+};
+
+/// One subpath, with every segment in absolute coordinates.
+#[derive(Clone, Debug)]
+pub(crate) struct Contour {
+	pub start: [f32; 2],
+	pub segments: Vec<Segment>,
+}
+impl Contour {
+	pub fn end(&self) -> [f32; 2] {
+		self.segments.last().map_or(self.start, |seg| seg.to())
+	}
+
+	/// The line that's implied when the contour is filled, if the contour doesn't already end where it started.
+	pub fn closing_line(&self) -> Option<LineSegment> {
+		let end = self.end();
+		(end != self.start).then(|| LineSegment::new(end, self.start))
+	}
+
+	/// The segments as they're filled, including the closing line.
+	pub fn closed_segments(&self) -> impl Iterator<Item = Segment> + '_ {
+		self.segments.iter().copied().chain(self.closing_line().map(Segment::Line))
+	}
+}
+
+impl Path {
+	/// Splits the path into its subpaths. Subpaths without any segments are skipped.
+	pub(crate) fn contours(&self) -> Vec<Contour> {
+		let mut contours = vec![];
+		let mut current = Contour { start: [0.0; 2], segments: vec![] };
+		for &cmd in &self.commands {
+			match cmd {
+				PathCommands::MoveTo(to) => {
+					let prev = std::mem::replace(&mut current, Contour { start: to, segments: vec![] });
+					if !prev.segments.is_empty() {
+						contours.push(prev);
+					}
+				},
+				PathCommands::LineTo(to) => {
+					current.segments.push(Segment::Line(LineSegment::new(current.end(), to)));
+				},
+				PathCommands::QuadraticCurveTo(ctrl, to) => {
+					current.segments.push(Segment::Quadratic(QuadraticBezier::new(current.end(), ctrl, to)));
+				},
+				PathCommands::ConicTo(ctrl, to, weight) => {
+					current.segments.push(Segment::Conic(Conic::new(current.end(), ctrl, to, weight)));
+				},
+			}
+		}
+		if !current.segments.is_empty() {
+			contours.push(current);
+		}
+		contours
+	}
+}
@@ -0,0 +1,107 @@
+use crate::{geom::Segment, Path};
+
+impl Path {
+	/// Cuts the path into dashes, as a path with one subpath per dash. `pattern` alternates between the lengths of dashes
+	/// and gaps, and is repeated twice if it has an odd number of entries, like SVG's `stroke-dasharray`. `offset` is how
+	/// far into the pattern each subpath starts.
+	///
+	/// Segments are cut at exact arc-length positions, and cut quadratics and conics are still quadratics and conics.
+	/// Zero-length dashes turn into zero-length lines, which [`Path::stroke`] draws as dots when the caps aren't butt
+	/// caps. If the pattern is empty, has negative entries, or adds up to zero, the path is returned undashed.
+	pub fn dash(&self, pattern: &[f32], offset: f32) -> Path {
+		if pattern.is_empty() || pattern.iter().any(|&len| len < 0.0 || !len.is_finite()) {
+			return self.clone();
+		}
+		let pattern: Vec<f32> =
+			if pattern.len() % 2 == 1 { pattern.iter().chain(pattern).copied().collect() } else { pattern.to_vec() };
+		let total: f32 = pattern.iter().sum();
+		if total <= 0.0 {
+			return self.clone();
+		}
+
+		let mut path = Path::start();
+		for contour in self.contours() {
+			let mut dasher = Dasher { pattern: &pattern, idx: 0, remaining: pattern[0], pen_down: false, path };
+			dasher.skip(offset.rem_euclid(total));
+			for seg in &contour.segments {
+				dasher.dash_segment(seg);
+			}
+			path = dasher.path;
+		}
+		path
+	}
+}
+
+struct Dasher<'a> {
+	pattern: &'a [f32],
+	/// the current entry in the pattern. Even entries are dashes, odd ones gaps.
+	idx: usize,
+	/// how much of the current entry is left
+	remaining: f32,
+	/// whether the current dash has already been started in `path`
+	pen_down: bool,
+	path: Path,
+}
+impl<'a> Dasher<'a> {
+	fn is_dash(&self) -> bool {
+		self.idx.is_multiple_of(2)
+	}
+
+	/// Moves through the pattern without drawing anything.
+	fn skip(&mut self, mut len: f32) {
+		while len > 0.0 && len >= self.remaining {
+			len -= self.remaining;
+			self.next_entry();
+		}
+		self.remaining -= len;
+	}
+
+	fn next_entry(&mut self) {
+		self.idx = (self.idx + 1) % self.pattern.len();
+		self.remaining = self.pattern[self.idx];
+		self.pen_down = false;
+	}
+
+	fn build(&mut self, f: impl FnOnce(Path) -> Path) {
+		self.path = f(std::mem::replace(&mut self.path, Path::start()));
+	}
+
+	fn dash_segment(&mut self, seg: &Segment) {
+		let len = seg.length();
+		let mut pos = 0.0;
+		loop {
+			// zero-length dashes are dots
+			while self.remaining == 0.0 {
+				if self.is_dash() {
+					let p = seg.eval(seg.t_at_length(pos));
+					self.build(|path| path.move_to(p).line_to(p));
+				}
+				self.next_entry();
+			}
+			if pos >= len {
+				break;
+			}
+
+			let step = self.remaining.min(len - pos);
+			if self.is_dash() {
+				let t0 = seg.t_at_length(pos);
+				let t1 = seg.t_at_length(pos + step);
+				let piece = seg.slice(t0, t1);
+				if !self.pen_down {
+					self.build(|path| path.move_to(piece.from()));
+					self.pen_down = true;
+				}
+				self.build(|path| match piece {
+					Segment::Line(line) => path.line_to(line.to),
+					Segment::Quadratic(quad) => path.quadratic_curve_to(quad.ctrl, quad.to),
+					Segment::Conic(conic) => path.conic_to(conic.ctrl, conic.to, conic.weight),
+				});
+			}
+			pos += step;
+			self.remaining -= step;
+			if self.remaining <= 0.0 {
+				self.next_entry();
+			}
+		}
+	}
+}
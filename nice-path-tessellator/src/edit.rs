@@ -0,0 +1,166 @@
+use std::ops::Range;
+
+use crate::{checked, Conic, LineSegment, Path, PathCommands, QuadraticBezier, Segment};
+
+/// A drawing command with the pen position it starts from, so it's meaningful on its own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathSegment {
+	Line { from: [f32; 2], to: [f32; 2] },
+	Quadratic { from: [f32; 2], ctrl: [f32; 2], to: [f32; 2] },
+	Conic { from: [f32; 2], ctrl: [f32; 2], to: [f32; 2], weight: f32 },
+}
+impl PathSegment {
+	pub fn from(&self) -> [f32; 2] {
+		match *self {
+			PathSegment::Line { from, .. } | PathSegment::Quadratic { from, .. } | PathSegment::Conic { from, .. } => {
+				from
+			},
+		}
+	}
+
+	pub fn to(&self) -> [f32; 2] {
+		match *self {
+			PathSegment::Line { to, .. } | PathSegment::Quadratic { to, .. } | PathSegment::Conic { to, .. } => to,
+		}
+	}
+}
+
+impl From<PathSegment> for Segment {
+	fn from(seg: PathSegment) -> Self {
+		match seg {
+			PathSegment::Line { from, to } => Segment::Line(LineSegment::new(from, to)),
+			PathSegment::Quadratic { from, ctrl, to } => Segment::Quadratic(QuadraticBezier::new(from, ctrl, to)),
+			PathSegment::Conic { from, ctrl, to, weight } => Segment::Conic(Conic::new(from, ctrl, to, weight)),
+		}
+	}
+}
+
+impl Path {
+	/// Builds a path out of existing commands. Like a path built with [`Path::start`], it starts at the origin until
+	/// the first [`PathCommands::MoveTo`].
+	///
+	/// Like [`Path::conic_to`], this and the other editing methods panic on conic weights that aren't positive and
+	/// finite.
+	pub fn from_commands(commands: Vec<PathCommands>) -> Self {
+		Self { commands: commands.into_iter().map(checked).collect() }
+	}
+
+	/// Every command in order. Indices into this slice are what the editing methods take.
+	pub fn commands(&self) -> &[PathCommands] {
+		&self.commands
+	}
+
+	pub fn len(&self) -> usize {
+		self.commands.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+
+	/// Where the pen is before the command at `index` is drawn.
+	pub fn pen_before(&self, index: usize) -> [f32; 2] {
+		match index.checked_sub(1).and_then(|i| self.commands.get(i)) {
+			Some(&cmd) => end_point(cmd),
+			None => [0.0; 2],
+		}
+	}
+
+	/// The command at `index` as a segment with its start point, or `None` if it's a [`PathCommands::MoveTo`] or out of
+	/// range.
+	pub fn segment(&self, index: usize) -> Option<PathSegment> {
+		let from = self.pen_before(index);
+		match *self.commands.get(index)? {
+			PathCommands::MoveTo(_) => None,
+			PathCommands::LineTo(to) => Some(PathSegment::Line { from, to }),
+			PathCommands::QuadraticCurveTo(ctrl, to) => Some(PathSegment::Quadratic { from, ctrl, to }),
+			PathCommands::ConicTo(ctrl, to, weight) => Some(PathSegment::Conic { from, ctrl, to, weight }),
+		}
+	}
+
+	/// Every segment in order, with its index into [`Path::commands`].
+	pub fn segments(&self) -> impl Iterator<Item = (usize, PathSegment)> + '_ {
+		(0..self.commands.len()).filter_map(move |i| self.segment(i).map(|seg| (i, seg)))
+	}
+
+	pub fn push(&mut self, command: PathCommands) {
+		self.commands.push(checked(command));
+	}
+
+	/// Inserts `command` before the command at `index`. Later segments keep their end points, so the segment after
+	/// `command` now starts where `command` ends.
+	pub fn insert(&mut self, index: usize, command: PathCommands) {
+		self.commands.insert(index, checked(command));
+	}
+
+	pub fn remove(&mut self, index: usize) -> PathCommands {
+		self.commands.remove(index)
+	}
+
+	/// Swaps the command at `index` for `command`, and returns the old one.
+	pub fn replace(&mut self, index: usize, command: PathCommands) -> PathCommands {
+		std::mem::replace(&mut self.commands[index], checked(command))
+	}
+
+	/// The range of [`Path::commands`] that makes up each subpath. Every subpath but the first starts with its
+	/// [`PathCommands::MoveTo`], and the first only doesn't if the path starts drawing from the origin.
+	pub fn subpath_ranges(&self) -> Vec<Range<usize>> {
+		let mut starts: Vec<usize> = (0..self.commands.len())
+			.filter(|&i| i == 0 || matches!(self.commands[i], PathCommands::MoveTo(_)))
+			.collect();
+		starts.push(self.commands.len());
+		starts.windows(2).map(|pair| pair[0]..pair[1]).collect()
+	}
+
+	/// Each subpath as a path of its own, starting with a [`PathCommands::MoveTo`].
+	pub fn subpaths(&self) -> Vec<Path> {
+		self.subpath_ranges()
+			.into_iter()
+			.map(|range| {
+				let mut commands = self.commands[range.clone()].to_vec();
+				if !matches!(commands[0], PathCommands::MoveTo(_)) {
+					commands.insert(0, PathCommands::MoveTo(self.pen_before(range.start)));
+				}
+				Path { commands }
+			})
+			.collect()
+	}
+
+	/// Reverses the direction of the subpath at `index` in [`Path::subpath_ranges`], so it starts where it used to end
+	/// and traces the same segments backwards.
+	pub fn reverse_subpath(&mut self, index: usize) {
+		let range = self.subpath_ranges()[index].clone();
+		let mut pen = self.pen_before(range.start);
+		let mut segments = vec![];
+		for &cmd in &self.commands[range.clone()] {
+			match cmd {
+				PathCommands::MoveTo(_) => {},
+				PathCommands::LineTo(to) => segments.push(PathSegment::Line { from: pen, to }),
+				PathCommands::QuadraticCurveTo(ctrl, to) => {
+					segments.push(PathSegment::Quadratic { from: pen, ctrl, to })
+				},
+				PathCommands::ConicTo(ctrl, to, weight) => {
+					segments.push(PathSegment::Conic { from: pen, ctrl, to, weight })
+				},
+			}
+			pen = end_point(cmd);
+		}
+
+		let mut reversed = vec![PathCommands::MoveTo(pen)];
+		reversed.extend(segments.iter().rev().map(|seg| match *seg {
+			PathSegment::Line { from, .. } => PathCommands::LineTo(from),
+			PathSegment::Quadratic { from, ctrl, .. } => PathCommands::QuadraticCurveTo(ctrl, from),
+			PathSegment::Conic { from, ctrl, weight, .. } => PathCommands::ConicTo(ctrl, from, weight),
+		}));
+		self.commands.splice(range, reversed);
+	}
+}
+
+fn end_point(cmd: PathCommands) -> [f32; 2] {
+	match cmd {
+		PathCommands::MoveTo(to)
+		| PathCommands::LineTo(to)
+		| PathCommands::QuadraticCurveTo(_, to)
+		| PathCommands::ConicTo(_, to, _) => to,
+	}
+}
@@ -0,0 +1,88 @@
+use crate::{
+	geom::{cross, dot, length, sub, QuadraticBezier, Segment},
+	Path,
+};
+
+impl Path {
+	/// Approximates every subpath with a polyline that's within `tolerance` of it, in path units. Each polyline starts at
+	/// its subpath's start point and, like the subpath, is implicitly closed when filled.
+	///
+	/// Quadratics are subdivided with Raph Levien's parabola integral method: the number of points needed is estimated
+	/// up front from the curve's shape, and they're spread so the error is about the same everywhere, which gets close to
+	/// the fewest points possible for the tolerance. Conics are first approximated with quadratics within half the
+	/// tolerance, and those are flattened within the other half.
+	///
+	/// Panics if `tolerance` isn't positive, since no number of points gets within it.
+	pub fn flatten(&self, tolerance: f32) -> impl Iterator<Item = Vec<[f32; 2]>> {
+		assert!(tolerance > 0.0, "flattening tolerance must be positive, not {}", tolerance);
+		self.contours().into_iter().map(move |contour| {
+			let mut points = vec![contour.start];
+			for seg in &contour.segments {
+				match seg {
+					Segment::Line(line) => points.push(line.to),
+					Segment::Quadratic(quad) => flatten_quadratic(quad, tolerance, &mut points),
+					Segment::Conic(conic) => {
+						for quad in conic.to_quadratics(tolerance / 2.0) {
+							flatten_quadratic(&quad, tolerance / 2.0, &mut points);
+						}
+					},
+				}
+			}
+			points
+		})
+	}
+}
+
+/// Appends the points after `quad.from`.
+fn flatten_quadratic(quad: &QuadraticBezier, tolerance: f32, out: &mut Vec<[f32; 2]>) {
+	// the curve is a piece of the parabola y = x², between x0 and x2, scaled down by `scale`
+	let d01 = sub(quad.ctrl, quad.from);
+	let d12 = sub(quad.to, quad.ctrl);
+	let dd = sub(d12, d01);
+	let chord_cross = cross(sub(quad.to, quad.from), dd);
+	let x0 = dot(d01, dd) / chord_cross;
+	let x2 = dot(d12, dd) / chord_cross;
+	let scale = (chord_cross / (length(dd) * (x2 - x0))).abs();
+	if !(x0.is_finite() && x2.is_finite() && scale.is_finite()) {
+		// a straight curve. It can still double back on itself if the control point is beyond an endpoint.
+		if let Some(t) = quad.extrema().into_iter().flatten().next() {
+			out.push(quad.eval(t));
+		}
+		out.push(quad.to);
+		return;
+	}
+
+	let a0 = approx_parabola_integral(x0);
+	let a2 = approx_parabola_integral(x2);
+	let sqrt_tolerance = tolerance.sqrt();
+	let da = (a2 - a0).abs();
+	let sqrt_scale = scale.sqrt();
+	let estimate = if x0.signum() == x2.signum() {
+		da * sqrt_scale
+	} else {
+		// the curve goes through the parabola's vertex, where the estimate above breaks down
+		let x_min = sqrt_tolerance / sqrt_scale;
+		sqrt_tolerance * da / approx_parabola_integral(x_min)
+	};
+	let n = ((0.5 * estimate / sqrt_tolerance).ceil() as usize).max(1);
+
+	let u0 = approx_parabola_inv_integral(a0);
+	let u2 = approx_parabola_inv_integral(a2);
+	for i in 1..n {
+		let u = approx_parabola_inv_integral(a0 + (a2 - a0) * i as f32 / n as f32);
+		out.push(quad.eval((u - u0) / (u2 - u0)));
+	}
+	out.push(quad.to);
+}
+
+/// An approximation of `∫ (1 + 4x²)^-0.25 dx`, which measures how many points a parabola needs up to `x`.
+fn approx_parabola_integral(x: f32) -> f32 {
+	const D: f32 = 0.67;
+	x / (1.0 - D + (D.powi(4) + 0.25 * x * x).sqrt().sqrt())
+}
+
+/// An approximation of the inverse of [`approx_parabola_integral`].
+fn approx_parabola_inv_integral(x: f32) -> f32 {
+	const B: f32 = 0.39;
+	x * (1.0 - B + (B * B + 0.25 * x * x).sqrt())
+}
@@ -0,0 +1,856 @@
+use array_init::array_init;
+
+use crate::Bounds;
+
+/// A line, a quadratic or a conic, as they appear in a [`crate::Path`] but with absolute start points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+	Line(LineSegment),
+	Quadratic(QuadraticBezier),
+	Conic(Conic),
+}
+impl Segment {
+	pub fn from(&self) -> [f32; 2] {
+		match self {
+			Segment::Line(line) => line.from,
+			Segment::Quadratic(quad) => quad.from,
+			Segment::Conic(conic) => conic.from,
+		}
+	}
+
+	pub fn to(&self) -> [f32; 2] {
+		match self {
+			Segment::Line(line) => line.to,
+			Segment::Quadratic(quad) => quad.to,
+			Segment::Conic(conic) => conic.to,
+		}
+	}
+
+	pub fn eval(&self, t: f32) -> [f32; 2] {
+		match self {
+			Segment::Line(line) => line.eval(t),
+			Segment::Quadratic(quad) => quad.eval(t),
+			Segment::Conic(conic) => conic.eval(t),
+		}
+	}
+
+	pub fn derivative(&self, t: f32) -> [f32; 2] {
+		match self {
+			Segment::Line(line) => line.derivative(t),
+			Segment::Quadratic(quad) => quad.derivative(t),
+			Segment::Conic(conic) => conic.derivative(t),
+		}
+	}
+
+	pub fn length(&self) -> f32 {
+		match self {
+			Segment::Line(line) => line.length(),
+			Segment::Quadratic(quad) => quad.arc_length(),
+			Segment::Conic(conic) => conic.arc_length(),
+		}
+	}
+
+	pub fn nearest_t(&self, p: [f32; 2]) -> f32 {
+		match self {
+			Segment::Line(line) => line.nearest_t(p),
+			Segment::Quadratic(quad) => quad.nearest_t(p),
+			Segment::Conic(conic) => conic.nearest_t(p),
+		}
+	}
+
+	pub fn nearest_point(&self, p: [f32; 2]) -> [f32; 2] {
+		self.eval(self.nearest_t(p))
+	}
+
+	/// Parameters in `(0, 1)` where the segment turns around in x and in y, respectively. Lines never do.
+	pub fn extrema(&self) -> [Option<f32>; 2] {
+		match self {
+			Segment::Line(_) => [None; 2],
+			Segment::Quadratic(quad) => quad.extrema(),
+			Segment::Conic(conic) => conic.extrema(),
+		}
+	}
+
+	/// The tightest box around the segment.
+	pub fn bounding_box(&self) -> Bounds {
+		match self {
+			Segment::Line(line) => line.bounding_box(),
+			Segment::Quadratic(quad) => quad.bounding_box(),
+			Segment::Conic(conic) => conic.bounding_box(),
+		}
+	}
+
+	/// Every point where the two segments cross or touch, within `eps`. See [`Intersection`].
+	pub fn intersections(&self, other: &Segment, eps: f32) -> Vec<Intersection> {
+		intersect_segments(self, other, eps)
+	}
+
+	pub fn split(&self, t: f32) -> (Self, Self) {
+		match self {
+			Segment::Line(line) => {
+				let (a, b) = line.split(t);
+				(Segment::Line(a), Segment::Line(b))
+			},
+			Segment::Quadratic(quad) => {
+				let (a, b) = quad.split(t);
+				(Segment::Quadratic(a), Segment::Quadratic(b))
+			},
+			Segment::Conic(conic) => {
+				let (a, b) = conic.split(t);
+				(Segment::Conic(a), Segment::Conic(b))
+			},
+		}
+	}
+
+	/// The part of the segment between `t0` and `t1`.
+	pub fn slice(&self, t0: f32, t1: f32) -> Self {
+		match self {
+			Segment::Line(line) => Segment::Line(LineSegment::new(line.eval(t0), line.eval(t1))),
+			Segment::Quadratic(quad) => Segment::Quadratic(quad.slice(t0, t1)),
+			Segment::Conic(conic) => Segment::Conic(conic.slice(t0, t1)),
+		}
+	}
+
+	/// The parameter at arc length `s` from the start.
+	pub fn t_at_length(&self, s: f32) -> f32 {
+		match self {
+			Segment::Line(line) => {
+				let len = line.length();
+				if len == 0.0 {
+					0.0
+				} else {
+					(s / len).clamp(0.0, 1.0)
+				}
+			},
+			Segment::Quadratic(quad) => quad.t_at_length(s),
+			Segment::Conic(conic) => conic.t_at_length(s),
+		}
+	}
+
+	/// The same segment, traversed the other way.
+	pub fn reversed(&self) -> Self {
+		match self {
+			Segment::Line(line) => Segment::Line(LineSegment::new(line.to, line.from)),
+			Segment::Quadratic(quad) => Segment::Quadratic(QuadraticBezier::new(quad.to, quad.ctrl, quad.from)),
+			Segment::Conic(conic) => Segment::Conic(Conic::new(conic.to, conic.ctrl, conic.from, conic.weight)),
+		}
+	}
+
+	/// The same segment with its endpoints moved, e.g. to snap them onto a shared vertex.
+	pub fn with_endpoints(&self, from: [f32; 2], to: [f32; 2]) -> Self {
+		match self {
+			Segment::Line(_) => Segment::Line(LineSegment::new(from, to)),
+			Segment::Quadratic(quad) => Segment::Quadratic(QuadraticBezier::new(from, quad.ctrl, to)),
+			Segment::Conic(conic) => Segment::Conic(Conic::new(from, conic.ctrl, to, conic.weight)),
+		}
+	}
+
+	/// The box around every point, including the control point.
+	pub fn control_bounds(&self) -> Bounds {
+		let mut bounds = Bounds::from_point(self.from());
+		bounds.include(self.to());
+		match self {
+			Segment::Line(_) => {},
+			Segment::Quadratic(quad) => bounds.include(quad.ctrl),
+			Segment::Conic(conic) => bounds.include(conic.ctrl),
+		}
+		bounds
+	}
+}
+
+/// A straight line, parametrised from `from` at `t = 0` to `to` at `t = 1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineSegment {
+	pub from: [f32; 2],
+	pub to: [f32; 2],
+}
+impl LineSegment {
+	pub fn new(from: [f32; 2], to: [f32; 2]) -> Self {
+		Self { from, to }
+	}
+
+	pub fn eval(&self, t: f32) -> [f32; 2] {
+		lerp(self.from, self.to, t)
+	}
+
+	pub fn derivative(&self, _t: f32) -> [f32; 2] {
+		sub(self.to, self.from)
+	}
+
+	pub fn length(&self) -> f32 {
+		length(sub(self.to, self.from))
+	}
+
+	/// The parameter of the point on the segment closest to `p`.
+	pub fn nearest_t(&self, p: [f32; 2]) -> f32 {
+		let dir = sub(self.to, self.from);
+		let len2 = dot(dir, dir);
+		if len2 == 0.0 {
+			return 0.0;
+		}
+		(dot(sub(p, self.from), dir) / len2).clamp(0.0, 1.0)
+	}
+
+	pub fn nearest_point(&self, p: [f32; 2]) -> [f32; 2] {
+		self.eval(self.nearest_t(p))
+	}
+
+	pub fn split(&self, t: f32) -> (Self, Self) {
+		let mid = self.eval(t);
+		(Self::new(self.from, mid), Self::new(mid, self.to))
+	}
+
+	pub fn bounding_box(&self) -> Bounds {
+		let mut bounds = Bounds::from_point(self.from);
+		bounds.include(self.to);
+		bounds
+	}
+}
+
+/// A quadratic Bézier curve, parametrised from `from` at `t = 0` to `to` at `t = 1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadraticBezier {
+	pub from: [f32; 2],
+	pub ctrl: [f32; 2],
+	pub to: [f32; 2],
+}
+impl QuadraticBezier {
+	pub fn new(from: [f32; 2], ctrl: [f32; 2], to: [f32; 2]) -> Self {
+		Self { from, ctrl, to }
+	}
+
+	pub fn eval(&self, t: f32) -> [f32; 2] {
+		let a = lerp(self.from, self.ctrl, t);
+		let b = lerp(self.ctrl, self.to, t);
+		lerp(a, b, t)
+	}
+
+	pub fn derivative(&self, t: f32) -> [f32; 2] {
+		let a = sub(self.ctrl, self.from);
+		let b = sub(self.to, self.ctrl);
+		scale(lerp(a, b, t), 2.0)
+	}
+
+	/// Parameters in `(0, 1)` where the curve turns around in x and in y, respectively.
+	pub fn extrema(&self) -> [Option<f32>; 2] {
+		array_init(|axis| {
+			let denom = self.from[axis] - 2.0 * self.ctrl[axis] + self.to[axis];
+			if denom == 0.0 {
+				return None;
+			}
+			let t = (self.from[axis] - self.ctrl[axis]) / denom;
+			(t > 0.0 && t < 1.0).then_some(t)
+		})
+	}
+
+	/// Exact arc length, using the closed form of the integral of `|B'(t)|`.
+	pub fn arc_length(&self) -> f32 {
+		let [p0, p1, p2] = [self.from, self.ctrl, self.to].map(|p| [p[0] as f64, p[1] as f64]);
+		let a = [p0[0] - 2.0 * p1[0] + p2[0], p0[1] - 2.0 * p1[1] + p2[1]];
+		let b = [2.0 * (p1[0] - p0[0]), 2.0 * (p1[1] - p0[1])];
+
+		let aa = 4.0 * (a[0] * a[0] + a[1] * a[1]);
+		let bb = 4.0 * (a[0] * b[0] + a[1] * b[1]);
+		let cc = b[0] * b[0] + b[1] * b[1];
+
+		// when the control point is on the line through the endpoints the log term blows up, but then the curve is
+		// just a line that may double back on itself once
+		let collinear = (a[0] * b[1] - a[1] * b[0]).abs() <= 1e-9 * (aa + cc);
+		if aa <= 1e-12 * cc.max(1e-12) || collinear {
+			let mut len = length(sub(self.to, self.from));
+			if aa > 0.0 {
+				let t = -bb / (2.0 * aa);
+				if t > 0.0 && t < 1.0 {
+					let turn = self.eval(t as f32);
+					len = length(sub(turn, self.from)) + length(sub(self.to, turn));
+				}
+			}
+			return len;
+		}
+
+		let sabc = 2.0 * (aa + bb + cc).sqrt();
+		let a2 = aa.sqrt();
+		let a32 = 2.0 * aa * a2;
+		let c2 = 2.0 * cc.sqrt();
+		let ba = bb / a2;
+		let len = (a32 * sabc
+			+ a2 * bb * (sabc - c2)
+			+ (4.0 * cc * aa - bb * bb) * ((2.0 * a2 + ba + sabc) / (ba + c2)).ln())
+			/ (4.0 * a32);
+		len as f32
+	}
+
+	/// The tightest box around the curve, which only includes the control point where the curve actually reaches it.
+	pub fn bounding_box(&self) -> Bounds {
+		let mut bounds = Bounds::from_point(self.from);
+		bounds.include(self.to);
+		for t in self.extrema().into_iter().flatten() {
+			bounds.include(self.eval(t));
+		}
+		bounds
+	}
+
+	pub fn nearest_point(&self, p: [f32; 2]) -> [f32; 2] {
+		self.eval(self.nearest_t(p))
+	}
+
+	/// The parameter of the point on the curve closest to `p`.
+	pub fn nearest_t(&self, p: [f32; 2]) -> f32 {
+		// (B(t) - p) . B'(t) = 0 is a cubic in t
+		let a = [self.from[0] - 2.0 * self.ctrl[0] + self.to[0], self.from[1] - 2.0 * self.ctrl[1] + self.to[1]];
+		let b = scale(sub(self.ctrl, self.from), 2.0);
+		let c = sub(self.from, p);
+		let roots = solve_cubic(2.0 * dot(a, a), 3.0 * dot(a, b), dot(b, b) + 2.0 * dot(a, c), dot(b, c));
+
+		let dist2 = |t: f32| {
+			let d = sub(self.eval(t), p);
+			dot(d, d)
+		};
+		let mut best = (0.0, dist2(0.0));
+		for t in roots.into_iter().flatten().map(|t| t.clamp(0.0, 1.0)).chain([1.0]) {
+			let d = dist2(t);
+			if d < best.1 {
+				best = (t, d);
+			}
+		}
+		best.0
+	}
+
+	/// The parameter at arc length `s` from the start, found with Newton's method on the exact arc length and falling
+	/// back to bisection where that would leave the bracket.
+	pub fn t_at_length(&self, s: f32) -> f32 {
+		t_at_length(s, self.arc_length(), |t| self.split(t).0.arc_length(), |t| length(self.derivative(t)))
+	}
+
+	/// The part of the curve between `t0` and `t1`.
+	pub fn slice(&self, t0: f32, t1: f32) -> Self {
+		let head = if t1 < 1.0 { self.split(t1).0 } else { *self };
+		if t0 <= 0.0 || t1 <= 0.0 {
+			return head;
+		}
+		head.split(t0 / t1).1
+	}
+
+	/// de Casteljau split at `t`. The two halves share the split point exactly.
+	pub fn split(&self, t: f32) -> (Self, Self) {
+		let a = lerp(self.from, self.ctrl, t);
+		let b = lerp(self.ctrl, self.to, t);
+		let mid = lerp(a, b, t);
+		(Self::new(self.from, a, mid), Self::new(mid, b, self.to))
+	}
+
+	/// The control triangle, which is also the triangle Loop-Blinn draws the curve in.
+	pub fn hull(&self) -> [[f32; 2]; 3] {
+		[self.from, self.ctrl, self.to]
+	}
+}
+
+/// How many times a conic may be halved while approximating it with quadratics.
+const MAX_CONIC_DEPTH: u32 = 10;
+/// How many times a conic's arc length integral may be halved.
+const MAX_CONIC_LENGTH_DEPTH: u32 = 8;
+/// Samples taken along a conic before refining the nearest one in [`Conic::nearest_t`].
+const CONIC_NEAREST_SAMPLES: usize = 16;
+
+/// Gauss-Legendre nodes and weights on `[0, 1]`, exact for polynomials up to degree 9.
+pub(crate) const GAUSS_LEGENDRE_5: [(f32, f32); 5] = [
+	(0.046910077, 0.11846344),
+	(0.23076534, 0.23931434),
+	(0.5, 0.28444445),
+	(0.76923466, 0.23931434),
+	(0.9530899, 0.11846344),
+];
+
+/// A rational quadratic Bézier curve, which can represent circular and elliptical arcs exactly. The endpoints have a
+/// weight of 1 and the control point has `weight`: 1 is an ordinary quadratic, less than 1 an ellipse and more than 1 a
+/// hyperbola. A quarter circle has a weight of `1 / sqrt(2)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Conic {
+	pub from: [f32; 2],
+	pub ctrl: [f32; 2],
+	pub to: [f32; 2],
+	pub weight: f32,
+}
+impl Conic {
+	pub fn new(from: [f32; 2], ctrl: [f32; 2], to: [f32; 2], weight: f32) -> Self {
+		Self { from, ctrl, to, weight }
+	}
+
+	pub fn eval(&self, t: f32) -> [f32; 2] {
+		let [b0, b1, b2] = [(1.0 - t) * (1.0 - t), 2.0 * self.weight * t * (1.0 - t), t * t];
+		let den = b0 + b1 + b2;
+		array_init(|axis| (b0 * self.from[axis] + b1 * self.ctrl[axis] + b2 * self.to[axis]) / den)
+	}
+
+	/// The derivative, from the standard form for rational quadratics with endpoint weights of 1.
+	pub fn derivative(&self, t: f32) -> [f32; 2] {
+		let (s, w) = (1.0 - t, self.weight);
+		let den = s * s + 2.0 * w * s * t + t * t;
+		let a = scale(sub(self.ctrl, self.from), w * s * s);
+		let b = scale(sub(self.to, self.from), s * t);
+		let c = scale(sub(self.to, self.ctrl), w * t * t);
+		scale(add(add(a, b), c), 2.0 / (den * den))
+	}
+
+	/// Parameters in `(0, 1)` where the curve turns around in x and in y, respectively. The curve stays inside its
+	/// control triangle and turns by less than half a turn, so it can't turn around twice.
+	pub fn extrema(&self) -> [Option<f32>; 2] {
+		array_init(|axis| {
+			let w = self.weight;
+			let a = self.ctrl[axis] - self.from[axis];
+			let b = self.to[axis] - self.from[axis];
+			let c = self.to[axis] - self.ctrl[axis];
+			// where the numerator of the derivative is zero
+			solve_quadratic(w * a - b + w * c, b - 2.0 * w * a, w * a)
+				.into_iter()
+				.flatten()
+				.find(|&t| t > 0.0 && t < 1.0)
+		})
+	}
+
+	/// The tightest box around the curve, which only includes the control point where the curve actually reaches it.
+	pub fn bounding_box(&self) -> Bounds {
+		let mut bounds = Bounds::from_point(self.from);
+		bounds.include(self.to);
+		for t in self.extrema().into_iter().flatten() {
+			bounds.include(self.eval(t));
+		}
+		bounds
+	}
+
+	/// Arc length, integrated numerically since conics have no closed form. Each half is integrated separately until
+	/// that stops changing the sum.
+	pub fn arc_length(&self) -> f32 {
+		self.arc_length_between(0.0, 1.0, 0) as f32
+	}
+
+	fn arc_length_between(&self, t0: f32, t1: f32, depth: u32) -> f64 {
+		let integrate = |t0: f32, t1: f32| -> f64 {
+			let span = t1 - t0;
+			GAUSS_LEGENDRE_5
+				.iter()
+				.map(|&(t, w)| (w * span) as f64 * length(self.derivative(t0 + t * span)) as f64)
+				.sum()
+		};
+		let mid = (t0 + t1) / 2.0;
+		let whole = integrate(t0, t1);
+		let halves = integrate(t0, mid) + integrate(mid, t1);
+		if (whole - halves).abs() <= 1e-7 * halves || depth >= MAX_CONIC_LENGTH_DEPTH {
+			halves
+		} else {
+			self.arc_length_between(t0, mid, depth + 1) + self.arc_length_between(mid, t1, depth + 1)
+		}
+	}
+
+	/// The parameter at arc length `s` from the start, found the same way as [`QuadraticBezier::t_at_length`].
+	pub fn t_at_length(&self, s: f32) -> f32 {
+		t_at_length(s, self.arc_length(), |t| self.split(t).0.arc_length(), |t| length(self.derivative(t)))
+	}
+
+	pub fn nearest_point(&self, p: [f32; 2]) -> [f32; 2] {
+		self.eval(self.nearest_t(p))
+	}
+
+	/// The parameter of the point on the curve closest to `p`. There's no closed form, so the curve is sampled, and
+	/// between the closest sample's neighbours the point is bisected for where the curve stops approaching `p`.
+	pub fn nearest_t(&self, p: [f32; 2]) -> f32 {
+		let dist2 = |t: f32| {
+			let d = sub(self.eval(t), p);
+			dot(d, d)
+		};
+		// positive where the curve moves away from `p`
+		let receding = |t: f32| dot(sub(self.eval(t), p), self.derivative(t)) > 0.0;
+		let step = 1.0 / CONIC_NEAREST_SAMPLES as f32;
+		let closest = (0..=CONIC_NEAREST_SAMPLES)
+			.map(|i| i as f32 * step)
+			.min_by(|&a, &b| dist2(a).total_cmp(&dist2(b)))
+			.unwrap_or(0.0);
+		let (mut lo, mut hi) = ((closest - step).max(0.0), (closest + step).min(1.0));
+		if receding(lo) {
+			return lo;
+		}
+		if !receding(hi) {
+			return hi;
+		}
+		for _ in 0..32 {
+			let mid = (lo + hi) / 2.0;
+			if receding(mid) {
+				hi = mid;
+			} else {
+				lo = mid;
+			}
+		}
+		(lo + hi) / 2.0
+	}
+
+	/// The part of the curve between `t0` and `t1`, blossomed from the homogeneous control points and renormalised like
+	/// [`Conic::split`].
+	pub fn slice(&self, t0: f32, t1: f32) -> Self {
+		if t0 <= 0.0 && t1 >= 1.0 {
+			return *self;
+		}
+		let [q0, q1, q2] =
+			[(self.from, 1.0), (self.ctrl, self.weight), (self.to, 1.0)].map(|(p, w)| [p[0] * w, p[1] * w, w]);
+		let blossom = |a: f32, b: f32| -> [f32; 3] {
+			let (c0, c1, c2) = ((1.0 - a) * (1.0 - b), (1.0 - a) * b + a * (1.0 - b), a * b);
+			array_init(|i| c0 * q0[i] + c1 * q1[i] + c2 * q2[i])
+		};
+		let project = |p: [f32; 3]| [p[0] / p[2], p[1] / p[2]];
+		let (from, ctrl, to) = (blossom(t0, t0), blossom(t0, t1), blossom(t1, t1));
+		Self::new(project(from), project(ctrl), project(to), ctrl[2] / (from[2] * to[2]).sqrt())
+	}
+
+	/// de Casteljau split at `t`, done on the homogeneous control points. Both halves are renormalised so their
+	/// endpoints have a weight of 1 again.
+	pub fn split(&self, t: f32) -> (Self, Self) {
+		let lift = |p: [f32; 2], w: f32| [p[0] * w, p[1] * w, w];
+		let mix = |a: [f32; 3], b: [f32; 3]| -> [f32; 3] { array_init(|i| a[i] + (b[i] - a[i]) * t) };
+		let project = |p: [f32; 3]| [p[0] / p[2], p[1] / p[2]];
+
+		let q0 = lift(self.from, 1.0);
+		let q1 = lift(self.ctrl, self.weight);
+		let q2 = lift(self.to, 1.0);
+		let a = mix(q0, q1);
+		let b = mix(q1, q2);
+		let mid = mix(a, b);
+		(
+			Self::new(self.from, project(a), project(mid), a[2] / mid[2].sqrt()),
+			Self::new(project(mid), project(b), self.to, b[2] / mid[2].sqrt()),
+		)
+	}
+
+	/// The control triangle. Like a quadratic's, it contains the whole curve.
+	pub fn hull(&self) -> [[f32; 2]; 3] {
+		[self.from, self.ctrl, self.to]
+	}
+
+	/// Approximates the conic with quadratics that are within `tolerance` of it.
+	pub fn to_quadratics(&self, tolerance: f32) -> Vec<QuadraticBezier> {
+		let mut out = vec![];
+		self.push_quadratics(tolerance, 0, &mut out);
+		out
+	}
+
+	fn push_quadratics(&self, tolerance: f32, depth: u32, out: &mut Vec<QuadraticBezier>) {
+		// the distance between the conic and the quadratic with the same control points, which shrinks about 4x with
+		// every halving
+		let a = self.weight - 1.0;
+		let k = a / (4.0 * (2.0 + a));
+		let error = k.abs() * length(add(sub(self.from, scale(self.ctrl, 2.0)), self.to));
+		if error <= tolerance || depth >= MAX_CONIC_DEPTH {
+			out.push(QuadraticBezier::new(self.from, self.ctrl, self.to));
+		} else {
+			let (a, b) = self.split(0.5);
+			a.push_quadratics(tolerance, depth + 1, out);
+			b.push_quadratics(tolerance, depth + 1, out);
+		}
+	}
+}
+
+/// The parameter at arc length `s` along a curve `total` long, where `head_length(t)` is the length up to `t` and
+/// `speed(t)` the length of the derivative. Uses Newton's method, falling back to bisection where that would leave the
+/// bracket.
+fn t_at_length(s: f32, total: f32, head_length: impl Fn(f32) -> f32, speed: impl Fn(f32) -> f32) -> f32 {
+	if s <= 0.0 || total == 0.0 {
+		return 0.0;
+	}
+	if s >= total {
+		return 1.0;
+	}
+
+	let (mut lo, mut hi) = (0.0f32, 1.0f32);
+	let mut t = s / total;
+	for _ in 0..32 {
+		let err = head_length(t) - s;
+		if err.abs() <= total * 1e-6 {
+			break;
+		}
+		if err > 0.0 {
+			hi = t;
+		} else {
+			lo = t;
+		}
+		let speed = speed(t);
+		let next = if speed > 0.0 { t - err / speed } else { f32::NAN };
+		t = if next > lo && next < hi { next } else { (lo + hi) / 2.0 };
+	}
+	t
+}
+
+/// Real roots of `a t^2 + b t + c`.
+pub(crate) fn solve_quadratic(a: f32, b: f32, c: f32) -> [Option<f32>; 2] {
+	let (a, b, c) = (a as f64, b as f64, c as f64);
+	if a.abs() <= 1e-12 * (b.abs() + c.abs()) {
+		if b == 0.0 {
+			return [None, None];
+		}
+		return [Some((-c / b) as f32), None];
+	}
+	let disc = b * b - 4.0 * a * c;
+	if disc < 0.0 {
+		return [None, None];
+	}
+	// the numerically stable form, which avoids cancellation between -b and the root
+	let q = -0.5 * (b + b.signum() * disc.sqrt());
+	if q == 0.0 {
+		return [Some(0.0), None];
+	}
+	[Some((q / a) as f32), Some((c / q) as f32)]
+}
+
+/// Real roots of `a t^3 + b t^2 + c t + d`.
+pub(crate) fn solve_cubic(a: f32, b: f32, c: f32, d: f32) -> [Option<f32>; 3] {
+	if a.abs() <= 1e-6 * (b.abs() + c.abs() + d.abs()) {
+		let [r0, r1] = solve_quadratic(b, c, d);
+		return [r0, r1, None];
+	}
+	let (a, b, c, d) = (a as f64, b as f64, c as f64, d as f64);
+	// depressed cubic x^3 + px + q with t = x - b / 3a
+	let (b, c, d) = (b / a, c / a, d / a);
+	let p = c - b * b / 3.0;
+	let q = 2.0 * b * b * b / 27.0 - b * c / 3.0 + d;
+	let shift = -b / 3.0;
+
+	let disc = q * q / 4.0 + p * p * p / 27.0;
+	if disc > 0.0 {
+		let sqrt_disc = disc.sqrt();
+		let x = (-q / 2.0 + sqrt_disc).cbrt() + (-q / 2.0 - sqrt_disc).cbrt();
+		[Some((x + shift) as f32), None, None]
+	} else if p == 0.0 {
+		[Some(shift as f32), None, None]
+	} else {
+		// three real roots, found with the trigonometric method
+		let r = 2.0 * (-p / 3.0).sqrt();
+		let phi = ((3.0 * q) / (p * r)).clamp(-1.0, 1.0).acos() / 3.0;
+		array_init(|k| Some((r * (phi - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos() + shift) as f32))
+	}
+}
+
+pub(crate) fn lerp(a: [f32; 2], b: [f32; 2], t: f32) -> [f32; 2] {
+	[a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+}
+
+pub(crate) fn add(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+	[a[0] + b[0], a[1] + b[1]]
+}
+
+pub(crate) fn sub(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+	[a[0] - b[0], a[1] - b[1]]
+}
+
+pub(crate) fn scale(a: [f32; 2], s: f32) -> [f32; 2] {
+	[a[0] * s, a[1] * s]
+}
+
+pub(crate) fn dot(a: [f32; 2], b: [f32; 2]) -> f32 {
+	a[0] * b[0] + a[1] * b[1]
+}
+
+pub(crate) fn length(a: [f32; 2]) -> f32 {
+	dot(a, a).sqrt()
+}
+
+pub(crate) fn cross(a: [f32; 2], b: [f32; 2]) -> f32 {
+	a[0] * b[1] - a[1] * b[0]
+}
+
+/// Twice the signed area of a triangle. Positive when the points wind clockwise in y-down coordinates.
+pub(crate) fn tri_area2(tri: &[[f32; 2]; 3]) -> f32 {
+	cross(sub(tri[1], tri[0]), sub(tri[2], tri[0]))
+}
+
+/// Whether the interiors of two triangles overlap by more than `eps`. Triangles that only touch along an edge or at a
+/// vertex are not considered overlapping.
+pub(crate) fn tris_overlap(a: &[[f32; 2]; 3], b: &[[f32; 2]; 3], eps: f32) -> bool {
+	// separating axis theorem: two convex polygons are disjoint iff one of their edge normals separates them
+	for tri in [a, b] {
+		for i in 0..3 {
+			let edge = sub(tri[(i + 1) % 3], tri[i]);
+			let len = dot(edge, edge).sqrt();
+			if len == 0.0 {
+				continue;
+			}
+			let axis = [-edge[1] / len, edge[0] / len];
+			let (a_min, a_max) = project(a, axis);
+			let (b_min, b_max) = project(b, axis);
+			if a_max - b_min <= eps || b_max - a_min <= eps {
+				return false;
+			}
+		}
+	}
+	true
+}
+
+fn project(tri: &[[f32; 2]; 3], axis: [f32; 2]) -> (f32, f32) {
+	let mut min = f32::INFINITY;
+	let mut max = f32::NEG_INFINITY;
+	for &p in tri {
+		let d = dot(p, axis);
+		min = min.min(d);
+		max = max.max(d);
+	}
+	(min, max)
+}
+
+/// A point where two segments meet, with the parameter on each.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Intersection {
+	pub t_a: f32,
+	pub t_b: f32,
+	pub point: [f32; 2],
+}
+
+/// Every point where `a` and `b` cross or touch, within `eps`. Includes endpoints of one segment that lie on the other,
+/// so T-junctions and the ends of overlapping stretches are found too.
+pub(crate) fn intersect_segments(a: &Segment, b: &Segment, eps: f32) -> Vec<Intersection> {
+	let mut out = vec![];
+
+	for (t_a, p) in [(0.0, a.from()), (1.0, a.to())] {
+		let t_b = b.nearest_t(p);
+		if length(sub(b.eval(t_b), p)) <= eps {
+			out.push(Intersection { t_a, t_b, point: p });
+		}
+	}
+	for (t_b, p) in [(0.0, b.from()), (1.0, b.to())] {
+		let t_a = a.nearest_t(p);
+		if length(sub(a.eval(t_a), p)) <= eps {
+			out.push(Intersection { t_a, t_b, point: p });
+		}
+	}
+
+	match (a, b) {
+		(Segment::Line(a), Segment::Line(b)) => out.extend(intersect_lines(a, b)),
+		(Segment::Line(line), curve) => out.extend(intersect_line_curve(line, curve)),
+		(curve, Segment::Line(line)) => {
+			out.extend(intersect_line_curve(line, curve).into_iter().map(|i| Intersection {
+				t_a: i.t_b,
+				t_b: i.t_a,
+				point: i.point,
+			}))
+		},
+		_ => out.extend(intersect_curves(a, b, eps)),
+	}
+
+	dedup_intersections(&mut out, eps);
+	out
+}
+
+fn intersect_lines(a: &LineSegment, b: &LineSegment) -> Option<Intersection> {
+	let da = sub(a.to, a.from);
+	let db = sub(b.to, b.from);
+	let denom = cross(da, db);
+	if denom == 0.0 {
+		// parallel, and any overlap is covered by the endpoint checks
+		return None;
+	}
+	let offset = sub(b.from, a.from);
+	let t_a = cross(offset, db) / denom;
+	let t_b = cross(offset, da) / denom;
+	((0.0..=1.0).contains(&t_a) && (0.0..=1.0).contains(&t_b)).then(|| Intersection { t_a, t_b, point: a.eval(t_a) })
+}
+
+fn intersect_line_curve(line: &LineSegment, curve: &Segment) -> Vec<Intersection> {
+	let (hull, weight) = match curve {
+		Segment::Line(other) => return intersect_lines(line, other).into_iter().collect(),
+		Segment::Quadratic(quad) => (quad.hull(), 1.0),
+		Segment::Conic(conic) => (conic.hull(), conic.weight),
+	};
+	let dir = sub(line.to, line.from);
+	let len2 = dot(dir, dir);
+
+	// signed distances of the control points from the line, which makes the curve's distance times its denominator a
+	// quadratic in t
+	let [d0, d1, d2] = hull.map(|p| cross(dir, sub(p, line.from)));
+	let roots = match len2 == 0.0 {
+		true => [None, None],
+		false => solve_quadratic(d0 - 2.0 * weight * d1 + d2, 2.0 * (weight * d1 - d0), d0),
+	};
+
+	roots
+		.into_iter()
+		.flatten()
+		.filter(|t| (-1e-6..=1.0 + 1e-6).contains(t))
+		.filter_map(|t_b| {
+			let t_b = t_b.clamp(0.0, 1.0);
+			let point = curve.eval(t_b);
+			let t_a = dot(sub(point, line.from), dir) / len2;
+			(-1e-6..=1.0 + 1e-6).contains(&t_a).then(|| Intersection { t_a: t_a.clamp(0.0, 1.0), t_b, point })
+		})
+		.collect()
+}
+
+/// Recursively subdivides both curves, keeping pairs whose hulls' boxes overlap, until the pieces are smaller than
+/// `eps`.
+fn intersect_curves(a: &Segment, b: &Segment, eps: f32) -> Vec<Intersection> {
+	struct Search {
+		eps: f32,
+		/// caps the work done on curves that coincide, where every piece overlaps its counterpart
+		budget: u32,
+		out: Vec<Intersection>,
+	}
+	impl Search {
+		fn recurse(&mut self, a: &Segment, a_range: [f32; 2], b: &Segment, b_range: [f32; 2]) {
+			if self.budget == 0 {
+				return;
+			}
+			self.budget -= 1;
+
+			let a_bounds = a.control_bounds();
+			let b_bounds = b.control_bounds();
+			if !a_bounds.overlaps(&b_bounds, self.eps) {
+				return;
+			}
+
+			let a_small = a_bounds.width().max(a_bounds.height()) <= self.eps;
+			let b_small = b_bounds.width().max(b_bounds.height()) <= self.eps;
+			if a_small && b_small {
+				let t_a = (a_range[0] + a_range[1]) / 2.0;
+				let t_b = (b_range[0] + b_range[1]) / 2.0;
+				self.out.push(Intersection { t_a, t_b, point: a.eval(0.5) });
+				return;
+			}
+
+			let a_mid = (a_range[0] + a_range[1]) / 2.0;
+			let b_mid = (b_range[0] + b_range[1]) / 2.0;
+			match (a_small, b_small) {
+				(false, false) => {
+					let (a0, a1) = a.split(0.5);
+					let (b0, b1) = b.split(0.5);
+					self.recurse(&a0, [a_range[0], a_mid], &b0, [b_range[0], b_mid]);
+					self.recurse(&a0, [a_range[0], a_mid], &b1, [b_mid, b_range[1]]);
+					self.recurse(&a1, [a_mid, a_range[1]], &b0, [b_range[0], b_mid]);
+					self.recurse(&a1, [a_mid, a_range[1]], &b1, [b_mid, b_range[1]]);
+				},
+				(false, true) => {
+					let (a0, a1) = a.split(0.5);
+					self.recurse(&a0, [a_range[0], a_mid], b, b_range);
+					self.recurse(&a1, [a_mid, a_range[1]], b, b_range);
+				},
+				_ => {
+					let (b0, b1) = b.split(0.5);
+					self.recurse(a, a_range, &b0, [b_range[0], b_mid]);
+					self.recurse(a, a_range, &b1, [b_mid, b_range[1]]);
+				},
+			}
+		}
+	}
+
+	let mut search = Search { eps, budget: 4096, out: vec![] };
+	search.recurse(a, [0.0, 1.0], b, [0.0, 1.0]);
+	if search.budget == 0 {
+		// the curves overlap along a stretch rather than crossing, and the endpoint checks already split them there
+		return vec![];
+	}
+	search.out
+}
+
+/// Removes intersections that are within `eps` of an earlier one.
+fn dedup_intersections(out: &mut Vec<Intersection>, eps: f32) {
+	let mut kept: Vec<Intersection> = Vec::with_capacity(out.len());
+	for i in out.drain(..) {
+		if kept.iter().all(|k| length(sub(k.point, i.point)) > eps * 4.0) {
+			kept.push(i);
+		}
+	}
+	*out = kept;
+}
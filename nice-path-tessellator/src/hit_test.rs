@@ -0,0 +1,252 @@
+use crate::{
+	geom::{dot, solve_quadratic, sub, Conic, QuadraticBezier, Segment},
+	Bounds, FillRule, Path,
+};
+
+/// Rows and columns in a [`HitTestIndex`] grid are capped at this many.
+const MAX_GRID_SIZE: usize = 256;
+
+impl Path {
+	/// Whether `point` is inside the filled path. The winding number is computed analytically, so this agrees with the
+	/// exact curves rather than a flattened approximation.
+	pub fn contains(&self, point: [f32; 2], fill_rule: FillRule) -> bool {
+		fill_rule.is_inside(self.winding_number(point))
+	}
+
+	pub(crate) fn winding_number(&self, point: [f32; 2]) -> i32 {
+		let mut winding = 0;
+		for contour in self.contours() {
+			for seg in contour.closed_segments() {
+				for_each_monotonic(seg, |piece| winding += winding_contribution(&piece, point));
+			}
+		}
+		winding
+	}
+
+	/// Whether `point` is within `width / 2` of any segment, as if the path were stroked with that width. Like
+	/// [`Path::length`], the implicit closing lines of subpaths aren't part of the outline.
+	pub fn hit_test_stroke(&self, point: [f32; 2], width: f32) -> bool {
+		let max_dist2 = (width / 2.0) * (width / 2.0);
+		self.contours().iter().flat_map(|contour| &contour.segments).any(|seg| distance2(seg, point) <= max_dist2)
+	}
+
+	/// The segments the path is filled with, closing lines included, split wherever they turn around in x or y. Each
+	/// piece crosses any horizontal or vertical line at most once, which makes them easy to cast rays against and to
+	/// bin into a grid, e.g. to count winding numbers on the GPU.
+	pub fn monotonic_pieces(&self) -> Vec<Segment> {
+		let mut pieces = vec![];
+		for contour in self.contours() {
+			for seg in contour.closed_segments() {
+				if let Segment::Line(_) = seg {
+					pieces.push(seg);
+					continue;
+				}
+				let mut splits: Vec<f32> = seg.extrema().into_iter().flatten().collect();
+				splits.sort_by(f32::total_cmp);
+				let mut t0 = 0.0;
+				for t1 in splits.into_iter().chain([1.0]) {
+					let mut piece = seg.slice(t0, t1);
+					// the split points are only extrema up to rounding, so keep the control point in between the ends
+					if let Segment::Quadratic(QuadraticBezier { from, ctrl, to })
+					| Segment::Conic(Conic { from, ctrl, to, .. }) = &mut piece
+					{
+						for axis in 0..2 {
+							ctrl[axis] = ctrl[axis].clamp(from[axis].min(to[axis]), from[axis].max(to[axis]));
+						}
+					}
+					pieces.push(piece);
+					t0 = t1;
+				}
+			}
+		}
+		pieces
+	}
+
+	/// Builds a [`HitTestIndex`] for answering many hit tests against this path.
+	pub fn hit_test_index(&self) -> HitTestIndex {
+		HitTestIndex::new(self)
+	}
+}
+
+/// A uniform grid over a path's segments that makes [`Path::contains`] and [`Path::hit_test_stroke`] queries only look
+/// at nearby segments. Worth building for paths with many segments that are hit tested repeatedly, e.g. on every mouse
+/// move.
+#[derive(Clone, Debug)]
+pub struct HitTestIndex {
+	bounds: Option<Bounds>,
+	grid_size: [usize; 2],
+	/// y-monotonic pieces of every segment, including closing lines
+	fill_pieces: Vec<Segment>,
+	/// for each grid row, the fill pieces whose y range overlaps it
+	rows: Vec<Vec<u32>>,
+	/// every explicit segment
+	stroke_segs: Vec<Segment>,
+	/// for each grid cell, row-major, the stroke segments whose control box overlaps it
+	cells: Vec<Vec<u32>>,
+}
+impl HitTestIndex {
+	pub fn new(path: &Path) -> Self {
+		let bounds = path.control_bounds();
+		let contours = path.contours();
+
+		let mut fill_pieces = vec![];
+		let mut stroke_segs = vec![];
+		for contour in &contours {
+			stroke_segs.extend(contour.segments.iter().copied());
+			for seg in contour.closed_segments() {
+				for_each_monotonic(seg, |piece| fill_pieces.push(piece));
+			}
+		}
+
+		let side = ((stroke_segs.len() as f32).sqrt().ceil() as usize).clamp(1, MAX_GRID_SIZE);
+		let mut index = Self {
+			bounds,
+			grid_size: [side; 2],
+			fill_pieces,
+			rows: vec![vec![]; side],
+			stroke_segs,
+			cells: vec![vec![]; side * side],
+		};
+
+		for (i, piece) in index.fill_pieces.iter().enumerate() {
+			let piece_bounds = piece.control_bounds();
+			let [_, row_min] = index.cell_of(piece_bounds.left_top);
+			let [_, row_max] = index.cell_of(piece_bounds.right_bottom);
+			for row in &mut index.rows[row_min..=row_max] {
+				row.push(i as u32);
+			}
+		}
+		for (i, seg) in index.stroke_segs.iter().enumerate() {
+			let seg_bounds = seg.control_bounds();
+			let [col_min, row_min] = index.cell_of(seg_bounds.left_top);
+			let [col_max, row_max] = index.cell_of(seg_bounds.right_bottom);
+			for row in row_min..=row_max {
+				for col in col_min..=col_max {
+					index.cells[row * side + col].push(i as u32);
+				}
+			}
+		}
+
+		index
+	}
+
+	/// Same as [`Path::contains`].
+	pub fn contains(&self, point: [f32; 2], fill_rule: FillRule) -> bool {
+		fill_rule.is_inside(self.winding_number(point))
+	}
+
+	pub(crate) fn winding_number(&self, point: [f32; 2]) -> i32 {
+		let bounds = match self.bounds {
+			Some(bounds) => bounds,
+			None => return 0,
+		};
+		if point[1] < bounds.left_top[1] || point[1] > bounds.right_bottom[1] {
+			return 0;
+		}
+		let [_, row] = self.cell_of(point);
+		self.rows[row].iter().map(|&i| winding_contribution(&self.fill_pieces[i as usize], point)).sum()
+	}
+
+	/// Same as [`Path::hit_test_stroke`].
+	pub fn hit_test_stroke(&self, point: [f32; 2], width: f32) -> bool {
+		let bounds = match self.bounds {
+			Some(bounds) => bounds,
+			None => return false,
+		};
+		let radius = width / 2.0;
+		let (min, max) = ([point[0] - radius, point[1] - radius], [point[0] + radius, point[1] + radius]);
+		if max[0] < bounds.left_top[0]
+			|| max[1] < bounds.left_top[1]
+			|| min[0] > bounds.right_bottom[0]
+			|| min[1] > bounds.right_bottom[1]
+		{
+			return false;
+		}
+
+		let [col_min, row_min] = self.cell_of(min);
+		let [col_max, row_max] = self.cell_of(max);
+		(row_min..=row_max).any(|row| {
+			(col_min..=col_max).any(|col| {
+				self.cells[row * self.grid_size[0] + col]
+					.iter()
+					.any(|&i| distance2(&self.stroke_segs[i as usize], point) <= radius * radius)
+			})
+		})
+	}
+
+	/// The cell containing `point`, clamped to the grid.
+	fn cell_of(&self, point: [f32; 2]) -> [usize; 2] {
+		let bounds = self.bounds.expect("empty paths have no cells");
+		let size = [bounds.width(), bounds.height()];
+		let mut cell = [0; 2];
+		for axis in 0..2 {
+			if size[axis] > 0.0 {
+				let rel = (point[axis] - bounds.left_top[axis]) / size[axis];
+				cell[axis] =
+					((rel * self.grid_size[axis] as f32) as isize).clamp(0, self.grid_size[axis] as isize - 1) as usize;
+			}
+		}
+		cell
+	}
+}
+
+/// Calls `f` with pieces of `seg` that are each monotonic in y.
+fn for_each_monotonic(seg: Segment, mut f: impl FnMut(Segment)) {
+	match seg.extrema()[1] {
+		Some(t) => {
+			let (a, b) = seg.split(t);
+			f(a);
+			f(b);
+		},
+		None => f(seg),
+	}
+}
+
+/// How a ray from `point` towards +x crossing a y-monotonic piece changes the winding number. Ranges are half-open in
+/// y so a ray through a shared endpoint is only counted once.
+fn winding_contribution(piece: &Segment, point: [f32; 2]) -> i32 {
+	let (from, to) = (piece.from(), piece.to());
+	let (dir, y_min, y_max) = if from[1] < to[1] { (1, from[1], to[1]) } else { (-1, to[1], from[1]) };
+	if point[1] < y_min || point[1] >= y_max {
+		return 0;
+	}
+
+	let x = match piece {
+		Segment::Line(line) => {
+			let t = (point[1] - line.from[1]) / (line.to[1] - line.from[1]);
+			line.eval(t)[0]
+		},
+		Segment::Quadratic(quad) => quad.eval(monotonic_t_at_y(quad.hull(), 1.0, point[1]))[0],
+		Segment::Conic(conic) => conic.eval(monotonic_t_at_y(conic.hull(), conic.weight, point[1]))[0],
+	};
+	if x > point[0] {
+		dir
+	} else {
+		0
+	}
+}
+
+/// Where a y-monotonic quadratic, or a conic with control points `hull` and `weight`, crosses `y`. That's where the
+/// curve's height above `y`, times the conic's denominator, is zero.
+fn monotonic_t_at_y(hull: [[f32; 2]; 3], weight: f32, y: f32) -> f32 {
+	let [from, ctrl, to] = hull.map(|p| p[1]);
+	let a = from - 2.0 * weight * ctrl + to + 2.0 * (weight - 1.0) * y;
+	let b = 2.0 * (weight * ctrl - from) - 2.0 * (weight - 1.0) * y;
+	let c = from - y;
+	solve_quadratic(a, b, c)
+		.into_iter()
+		.flatten()
+		.min_by(|t0, t1| dist_to_unit(*t0).total_cmp(&dist_to_unit(*t1)))
+		.unwrap_or(0.0)
+		.clamp(0.0, 1.0)
+}
+
+/// How far `t` is outside of `[0, 1]`.
+fn dist_to_unit(t: f32) -> f32 {
+	(-t).max(t - 1.0).max(0.0)
+}
+
+fn distance2(seg: &Segment, point: [f32; 2]) -> f32 {
+	let d = sub(seg.nearest_point(point), point);
+	dot(d, d)
+}
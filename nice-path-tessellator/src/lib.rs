@@ -1,11 +1,43 @@
-use std::f32::INFINITY;
+mod arc_length;
+mod boolean;
+mod contour;
+mod dash;
+mod edit;
+mod flatten;
+mod geom;
+mod hit_test;
+mod measure;
+mod offset;
+mod orientation;
+mod overlap;
+mod scene;
+mod simplify;
+mod stroke;
+mod svg;
+mod text;
+mod transform;
+
+pub use arc_length::*;
+pub use boolean::*;
+pub use edit::*;
+pub use geom::{Conic, Intersection, LineSegment, QuadraticBezier, Segment};
+pub use hit_test::*;
+pub use measure::*;
+pub use offset::*;
+pub use scene::*;
+pub use simplify::*;
+pub use stroke::*;
+pub use svg::*;
+pub use text::*;
+pub use transform::*;
 
 use array_init::array_init;
 use lyon::lyon_tessellation::{BuffersBuilder, FillOptions, FillVertex, VertexBuffers};
 use lyon::path::Path as LyonPath;
 use lyon::tessellation::FillTessellator as LyonFillTessellator;
+use overlap::subdivide_overlapping_curves;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Path {
 	commands: Vec<PathCommands>,
 }
@@ -28,36 +60,86 @@ impl Path {
 		self.commands.push(PathCommands::QuadraticCurveTo(ctrl, to));
 		self
 	}
+
+	/// A rational quadratic, which can draw circles and ellipses exactly. See [`Conic`] for what `weight` does. Panics
+	/// unless it's positive and finite, since the curve would leave its control triangle.
+	pub fn conic_to(mut self, ctrl: [f32; 2], to: [f32; 2], weight: f32) -> Self {
+		self.commands.push(checked(PathCommands::ConicTo(ctrl, to, weight)));
+		self
+	}
 }
 
-#[derive(Clone, Copy, Debug)]
+/// Panics if `command` is a conic that [`Path::conic_to`] would reject, so every way into a path checks the same.
+fn checked(command: PathCommands) -> PathCommands {
+	if let PathCommands::ConicTo(_, _, weight) = command {
+		assert!(weight > 0.0 && weight.is_finite(), "conic weights must be positive and finite, not {}", weight);
+	}
+	command
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PathCommands {
 	MoveTo([f32; 2]),
 	LineTo([f32; 2]),
 	/// ctrl, to
 	QuadraticCurveTo([f32; 2], [f32; 2]),
+	/// ctrl, to, weight
+	ConicTo([f32; 2], [f32; 2], f32),
 }
 
+/// How overlapping subpaths and self-intersections decide what's inside, as in SVG's `fill-rule`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+	EvenOdd,
+	NonZero,
+}
+impl FillRule {
+	pub fn is_inside(self, winding: i32) -> bool {
+		match self {
+			FillRule::EvenOdd => winding % 2 != 0,
+			FillRule::NonZero => winding != 0,
+		}
+	}
+}
+
+/// There are no curves left for lyon to flatten, but it also snaps vertices to edges this close, so it's kept well
+/// below a pixel.
+const LYON_TOLERANCE: f32 = 0.01;
+
 pub fn fill_tessellate(path: &Path) -> VecPathVertBufs {
+	// lyon and the curve triangles below must agree on the chords, so both use the subdivided path
+	let path = subdivide_overlapping_curves(path);
+
 	let mut lyon_builder = LyonPath::builder();
+	let mut in_subpath = false;
 	for &cmd in &path.commands {
 		match cmd {
-			PathCommands::MoveTo(to) => lyon_builder.begin(to.into()),
+			PathCommands::MoveTo(to) => {
+				if in_subpath {
+					lyon_builder.close();
+				}
+				in_subpath = true;
+				lyon_builder.begin(to.into())
+			},
 			PathCommands::LineTo(to) => lyon_builder.line_to(to.into()),
-			PathCommands::QuadraticCurveTo(ctrl, to) => lyon_builder.quadratic_bezier_to(ctrl.into(), to.into()),
+			// lyon only fills between the chords of curves, and the curve triangles below add or remove the rest
+			PathCommands::QuadraticCurveTo(_, to) | PathCommands::ConicTo(_, to, _) => lyon_builder.line_to(to.into()),
 		};
 	}
-	lyon_builder.close();
-	let mut geometry: VertexBuffers<[f32; 2], u16> = VertexBuffers::new();
+	if in_subpath {
+		lyon_builder.close();
+	}
+	let mut geometry: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
 	LyonFillTessellator::new()
 		.tessellate_path(
 			&lyon_builder.build(),
-			&FillOptions::tolerance(INFINITY),
+			&FillOptions::tolerance(LYON_TOLERANCE),
 			&mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| vertex.position().to_array()),
 		)
 		.unwrap();
 
 	let mut bufs = VecPathVertBufs::new();
+	bufs.curve_coords = vec![[0.0; 4]; geometry.vertices.len()];
 	bufs.verts = geometry.vertices;
 	bufs.fill_idxs = geometry.indices;
 	let mut tess = FillTessellator::start(&mut bufs);
@@ -66,6 +148,7 @@ pub fn fill_tessellate(path: &Path) -> VecPathVertBufs {
 			PathCommands::MoveTo(to) => tess = tess.move_to(to),
 			PathCommands::LineTo(to) => tess = tess.line_to(to),
 			PathCommands::QuadraticCurveTo(ctrl, to) => tess = tess.quadratic_curve_to(ctrl, to),
+			PathCommands::ConicTo(ctrl, to, weight) => tess = tess.conic_to(ctrl, to, weight),
 		}
 	}
 
@@ -74,102 +157,135 @@ pub fn fill_tessellate(path: &Path) -> VecPathVertBufs {
 
 #[derive(Default)]
 pub struct VertCounter {
-	vert_count: u16,
-	fill_idx_count: u16,
-	quadratic_idx_count: u16,
+	vert_count: u32,
+	fill_idx_count: u32,
+	quadratic_idx_count: u32,
+	fan_idx_count: u32,
 }
 impl BufsBuilder for VertCounter {
-	fn verts_len(&self) -> u16 {
+	fn verts_len(&self) -> u32 {
 		self.vert_count
 	}
 
-	fn push_vert(&mut self, _pos: [f32; 2]) {
+	fn push_vert(&mut self, _pos: [f32; 2], _curve_coords: [f32; 4]) {
 		self.vert_count += 1;
 	}
 
-	fn push_fill_tri(&mut self, _idxs: [u16; 3]) {
+	fn push_fill_tri(&mut self, _idxs: [u32; 3]) {
 		self.fill_idx_count += 3;
 	}
 
-	fn push_quadratic_tri(&mut self, _idxs: [u16; 3]) {
+	fn push_quadratic_tri(&mut self, _idxs: [u32; 3]) {
 		self.quadratic_idx_count += 3;
 	}
+
+	fn push_fan_tri(&mut self, _idxs: [u32; 3]) {
+		self.fan_idx_count += 3;
+	}
 }
 
 pub struct FillTessellator<'a> {
 	bufs: &'a mut dyn BufsBuilder,
 	start: Vert,
 	pen: Vert,
+	start_pos: [f32; 2],
+	pen_pos: [f32; 2],
 }
 impl<'a> FillTessellator<'a> {
-	pub fn start(bufs: &mut dyn BufsBuilder) -> FillTessellator {
-		FillTessellator { bufs, start: Vert::Pos([0.0; 2]), pen: Vert::Pos([0.0; 2]) }
+	pub fn start(bufs: &mut dyn BufsBuilder) -> FillTessellator<'_> {
+		FillTessellator {
+			bufs,
+			start: Vert::Pos([0.0; 2]),
+			pen: Vert::Pos([0.0; 2]),
+			start_pos: [0.0; 2],
+			pen_pos: [0.0; 2],
+		}
 	}
 
 	pub fn move_to(mut self, to: [f32; 2]) -> Self {
 		self.start = Vert::Pos(to);
 		self.pen = Vert::Pos(to);
+		self.start_pos = to;
+		self.pen_pos = to;
 		self
 	}
 
 	pub fn line_to(mut self, to: [f32; 2]) -> Self {
-		self.line_to_impl(Vert::Pos(to));
+		self.line_to_impl(to);
 		self
 	}
 
-	fn quadratic_curve_to(mut self, ctrl: [f32; 2], to: [f32; 2]) -> Self {
-		let mut to = Vert::Pos(to);
-		[self.pen, _, to] = self.push_quadratic_tri([self.pen, Vert::Pos(ctrl), to]);
+	fn quadratic_curve_to(self, ctrl: [f32; 2], to: [f32; 2]) -> Self {
+		self.conic_to(ctrl, to, 1.0)
+	}
+
+	fn conic_to(mut self, ctrl: [f32; 2], to: [f32; 2], weight: f32) -> Self {
+		self.push_curve_tri([self.pen_pos, ctrl, to], weight);
 		self.line_to_impl(to);
 		self
 	}
 
-	fn line_to_impl(&mut self, to: Vert) {
-		// if self.pen != self.start {
-		// 	[self.start, self.pen, to] = self.push_fill_tri([self.start, self.pen, to]);
-		// }
-		self.pen = to;
-	}
-
-	// fn push_fill_tri(&mut self, verts: [Vert; 3]) -> [Vert; 3] {
-	// let idxs = self.make_tri(verts);
-	// self.bufs.push_fill_tri(idxs);
-	// array_init(|i| Vert::Idx(idxs[i]))
-	// 	verts
-	// }
-
-	fn push_quadratic_tri(&mut self, verts: [Vert; 3]) -> [Vert; 3] {
-		let idxs = self.make_tri(verts);
-		self.bufs.push_quadratic_tri(idxs);
-		array_init(|i| Vert::Idx(idxs[i]))
-	}
-
-	fn make_tri(&mut self, verts: [Vert; 3]) -> [u16; 3] {
-		let mut idxs = [0; 3];
-		for (i, &vert) in verts.iter().enumerate() {
-			match vert {
-				Vert::Pos(pos) => {
-					idxs[i] = self.bufs.verts_len();
-					self.bufs.push_vert(pos);
-				},
-				Vert::Idx(idx) => idxs[i] = idx,
-			}
+	/// Fans the chords out from the start of the subpath. Each triangle is wound the same way as its chord, so counting
+	/// windings over the fan and the curve triangles gives the path's winding number.
+	fn line_to_impl(&mut self, to: [f32; 2]) {
+		if self.pen_pos != self.start_pos && to != self.start_pos && to != self.pen_pos {
+			let idxs = [self.shared_idx(self.start), self.shared_idx(self.pen), self.shared_idx(Vert::Pos(to))];
+			self.bufs.push_fan_tri(idxs);
+			self.start = Vert::Idx(idxs[0]);
+			self.pen = Vert::Idx(idxs[2]);
+		} else {
+			self.pen = Vert::Pos(to);
+		}
+		self.pen_pos = to;
+	}
+
+	/// The index of a fan vertex, pushing it if it isn't in the buffers yet.
+	fn shared_idx(&mut self, vert: Vert) -> u32 {
+		match vert {
+			Vert::Idx(idx) => idx,
+			Vert::Pos(pos) => {
+				let idx = self.bufs.verts_len();
+				self.bufs.push_vert(pos, [0.0; 4]);
+				idx
+			},
 		}
-		idxs
+	}
+
+	/// Curve triangles never share vertices with anything, because every vertex carries its own corner's coordinates
+	/// for the curve's implicit equation.
+	fn push_curve_tri(&mut self, verts: [[f32; 2]; 3], weight: f32) {
+		let first = self.bufs.verts_len();
+		for (i, pos) in verts.into_iter().enumerate() {
+			let mut curve_coords = [0.0, 0.0, 0.0, weight];
+			curve_coords[i] = 1.0;
+			self.bufs.push_vert(pos, curve_coords);
+		}
+		self.bufs.push_quadratic_tri(array_init(|i| first + i as u32));
 	}
 }
 pub trait BufsBuilder {
-	fn verts_len(&self) -> u16;
-	fn push_vert(&mut self, pos: [f32; 2]);
-	fn push_fill_tri(&mut self, idxs: [u16; 3]);
-	fn push_quadratic_tri(&mut self, idxs: [u16; 3]);
+	fn verts_len(&self) -> u32;
+	/// `curve_coords` are the vertex's barycentric coordinates in its curve triangle, followed by the curve's conic
+	/// weight. They're all zero for vertices that aren't part of a curve triangle.
+	fn push_vert(&mut self, pos: [f32; 2], curve_coords: [f32; 4]);
+	fn push_fill_tri(&mut self, idxs: [u32; 3]);
+	fn push_quadratic_tri(&mut self, idxs: [u32; 3]);
+	/// See [`VecPathVertBufs::fan_idxs`]. Builders that only fill with the even-odd rule can leave the fan out.
+	fn push_fan_tri(&mut self, _idxs: [u32; 3]) {}
 }
 
-#[derive(Clone, Debug)]
+/// `left_top` and `right_bottom` cover every vertex, including curve control points, so they're a loose fit. Use
+/// [`Path::bounds`] for exact bounds.
+#[derive(Clone, Debug, Default)]
 pub struct VecPathVertBufs {
 	pub verts: Vec<[f32; 2]>,
-	pub fill_idxs: Vec<u16>,
-	pub quadratic_idxs: Vec<u16>,
+	/// one per vertex, see [`BufsBuilder::push_vert`]
+	pub curve_coords: Vec<[f32; 4]>,
+	pub fill_idxs: Vec<u32>,
+	/// curve triangles, for quadratics as well as conics
+	pub quadratic_idxs: Vec<u32>,
+	/// a fan over the chords of every subpath, wound like the path, for counting windings instead of parity
+	pub fan_idxs: Vec<u32>,
 	pub left_top: [Option<f32>; 2],
 	pub right_bottom: [Option<f32>; 2],
 }
@@ -177,42 +293,51 @@ impl VecPathVertBufs {
 	pub fn new() -> Self {
 		VecPathVertBufs {
 			verts: vec![],
+			curve_coords: vec![],
 			fill_idxs: vec![],
 			quadratic_idxs: vec![],
+			fan_idxs: vec![],
 			left_top: [None; 2],
 			right_bottom: [None; 2],
 		}
 	}
 }
 impl BufsBuilder for VecPathVertBufs {
-	fn verts_len(&self) -> u16 {
-		self.verts.len() as _
+	fn verts_len(&self) -> u32 {
+		self.verts.len().try_into().expect("more vertices than u32 indices can reach")
 	}
 
-	fn push_vert(&mut self, pos: [f32; 2]) {
+	fn push_vert(&mut self, pos: [f32; 2], curve_coords: [f32; 4]) {
 		self.left_top[0] = self.left_top[0].map(|x| x.min(pos[0])).or(Some(pos[0]));
 		self.left_top[1] = self.left_top[1].map(|x| x.min(pos[1])).or(Some(pos[1]));
 		self.right_bottom[0] = self.right_bottom[0].map(|x| x.max(pos[0])).or(Some(pos[0]));
 		self.right_bottom[1] = self.right_bottom[1].map(|x| x.max(pos[1])).or(Some(pos[1]));
 
 		self.verts.push(pos);
+		self.curve_coords.push(curve_coords);
 	}
 
-	fn push_fill_tri(&mut self, idxs: [u16; 3]) {
+	fn push_fill_tri(&mut self, idxs: [u32; 3]) {
 		self.fill_idxs.push(idxs[0]);
 		self.fill_idxs.push(idxs[1]);
 		self.fill_idxs.push(idxs[2]);
 	}
 
-	fn push_quadratic_tri(&mut self, idxs: [u16; 3]) {
+	fn push_quadratic_tri(&mut self, idxs: [u32; 3]) {
 		self.quadratic_idxs.push(idxs[0]);
 		self.quadratic_idxs.push(idxs[1]);
 		self.quadratic_idxs.push(idxs[2]);
 	}
+
+	fn push_fan_tri(&mut self, idxs: [u32; 3]) {
+		self.fan_idxs.push(idxs[0]);
+		self.fan_idxs.push(idxs[1]);
+		self.fan_idxs.push(idxs[2]);
+	}
 }
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Vert {
 	Pos([f32; 2]),
-	Idx(u16),
+	Idx(u32),
 }
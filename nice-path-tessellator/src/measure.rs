@@ -0,0 +1,149 @@
+use crate::{
+	geom::{cross, Segment, GAUSS_LEGENDRE_5},
+	Path,
+};
+
+/// Gauss-Legendre nodes and weights on `[0, 1]`. Three points integrate polynomials up to degree 5 exactly, which
+/// covers every integrand below for lines and quadratics.
+const GAUSS_LEGENDRE_3: [(f32, f32); 3] = [(0.11270167, 5.0 / 18.0), (0.5, 8.0 / 18.0), (0.8872983, 5.0 / 18.0)];
+/// Conics are rational, so no rule integrates them exactly, but they're smooth enough that this many pieces of
+/// [`GAUSS_LEGENDRE_5`] leave no error that f32 can show.
+const CONIC_INTEGRAL_PIECES: usize = 8;
+
+/// An axis-aligned rectangle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+	pub left_top: [f32; 2],
+	pub right_bottom: [f32; 2],
+}
+impl Bounds {
+	pub fn from_point(point: [f32; 2]) -> Self {
+		Self { left_top: point, right_bottom: point }
+	}
+
+	pub fn include(&mut self, point: [f32; 2]) {
+		self.left_top = [self.left_top[0].min(point[0]), self.left_top[1].min(point[1])];
+		self.right_bottom = [self.right_bottom[0].max(point[0]), self.right_bottom[1].max(point[1])];
+	}
+
+	pub fn width(&self) -> f32 {
+		self.right_bottom[0] - self.left_top[0]
+	}
+
+	pub fn height(&self) -> f32 {
+		self.right_bottom[1] - self.left_top[1]
+	}
+
+	/// Whether the two boxes overlap, or are at most `eps` apart.
+	pub fn overlaps(&self, other: &Bounds, eps: f32) -> bool {
+		self.left_top[0] <= other.right_bottom[0] + eps
+			&& other.left_top[0] <= self.right_bottom[0] + eps
+			&& self.left_top[1] <= other.right_bottom[1] + eps
+			&& other.left_top[1] <= self.right_bottom[1] + eps
+	}
+}
+
+/// The direction a contour winds in, as it appears on screen (y pointing down).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindingDirection {
+	Clockwise,
+	CounterClockwise,
+}
+
+impl Path {
+	/// The tightest box around the filled shape. Curves contribute their extrema rather than their control points.
+	pub fn bounds(&self) -> Option<Bounds> {
+		let mut bounds: Option<Bounds> = None;
+		let mut include = |p| match &mut bounds {
+			Some(bounds) => bounds.include(p),
+			None => bounds = Some(Bounds::from_point(p)),
+		};
+		for contour in self.contours() {
+			include(contour.start);
+			for seg in &contour.segments {
+				let seg_bounds = seg.bounding_box();
+				include(seg_bounds.left_top);
+				include(seg_bounds.right_bottom);
+			}
+		}
+		bounds
+	}
+
+	/// The box around every point in the path, including control points. This is cheaper than [`Path::bounds`] and
+	/// always contains it.
+	pub fn control_bounds(&self) -> Option<Bounds> {
+		let mut bounds: Option<Bounds> = None;
+		let mut include = |p| match &mut bounds {
+			Some(bounds) => bounds.include(p),
+			None => bounds = Some(Bounds::from_point(p)),
+		};
+		for contour in self.contours() {
+			include(contour.start);
+			for seg in &contour.segments {
+				match seg {
+					Segment::Line(_) => {},
+					Segment::Quadratic(quad) => include(quad.ctrl),
+					Segment::Conic(conic) => include(conic.ctrl),
+				}
+				include(seg.to());
+			}
+		}
+		bounds
+	}
+
+	/// The signed area enclosed by the path, with every subpath implicitly closed. Clockwise subpaths count positive
+	/// and counter-clockwise ones negative, so holes only subtract if they wind opposite to their outline.
+	pub fn area(&self) -> f32 {
+		let mut area = 0.0;
+		for contour in self.contours() {
+			for seg in contour.closed_segments() {
+				area += integrate(&seg, |p, d| cross(p, d) / 2.0);
+			}
+		}
+		area
+	}
+
+	/// The center of mass of the enclosed area, weighted the same way as [`Path::area`]. `None` if the area is zero.
+	pub fn centroid(&self) -> Option<[f32; 2]> {
+		let (mut area, mut moment_x, mut moment_y) = (0.0, 0.0, 0.0);
+		for contour in self.contours() {
+			for seg in contour.closed_segments() {
+				area += integrate(&seg, |p, d| cross(p, d) / 2.0);
+				moment_x += integrate(&seg, |p, d| p[0] * p[0] * d[1] / 2.0);
+				moment_y -= integrate(&seg, |p, d| p[1] * p[1] * d[0] / 2.0);
+			}
+		}
+		(area != 0.0).then(|| [moment_x / area, moment_y / area])
+	}
+
+	/// Whether the path as a whole winds clockwise or counter-clockwise, going by the sign of [`Path::area`].
+	pub fn winding_direction(&self) -> Option<WindingDirection> {
+		let area = self.area();
+		if area > 0.0 {
+			Some(WindingDirection::Clockwise)
+		} else if area < 0.0 {
+			Some(WindingDirection::CounterClockwise)
+		} else {
+			None
+		}
+	}
+
+	/// The total arc length of every segment. The implicit closing lines of subpaths aren't included.
+	pub fn length(&self) -> f32 {
+		self.contours().iter().flat_map(|contour| &contour.segments).map(|seg| seg.length()).sum()
+	}
+}
+
+/// Integrates `f(position, derivative)` over `t` in `[0, 1]`.
+fn integrate(seg: &Segment, f: impl Fn([f32; 2], [f32; 2]) -> f32) -> f32 {
+	match seg {
+		Segment::Conic(_) => {
+			let pieces = CONIC_INTEGRAL_PIECES as f32;
+			(0..CONIC_INTEGRAL_PIECES)
+				.flat_map(|piece| GAUSS_LEGENDRE_5.iter().map(move |&(t, w)| ((piece as f32 + t) / pieces, w / pieces)))
+				.map(|(t, w)| w * f(seg.eval(t), seg.derivative(t)))
+				.sum()
+		},
+		_ => GAUSS_LEGENDRE_3.iter().map(|&(t, w)| w * f(seg.eval(t), seg.derivative(t))).sum(),
+	}
+}
@@ -0,0 +1,266 @@
+use std::f32::consts::FRAC_PI_4;
+
+use crate::{
+	boolean::resolve,
+	geom::{add, cross, dot, length, scale, sub, LineSegment, QuadraticBezier, Segment},
+	FillRule, Path,
+};
+
+/// How far offset curves may stray from the exact offset, in path units, when no tolerance is given.
+pub const DEFAULT_OFFSET_TOLERANCE: f32 = 0.05;
+/// How many times a quadratic may be halved while fitting its offset.
+const MAX_OFFSET_DEPTH: u32 = 10;
+
+/// How the offset outlines of two segments are connected where they'd otherwise leave a gap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineJoin {
+	/// Extends both outlines until they meet, unless that point is further than `limit` times the offset distance from
+	/// the corner, in which case it falls back to [`LineJoin::Bevel`].
+	Miter(f32),
+	Round,
+	Bevel,
+}
+
+impl Path {
+	/// Grows the filled shape by `distance`, or shrinks it if `distance` is negative. The path is read with the even-odd
+	/// rule, like the fill pipeline draws it. Quadratics and conics are offset to quadratics
+	/// within [`DEFAULT_OFFSET_TOLERANCE`], so the result has no conics.
+	pub fn offset(&self, distance: f32, join: LineJoin) -> Path {
+		self.offset_with_tolerance(distance, join, DEFAULT_OFFSET_TOLERANCE)
+	}
+
+	/// Same as [`Path::offset`], with a custom tolerance for the offset curves.
+	pub fn offset_with_tolerance(&self, distance: f32, join: LineJoin, tolerance: f32) -> Path {
+		// after resolving, every contour has the inside on its right, so "outwards" is always to the left
+		let index = self.hit_test_index();
+		let normalized = resolve(&[self], |p| FillRule::EvenOdd.is_inside(index.winding_number(p)));
+		if distance == 0.0 {
+			return normalized;
+		}
+
+		let mut raw = Path::start();
+		for contour in normalized.contours() {
+			let segs: Vec<Segment> = contour.closed_segments().collect();
+			let offset = offset_segments(&segs, true, distance, join, tolerance);
+			if let Some(first) = offset.first() {
+				raw = raw.move_to(first.from());
+				raw = push_segments(raw, &offset);
+			}
+		}
+
+		// offsetting turns cusps and concave corners inside out, which leaves loops with a winding number of zero or
+		// less, so only keep what the offset outlines wind around positively
+		let index = raw.hit_test_index();
+		resolve(&[&raw], |p| index.winding_number(p) > 0)
+	}
+}
+
+/// Offsets a chain of segments to their left by `distance`, joining consecutive offset segments. A `closed` chain is
+/// also joined from its last segment back to its first.
+pub(crate) fn offset_segments(
+	segs: &[Segment],
+	closed: bool,
+	distance: f32,
+	join: LineJoin,
+	tolerance: f32,
+) -> Vec<Segment> {
+	let segs: Vec<Segment> = segs.iter().copied().filter(|seg| seg.from() != seg.to()).collect();
+	let mut out = vec![];
+	for (i, seg) in segs.iter().enumerate() {
+		if i > 0 {
+			push_join(&mut out, seg.from(), end_tangent(&segs[i - 1]), start_tangent(seg), distance, join);
+		}
+		offset_segment(seg, distance, tolerance, &mut out);
+	}
+	if closed {
+		if let (Some(first), Some(last)) = (segs.first(), segs.last()) {
+			push_join(&mut out, first.from(), end_tangent(last), start_tangent(first), distance, join);
+		}
+	}
+	out
+}
+
+/// Appends `segs` to `path`, assuming the pen is already at the first segment's start.
+pub(crate) fn push_segments(mut path: Path, segs: &[Segment]) -> Path {
+	for seg in segs {
+		path = match seg {
+			Segment::Line(line) => path.line_to(line.to),
+			Segment::Quadratic(quad) => path.quadratic_curve_to(quad.ctrl, quad.to),
+			Segment::Conic(conic) => path.conic_to(conic.ctrl, conic.to, conic.weight),
+		};
+	}
+	path
+}
+
+fn offset_segment(seg: &Segment, distance: f32, tolerance: f32, out: &mut Vec<Segment>) {
+	match seg {
+		Segment::Line(line) => {
+			let n = scale(left_normal(sub(line.to, line.from)), distance);
+			out.push(Segment::Line(LineSegment::new(add(line.from, n), add(line.to, n))));
+		},
+		Segment::Quadratic(quad) => offset_quadratic(quad, distance, tolerance, 0, out),
+		// the offset of a conic isn't a conic either, so it's fitted to the offsets of quadratics close to the conic
+		Segment::Conic(conic) => {
+			for quad in conic.to_quadratics(tolerance / 2.0) {
+				offset_quadratic(&quad, distance, tolerance / 2.0, 0, out);
+			}
+		},
+	}
+}
+
+/// Fits a quadratic to the offset of `quad`: the endpoints are offset along their normals, and the control point is
+/// where the offset end tangents meet. Halves `quad` until that's within `tolerance` of the exact offset.
+fn offset_quadratic(quad: &QuadraticBezier, distance: f32, tolerance: f32, depth: u32, out: &mut Vec<Segment>) {
+	let seg = Segment::Quadratic(*quad);
+	let (t0, t1) = (start_tangent(&seg), end_tangent(&seg));
+	let from = add(quad.from, scale(left_normal(t0), distance));
+	let to = add(quad.to, scale(left_normal(t1), distance));
+
+	// a quadratic whose control point is on its chord is a line
+	let chord = sub(quad.to, quad.from);
+	if cross(sub(quad.ctrl, quad.from), chord).abs() <= 1e-6 * dot(chord, chord) && dot(t0, t1) > 0.0 {
+		out.push(Segment::Line(LineSegment::new(from, to)));
+		return;
+	}
+
+	let fitted = tangent_intersection(from, t0, to, t1).map(|ctrl| QuadraticBezier::new(from, ctrl, to));
+	let fits = fitted.is_some_and(|fitted| {
+		[0.25, 0.5, 0.75].iter().all(|&t| {
+			let exact = add(quad.eval(t), scale(left_normal(tangent_at(quad, t)), distance));
+			length(sub(fitted.nearest_point(exact), exact)) <= tolerance
+		})
+	});
+
+	match fitted {
+		Some(fitted) if fits => out.push(Segment::Quadratic(fitted)),
+		_ if depth >= MAX_OFFSET_DEPTH => out.push(Segment::Line(LineSegment::new(from, to))),
+		_ => {
+			let (a, b) = quad.split(0.5);
+			offset_quadratic(&a, distance, tolerance, depth + 1, out);
+			offset_quadratic(&b, distance, tolerance, depth + 1, out);
+		},
+	}
+}
+
+/// Where the ray from `from` along `t0` meets the ray arriving at `to` along `t1`, if it's ahead of `from` and behind
+/// `to`.
+pub(crate) fn tangent_intersection(from: [f32; 2], t0: [f32; 2], to: [f32; 2], t1: [f32; 2]) -> Option<[f32; 2]> {
+	let denom = cross(t0, t1);
+	if denom.abs() <= 1e-6 * length(t0) * length(t1) {
+		return None;
+	}
+	let offset = sub(to, from);
+	let s = cross(offset, t1) / denom;
+	let u = cross(t0, offset) / denom;
+	(s > 0.0 && u > 0.0).then(|| add(from, scale(t0, s)))
+}
+
+/// Connects the offset end of one segment to the offset start of the next, around their shared vertex `corner`.
+fn push_join(out: &mut Vec<Segment>, corner: [f32; 2], d0: [f32; 2], d1: [f32; 2], distance: f32, join: LineJoin) {
+	let u0 = scale(left_normal(d0), distance.signum());
+	let u1 = scale(left_normal(d1), distance.signum());
+	let radius = distance.abs();
+	let a = add(corner, scale(u0, radius));
+	let b = add(corner, scale(u1, radius));
+	if length(sub(a, b)) <= 1e-6 * radius {
+		return;
+	}
+
+	let turn = cross(d0, d1);
+	let reverses = turn.abs() <= 1e-6 * length(d0) * length(d1) && dot(d0, d1) < 0.0;
+	if !reverses && turn * distance < 0.0 {
+		// the offset outlines overlap here. Going through the corner keeps the outline's winding consistent, and the
+		// loop it makes is removed when the outline is resolved.
+		out.push(Segment::Line(LineSegment::new(a, corner)));
+		out.push(Segment::Line(LineSegment::new(corner, b)));
+		return;
+	}
+
+	match join {
+		LineJoin::Miter(limit) => {
+			match tangent_intersection(a, d0, b, d1) {
+				Some(tip) if length(sub(tip, corner)) <= limit * radius => {
+					out.push(Segment::Line(LineSegment::new(a, tip)));
+					out.push(Segment::Line(LineSegment::new(tip, b)));
+				},
+				_ => out.push(Segment::Line(LineSegment::new(a, b))),
+			};
+		},
+		LineJoin::Round => push_arc(out, corner, radius, u0, u1, d0),
+		LineJoin::Bevel => out.push(Segment::Line(LineSegment::new(a, b))),
+	}
+}
+
+/// Appends a circular arc around `center` from direction `u0` to `u1`, bulging towards `forward`, as quadratics of at
+/// most 45 degrees each.
+pub(crate) fn push_arc(
+	out: &mut Vec<Segment>,
+	center: [f32; 2],
+	radius: f32,
+	u0: [f32; 2],
+	u1: [f32; 2],
+	forward: [f32; 2],
+) {
+	let mut sweep = cross(u0, u1).atan2(dot(u0, u1));
+	if dot(rotate(u0, sweep / 2.0), forward) < 0.0 {
+		// take the long way around, so the arc bulges forwards
+		sweep -= sweep.signum() * 2.0 * std::f32::consts::PI;
+	}
+
+	let pieces = (sweep.abs() / FRAC_PI_4).ceil().max(1.0) as usize;
+	let step = sweep / pieces as f32;
+	let ctrl_radius = radius / (step / 2.0).cos();
+	let mut from = add(center, scale(u0, radius));
+	for i in 0..pieces {
+		let start = rotate(u0, step * i as f32);
+		let to = if i + 1 == pieces {
+			add(center, scale(u1, radius))
+		} else {
+			add(center, scale(rotate(start, step), radius))
+		};
+		let ctrl = add(center, scale(rotate(start, step / 2.0), ctrl_radius));
+		out.push(Segment::Quadratic(QuadraticBezier::new(from, ctrl, to)));
+		from = to;
+	}
+}
+
+pub(crate) fn start_tangent(seg: &Segment) -> [f32; 2] {
+	match seg {
+		Segment::Line(line) => sub(line.to, line.from),
+		Segment::Quadratic(quad) => tangent_at(quad, 0.0),
+		// a conic starts and ends in the same directions as the quadratic with its control points
+		Segment::Conic(conic) => tangent_at(&QuadraticBezier::new(conic.from, conic.ctrl, conic.to), 0.0),
+	}
+}
+
+pub(crate) fn end_tangent(seg: &Segment) -> [f32; 2] {
+	match seg {
+		Segment::Line(line) => sub(line.to, line.from),
+		Segment::Quadratic(quad) => tangent_at(quad, 1.0),
+		Segment::Conic(conic) => tangent_at(&QuadraticBezier::new(conic.from, conic.ctrl, conic.to), 1.0),
+	}
+}
+
+/// The direction of travel at `t`, which is still defined where the derivative vanishes because the control point
+/// coincides with an endpoint.
+fn tangent_at(quad: &QuadraticBezier, t: f32) -> [f32; 2] {
+	let d = quad.derivative(t);
+	if d != [0.0, 0.0] {
+		return d;
+	}
+	sub(quad.to, quad.from)
+}
+
+/// The unit normal to the left of `d`, on screen.
+pub(crate) fn left_normal(d: [f32; 2]) -> [f32; 2] {
+	let len = length(d);
+	if len == 0.0 {
+		return [0.0, 0.0];
+	}
+	[d[1] / len, -d[0] / len]
+}
+
+fn rotate(v: [f32; 2], angle: f32) -> [f32; 2] {
+	let (sin, cos) = angle.sin_cos();
+	[v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos]
+}
@@ -0,0 +1,40 @@
+use crate::{FillRule, Path, WindingDirection};
+
+impl Path {
+	/// Reverses every subpath in place, keeping the subpaths in the same order. Quadratics keep their control points,
+	/// so the shape is unchanged, but the signed area and winding numbers flip.
+	pub fn reverse(&mut self) {
+		for i in 0..self.subpath_ranges().len() {
+			self.reverse_subpath(i);
+		}
+	}
+
+	/// Orients outlines to wind in `outer`, and holes the other way, so the path fills the same with the nonzero rule as
+	/// with even-odd. A subpath is a hole if it's inside an odd number of the other subpaths.
+	///
+	/// This assumes the subpaths don't cross each other, like the output of the boolean operations. Subpaths without
+	/// area are left alone.
+	pub fn normalize_orientation(&mut self, outer: WindingDirection) {
+		let subpaths = self.subpaths();
+		let areas: Vec<f32> = subpaths.iter().map(|subpath| subpath.area()).collect();
+		// walk backwards, so reversing a subpath can't move the ones that are still to come
+		for i in (0..subpaths.len()).rev() {
+			let sample = match subpaths[i].contours().first().and_then(|contour| contour.segments.first()) {
+				Some(seg) if areas[i] != 0.0 => seg.eval(0.5),
+				_ => continue,
+			};
+			let depth = (0..subpaths.len())
+				.filter(|&j| j != i && areas[j] != 0.0 && subpaths[j].contains(sample, FillRule::NonZero))
+				.count();
+			let direction = match (depth % 2 == 0, outer) {
+				(true, direction) => direction,
+				(false, WindingDirection::Clockwise) => WindingDirection::CounterClockwise,
+				(false, WindingDirection::CounterClockwise) => WindingDirection::Clockwise,
+			};
+			let clockwise = areas[i] > 0.0;
+			if clockwise != (direction == WindingDirection::Clockwise) {
+				self.reverse_subpath(i);
+			}
+		}
+	}
+}
@@ -0,0 +1,124 @@
+use crate::{
+	geom::{tri_area2, tris_overlap, Conic, QuadraticBezier},
+	Path, PathCommands,
+};
+
+/// How many times a single quadratic may be halved. Curves that actually cross each other always have overlapping
+/// hulls near the crossing, so this is what stops the subdivision for them.
+const MAX_DEPTH: u32 = 6;
+/// Overlaps thinner than this (in path units) are ignored, so hulls that merely touch aren't split.
+const OVERLAP_EPS: f32 = 1e-3;
+
+#[derive(Clone, Copy)]
+enum Item {
+	MoveTo([f32; 2]),
+	LineTo([f32; 2]),
+	Quadratic(QuadraticBezier, u32),
+	Conic(Conic, u32),
+}
+impl Item {
+	fn hull(&self) -> Option<[[f32; 2]; 3]> {
+		match self {
+			Item::Quadratic(quad, _) => Some(quad.hull()),
+			Item::Conic(conic, _) => Some(conic.hull()),
+			_ => None,
+		}
+	}
+}
+
+/// Splits curve segments whose control triangles overlap another curve's control triangle, until all curve triangles
+/// are disjoint. Overlapping Loop-Blinn triangles double-count coverage and show up as notches.
+pub(crate) fn subdivide_overlapping_curves(path: &Path) -> Path {
+	let mut items = Vec::with_capacity(path.commands.len());
+	let mut pen = [0.0; 2];
+	for &cmd in &path.commands {
+		let (item, to) = match cmd {
+			PathCommands::MoveTo(to) => (Item::MoveTo(to), to),
+			PathCommands::LineTo(to) => (Item::LineTo(to), to),
+			PathCommands::QuadraticCurveTo(ctrl, to) => (Item::Quadratic(QuadraticBezier::new(pen, ctrl, to), 0), to),
+			PathCommands::ConicTo(ctrl, to, weight) => (Item::Conic(Conic::new(pen, ctrl, to, weight), 0), to),
+		};
+		items.push(item);
+		pen = to;
+	}
+
+	loop {
+		let overlapping = find_overlapping(&items);
+		if overlapping.is_empty() {
+			break;
+		}
+
+		let mut split_any = false;
+		let mut next = Vec::with_capacity(items.len() + overlapping.len());
+		let mut overlapping = overlapping.into_iter().peekable();
+		for (i, item) in items.into_iter().enumerate() {
+			match item {
+				Item::Quadratic(quad, depth) if overlapping.peek() == Some(&i) && depth < MAX_DEPTH => {
+					let (a, b) = quad.split(0.5);
+					next.push(Item::Quadratic(a, depth + 1));
+					next.push(Item::Quadratic(b, depth + 1));
+					split_any = true;
+				},
+				Item::Conic(conic, depth) if overlapping.peek() == Some(&i) && depth < MAX_DEPTH => {
+					let (a, b) = conic.split(0.5);
+					next.push(Item::Conic(a, depth + 1));
+					next.push(Item::Conic(b, depth + 1));
+					split_any = true;
+				},
+				item => next.push(item),
+			}
+			if overlapping.peek() == Some(&i) {
+				overlapping.next();
+			}
+		}
+		items = next;
+
+		if !split_any {
+			break;
+		}
+	}
+
+	let commands = items
+		.into_iter()
+		.map(|item| match item {
+			Item::MoveTo(to) => PathCommands::MoveTo(to),
+			Item::LineTo(to) => PathCommands::LineTo(to),
+			Item::Quadratic(quad, _) => PathCommands::QuadraticCurveTo(quad.ctrl, quad.to),
+			Item::Conic(conic, _) => PathCommands::ConicTo(conic.ctrl, conic.to, conic.weight),
+		})
+		.collect();
+	Path { commands }
+}
+
+/// Returns the sorted indices of every curve item whose hull overlaps another one.
+fn find_overlapping(items: &[Item]) -> Vec<usize> {
+	// (item index, hull, min x, max x), sorted by min x so we can sweep
+	let mut hulls: Vec<_> = items
+		.iter()
+		.enumerate()
+		.filter_map(|(i, item)| match item.hull() {
+			Some(hull) if tri_area2(&hull).abs() > OVERLAP_EPS => {
+				let min_x = hull.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min);
+				let max_x = hull.iter().map(|p| p[0]).fold(f32::NEG_INFINITY, f32::max);
+				Some((i, hull, min_x, max_x))
+			},
+			_ => None,
+		})
+		.collect();
+	hulls.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+	let mut overlapping = vec![false; items.len()];
+	for (j, &(a_idx, a_hull, _, a_max_x)) in hulls.iter().enumerate() {
+		for &(b_idx, b_hull, b_min_x, _) in &hulls[j + 1..] {
+			if b_min_x >= a_max_x {
+				break;
+			}
+			if tris_overlap(&a_hull, &b_hull, OVERLAP_EPS) {
+				overlapping[a_idx] = true;
+				overlapping[b_idx] = true;
+			}
+		}
+	}
+
+	overlapping.into_iter().enumerate().filter(|&(_, o)| o).map(|(i, _)| i).collect()
+}
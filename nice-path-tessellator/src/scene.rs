@@ -0,0 +1,463 @@
+use crate::{FillRule, Path, Transform};
+
+/// An sRGB colour with straight (not premultiplied) alpha. Every component is between 0 and 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+	pub r: f32,
+	pub g: f32,
+	pub b: f32,
+	pub a: f32,
+}
+impl Color {
+	pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+	pub const BLACK: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+	pub const WHITE: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+
+	pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+		Self { r, g, b, a }
+	}
+
+	pub fn from_rgba8(rgba: [u8; 4]) -> Self {
+		let [r, g, b, a] = rgba.map(|c| c as f32 / 255.0);
+		Self { r, g, b, a }
+	}
+
+	/// Rounded to 8 bits per component.
+	pub fn to_rgba8(self) -> [u8; 4] {
+		[self.r, self.g, self.b, self.a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+	}
+
+	pub fn with_alpha(self, a: f32) -> Self {
+		Self { a, ..self }
+	}
+
+	pub fn premultiplied(self) -> [f32; 4] {
+		[self.r * self.a, self.g * self.a, self.b * self.a, self.a]
+	}
+
+	pub fn from_premultiplied(rgba: [f32; 4]) -> Self {
+		match rgba[3] {
+			a if a > 0.0 => Self { r: rgba[0] / a, g: rgba[1] / a, b: rgba[2] / a, a },
+			_ => Color::TRANSPARENT,
+		}
+	}
+}
+
+/// What a gradient does past its first and last stops, as in SVG's `spreadMethod`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpreadMethod {
+	/// Keeps the colour of the nearest end.
+	Pad,
+	/// Repeats the gradient backwards and forwards.
+	Reflect,
+	Repeat,
+}
+impl SpreadMethod {
+	/// Maps any gradient position into `[0, 1]`.
+	pub fn apply(self, t: f32) -> f32 {
+		match self {
+			SpreadMethod::Pad => t.clamp(0.0, 1.0),
+			SpreadMethod::Repeat => t - t.floor(),
+			SpreadMethod::Reflect => 1.0 - ((t * 0.5 - (t * 0.5).floor()) * 2.0 - 1.0).abs(),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientStop {
+	/// Where the stop is, between 0 and 1. Stops are sorted by offset.
+	pub offset: f32,
+	pub color: Color,
+}
+
+/// The colour at `t`, which must already be spread into `[0, 1]`. Colours are interpolated with premultiplied alpha,
+/// like browsers do, so fading to a transparent stop doesn't darken the colour on the way.
+pub fn gradient_color(stops: &[GradientStop], t: f32) -> Color {
+	let after = stops.iter().position(|stop| stop.offset > t);
+	match after {
+		None => stops.last().map_or(Color::TRANSPARENT, |stop| stop.color),
+		Some(0) => stops[0].color,
+		Some(i) => {
+			let (a, b) = (&stops[i - 1], &stops[i]);
+			let s = (t - a.offset) / (b.offset - a.offset);
+			let (pa, pb) = (a.color.premultiplied(), b.color.premultiplied());
+			Color::from_premultiplied([0, 1, 2, 3].map(|c| pa[c] + (pb[c] - pa[c]) * s))
+		},
+	}
+}
+
+/// A gradient along the line from `start` to `end`, with `t` going from 0 to 1 along it and constant across it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearGradient {
+	pub start: [f32; 2],
+	pub end: [f32; 2],
+	pub stops: Vec<GradientStop>,
+	pub spread: SpreadMethod,
+	/// Maps the gradient's own space, where `start` and `end` are, to the space of the path it paints.
+	pub transform: Transform,
+}
+impl LinearGradient {
+	/// Maps the painted path's space to a space where `t` is the x coordinate, or `None` if the gradient is degenerate
+	/// and only its last stop shows.
+	pub fn unit_transform(&self) -> Option<Transform> {
+		let dir = [self.end[0] - self.start[0], self.end[1] - self.start[1]];
+		let len2 = dir[0] * dir[0] + dir[1] * dir[1];
+		if len2 == 0.0 {
+			return None;
+		}
+		// the y axis is only there to keep the transform invertible
+		let to_unit = Transform([dir[0] / len2, -dir[1], dir[1] / len2, dir[0], 0.0, 0.0]);
+		let to_unit = Transform::translate(-self.start[0], -self.start[1]).then(&to_unit);
+		Some(self.transform.invert()?.then(&to_unit))
+	}
+}
+
+/// A gradient over circles that blend from the focal point to the circle around `center`, as in SVG 1.1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialGradient {
+	pub center: [f32; 2],
+	pub radius: f32,
+	/// Where `t` is 0. It's moved just inside the circle if it's outside.
+	pub focal: [f32; 2],
+	pub stops: Vec<GradientStop>,
+	pub spread: SpreadMethod,
+	/// Maps the gradient's own space, where `center` and `focal` are, to the space of the path it paints.
+	pub transform: Transform,
+}
+impl RadialGradient {
+	/// Maps the painted path's space to a space where the circle is the unit circle around the origin, or `None` if the
+	/// gradient is degenerate and only its last stop shows.
+	pub fn unit_transform(&self) -> Option<Transform> {
+		if self.radius <= 0.0 {
+			return None;
+		}
+		let to_unit = Transform::translate(-self.center[0], -self.center[1])
+			.then(&Transform::scale(1.0 / self.radius, 1.0 / self.radius));
+		Some(self.transform.invert()?.then(&to_unit))
+	}
+
+	/// The focal point in the space of [`RadialGradient::unit_transform`].
+	pub fn unit_focal(&self) -> [f32; 2] {
+		let focal = [(self.focal[0] - self.center[0]) / self.radius, (self.focal[1] - self.center[1]) / self.radius];
+		let distance = focal[0].hypot(focal[1]);
+		// on the circle, `t` would be undefined on half the plane
+		const MAX_FOCAL_DISTANCE: f32 = 0.99;
+		match distance > MAX_FOCAL_DISTANCE {
+			true => focal.map(|c| c / distance * MAX_FOCAL_DISTANCE),
+			false => focal,
+		}
+	}
+
+	/// `t` at `p` in the space of [`RadialGradient::unit_transform`]: where `p` is between the focal point, at 0, and
+	/// the unit circle, at 1, along the ray from the focal point.
+	pub fn unit_t(focal: [f32; 2], p: [f32; 2]) -> f32 {
+		let d = [p[0] - focal[0], p[1] - focal[1]];
+		let dd = d[0] * d[0] + d[1] * d[1];
+		if dd == 0.0 {
+			return 0.0;
+		}
+		// the ray from the focal point through p hits the circle at focal + s d
+		let fd = focal[0] * d[0] + focal[1] * d[1];
+		let ff = focal[0] * focal[0] + focal[1] * focal[1];
+		let s = (-fd + (fd * fd - dd * (ff - 1.0)).sqrt()) / dd;
+		1.0 / s
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+	Solid(Color),
+	LinearGradient(LinearGradient),
+	RadialGradient(RadialGradient),
+}
+impl Paint {
+	/// The colour at `p`, in the space of the path being painted.
+	pub fn color_at(&self, p: [f32; 2]) -> Color {
+		match self {
+			Paint::Solid(color) => *color,
+			Paint::LinearGradient(gradient) => match gradient.unit_transform() {
+				Some(to_unit) => gradient_color(&gradient.stops, gradient.spread.apply(to_unit.apply(p)[0])),
+				None => gradient.stops.last().map_or(Color::TRANSPARENT, |stop| stop.color),
+			},
+			Paint::RadialGradient(gradient) => match gradient.unit_transform() {
+				Some(to_unit) => {
+					let t = RadialGradient::unit_t(gradient.unit_focal(), to_unit.apply(p));
+					gradient_color(&gradient.stops, gradient.spread.apply(t))
+				},
+				None => gradient.stops.last().map_or(Color::TRANSPARENT, |stop| stop.color),
+			},
+		}
+	}
+}
+
+/// How a [`Draw`] is composited onto what's under it. Colours are premultiplied, and are blended in whatever space
+/// the canvas keeps them in, like GPU blending.
+///
+/// The Porter-Duff operators mix by alpha alone. The rest are CSS's `mix-blend-mode`s, which mix the colours where
+/// both are opaque and then composite like [`BlendMode::SrcOver`]. Either way a draw only changes what it covers, so
+/// operators that would clear what's outside the source, like [`BlendMode::SrcIn`], only do so within the path, and
+/// anti-aliased edges fade between the blended colour and what was there.
+///
+/// The shaders number the modes with their discriminants, so they stay as they are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum BlendMode {
+	Clear = 0,
+	/// Replaces what's under it.
+	Src = 1,
+	/// Keeps what's under it, so it draws nothing.
+	Dst = 2,
+	/// Normal alpha blending.
+	#[default]
+	SrcOver = 3,
+	DstOver = 4,
+	SrcIn = 5,
+	DstIn = 6,
+	SrcOut = 7,
+	DstOut = 8,
+	SrcAtop = 9,
+	DstAtop = 10,
+	Xor = 11,
+	/// Adds the colours, up to 1, which is CSS's `plus-lighter`. Anti-aliased edges add the faded paint instead of
+	/// fading the sum, which is only different where it's clamped.
+	Plus = 12,
+	Multiply = 13,
+	Screen = 14,
+	Overlay = 15,
+	Darken = 16,
+	Lighten = 17,
+	ColorDodge = 18,
+	ColorBurn = 19,
+	HardLight = 20,
+	SoftLight = 21,
+	Difference = 22,
+	Exclusion = 23,
+	/// The hue of the source with the saturation and luminosity of what's under it.
+	Hue = 24,
+	Saturation = 25,
+	Color = 26,
+	Luminosity = 27,
+}
+impl BlendMode {
+	pub const ALL: [BlendMode; 28] = [
+		BlendMode::Clear,
+		BlendMode::Src,
+		BlendMode::Dst,
+		BlendMode::SrcOver,
+		BlendMode::DstOver,
+		BlendMode::SrcIn,
+		BlendMode::DstIn,
+		BlendMode::SrcOut,
+		BlendMode::DstOut,
+		BlendMode::SrcAtop,
+		BlendMode::DstAtop,
+		BlendMode::Xor,
+		BlendMode::Plus,
+		BlendMode::Multiply,
+		BlendMode::Screen,
+		BlendMode::Overlay,
+		BlendMode::Darken,
+		BlendMode::Lighten,
+		BlendMode::ColorDodge,
+		BlendMode::ColorBurn,
+		BlendMode::HardLight,
+		BlendMode::SoftLight,
+		BlendMode::Difference,
+		BlendMode::Exclusion,
+		BlendMode::Hue,
+		BlendMode::Saturation,
+		BlendMode::Color,
+		BlendMode::Luminosity,
+	];
+
+	/// The mode's name as a CSS `mix-blend-mode`, which only the Porter-Duff operators other than
+	/// [`BlendMode::SrcOver`] and [`BlendMode::Plus`] don't have.
+	pub fn css_name(self) -> Option<&'static str> {
+		Some(match self {
+			BlendMode::SrcOver => "normal",
+			BlendMode::Plus => "plus-lighter",
+			BlendMode::Multiply => "multiply",
+			BlendMode::Screen => "screen",
+			BlendMode::Overlay => "overlay",
+			BlendMode::Darken => "darken",
+			BlendMode::Lighten => "lighten",
+			BlendMode::ColorDodge => "color-dodge",
+			BlendMode::ColorBurn => "color-burn",
+			BlendMode::HardLight => "hard-light",
+			BlendMode::SoftLight => "soft-light",
+			BlendMode::Difference => "difference",
+			BlendMode::Exclusion => "exclusion",
+			BlendMode::Hue => "hue",
+			BlendMode::Saturation => "saturation",
+			BlendMode::Color => "color",
+			BlendMode::Luminosity => "luminosity",
+			_ => return None,
+		})
+	}
+
+	pub fn from_css_name(name: &str) -> Option<Self> {
+		BlendMode::ALL.into_iter().find(|mode| mode.css_name() == Some(name))
+	}
+
+	/// Composites the premultiplied `src` onto `dst` where the source is fully covered.
+	pub fn blend(self, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+		let (sa, da) = (src[3], dst[3]);
+		let porter_duff = |fs: f32, fd: f32| [0, 1, 2, 3].map(|i| src[i] * fs + dst[i] * fd);
+		match self {
+			BlendMode::Clear => [0.0; 4],
+			BlendMode::Src => src,
+			BlendMode::Dst => dst,
+			BlendMode::SrcOver => porter_duff(1.0, 1.0 - sa),
+			BlendMode::DstOver => porter_duff(1.0 - da, 1.0),
+			BlendMode::SrcIn => porter_duff(da, 0.0),
+			BlendMode::DstIn => porter_duff(0.0, sa),
+			BlendMode::SrcOut => porter_duff(1.0 - da, 0.0),
+			BlendMode::DstOut => porter_duff(0.0, 1.0 - sa),
+			BlendMode::SrcAtop => porter_duff(da, 1.0 - sa),
+			BlendMode::DstAtop => porter_duff(1.0 - da, sa),
+			BlendMode::Xor => porter_duff(1.0 - da, 1.0 - sa),
+			BlendMode::Plus => porter_duff(1.0, 1.0).map(|c| c.min(1.0)),
+			_ => {
+				let straight = |c: [f32; 4]| match c[3] {
+					a if a > 0.0 => [c[0] / a, c[1] / a, c[2] / a],
+					_ => [0.0; 3],
+				};
+				let mixed = self.mix(straight(src), straight(dst));
+				let mut out = [0.0, 0.0, 0.0, sa + da - sa * da];
+				for i in 0..3 {
+					out[i] = src[i] * (1.0 - da) + dst[i] * (1.0 - sa) + sa * da * mixed[i];
+				}
+				out
+			},
+		}
+	}
+
+	/// The colour the blend modes give where the straight `src` and `dst` are both opaque.
+	fn mix(self, src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+		let separable = |f: fn(f32, f32) -> f32| [0, 1, 2].map(|i| f(src[i], dst[i]));
+		let hard_light = |s: f32, d: f32| match s <= 0.5 {
+			true => d * 2.0 * s,
+			false => {
+				let s = 2.0 * s - 1.0;
+				d + s - d * s
+			},
+		};
+		match self {
+			BlendMode::Multiply => separable(|s, d| s * d),
+			BlendMode::Screen => separable(|s, d| s + d - s * d),
+			BlendMode::Overlay => [0, 1, 2].map(|i| hard_light(dst[i], src[i])),
+			BlendMode::Darken => separable(f32::min),
+			BlendMode::Lighten => separable(f32::max),
+			BlendMode::ColorDodge => separable(|s, d| match (d, s) {
+				(d, _) if d <= 0.0 => 0.0,
+				(_, s) if s >= 1.0 => 1.0,
+				_ => (d / (1.0 - s)).min(1.0),
+			}),
+			BlendMode::ColorBurn => separable(|s, d| match (d, s) {
+				(d, _) if d >= 1.0 => 1.0,
+				(_, s) if s <= 0.0 => 0.0,
+				_ => 1.0 - ((1.0 - d) / s).min(1.0),
+			}),
+			BlendMode::HardLight => [0, 1, 2].map(|i| hard_light(src[i], dst[i])),
+			BlendMode::SoftLight => separable(|s, d| match s <= 0.5 {
+				true => d - (1.0 - 2.0 * s) * d * (1.0 - d),
+				false => {
+					let lightened = if d <= 0.25 { ((16.0 * d - 12.0) * d + 4.0) * d } else { d.sqrt() };
+					d + (2.0 * s - 1.0) * (lightened - d)
+				},
+			}),
+			BlendMode::Difference => separable(|s, d| (s - d).abs()),
+			BlendMode::Exclusion => separable(|s, d| s + d - 2.0 * s * d),
+			BlendMode::Hue => with_luminosity(with_saturation(src, saturation(dst)), luminosity(dst)),
+			BlendMode::Saturation => with_luminosity(with_saturation(dst, saturation(src)), luminosity(dst)),
+			BlendMode::Color => with_luminosity(src, luminosity(dst)),
+			BlendMode::Luminosity => with_luminosity(dst, luminosity(src)),
+			_ => src,
+		}
+	}
+}
+
+fn luminosity(c: [f32; 3]) -> f32 {
+	0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn saturation(c: [f32; 3]) -> f32 {
+	c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+/// Stretches `c` between 0 and `saturation`, keeping its hue.
+fn with_saturation(c: [f32; 3], saturation: f32) -> [f32; 3] {
+	let (min, max) = (c[0].min(c[1]).min(c[2]), c[0].max(c[1]).max(c[2]));
+	match max > min {
+		true => c.map(|c| (c - min) * saturation / (max - min)),
+		false => [0.0; 3],
+	}
+}
+
+/// Shifts `c` to the `target` luminosity, then pulls it back between 0 and 1 towards grey.
+fn with_luminosity(c: [f32; 3], target: f32) -> [f32; 3] {
+	let shift = target - luminosity(c);
+	let c = c.map(|c| c + shift);
+	let l = luminosity(c);
+	let (min, max) = (c[0].min(c[1]).min(c[2]), c[0].max(c[1]).max(c[2]));
+	c.map(|c| {
+		let c = if min < 0.0 { l + (c - l) * l / (l - min) } else { c };
+		if max > 1.0 {
+			l + (c - l) * (1.0 - l) / (max - l)
+		} else {
+			c
+		}
+	})
+}
+
+/// One filled path in a [`Scene`]. Strokes are drawn by filling their outline, see [`Path::stroke`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Draw {
+	pub path: Path,
+	/// Maps the path, and its paint, into the scene.
+	pub transform: Transform,
+	pub paint: Paint,
+	pub fill_rule: FillRule,
+	/// Multiplies the paint's alpha.
+	pub opacity: f32,
+	pub blend_mode: BlendMode,
+}
+impl Draw {
+	pub fn new(path: Path, paint: Paint) -> Self {
+		Self {
+			path,
+			transform: Transform::IDENTITY,
+			paint,
+			fill_rule: FillRule::NonZero,
+			opacity: 1.0,
+			blend_mode: BlendMode::SrcOver,
+		}
+	}
+
+	/// The path mapped through `transform` and then `to_view`, with overlaps resolved if it's filled with
+	/// [`FillRule::NonZero`], so it fills correctly with the even-odd rule the fill pipeline uses. A single contour that
+	/// doesn't cross itself, like most basic shapes, already fills the same either way and is left as it is.
+	pub fn outline(&self, to_view: &Transform) -> Path {
+		let path = self.path.transform(&self.transform.then(to_view));
+		match self.fill_rule {
+			FillRule::NonZero if !path.is_simple() => path.union(&Path::start(), FillRule::NonZero),
+			_ => path,
+		}
+	}
+}
+
+/// Paths to be drawn in order, back to front, onto a canvas of `size`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scene {
+	pub size: [f32; 2],
+	pub draws: Vec<Draw>,
+}
+impl Scene {
+	pub fn new(size: [f32; 2]) -> Self {
+		Self { size, draws: vec![] }
+	}
+
+	pub fn push(&mut self, draw: Draw) {
+		self.draws.push(draw);
+	}
+}
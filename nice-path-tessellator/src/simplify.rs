@@ -0,0 +1,224 @@
+use crate::{
+	geom::{dot, length, scale, sub, Conic, LineSegment, QuadraticBezier, Segment},
+	offset::{push_segments, tangent_intersection},
+	Path,
+};
+
+/// Options for [`Path::simplify_with`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimplifyOptions {
+	/// How far the simplified path may stray from the original, in path units.
+	pub tolerance: f32,
+	/// Whether runs of short lines are replaced with quadratics where one fits them, e.g. for flattened curves that were
+	/// imported as polylines.
+	pub fit_curves: bool,
+}
+
+impl Path {
+	/// Cleans up the path without moving it more than `tolerance`: segments shorter than that are dropped, quadratics
+	/// and conics that are that close to straight become lines, and consecutive lines that are that close to collinear are merged.
+	/// This avoids degenerate curve triangles and wasted vertices when the path is tessellated.
+	///
+	/// Subpaths that end up without any segments are dropped.
+	pub fn simplify(&self, tolerance: f32) -> Path {
+		self.simplify_with(&SimplifyOptions { tolerance, fit_curves: false })
+	}
+
+	/// Same as [`Path::simplify`], with the option of fitting quadratics to dense polylines.
+	pub fn simplify_with(&self, options: &SimplifyOptions) -> Path {
+		let tolerance = options.tolerance.max(0.0);
+		let mut out = Path::start();
+		for contour in self.contours() {
+			let mut segs = demote_flat(&contour.segments, tolerance);
+			// fitting needs the dense polyline, before its lines are merged. Nearly straight runs can come out as flat
+			// quadratics, which are demoted again.
+			if options.fit_curves {
+				segs = demote_flat(&fit_quadratics(&segs, tolerance), tolerance);
+			}
+			let segs = merge_lines(&segs, tolerance);
+			if let Some(first) = segs.first() {
+				out = push_segments(out.move_to(first.from()), &segs);
+			}
+		}
+		out
+	}
+}
+
+/// Turns flat quadratics and conics into lines, and drops segments that don't get anywhere. The segments stay chained, so a
+/// dropped segment's end point is taken over by the next one's start.
+fn demote_flat(segs: &[Segment], tolerance: f32) -> Vec<Segment> {
+	let mut out: Vec<Segment> = vec![];
+	let mut pen = match segs.first() {
+		Some(seg) => seg.from(),
+		None => return out,
+	};
+	for seg in segs {
+		let seg = match seg.with_endpoints(pen, seg.to()) {
+			// the curve is a weighted average of its points with a weight of at most 1/2 on the control point, so it's
+			// never further from the chord than half the control point is
+			Segment::Quadratic(quad)
+				if distance_to_line(&LineSegment::new(quad.from, quad.to), quad.ctrl) <= 2.0 * tolerance =>
+			{
+				Segment::Line(LineSegment::new(quad.from, quad.to))
+			},
+			// likewise with a weight of at most w / (1 + w), which is what it has halfway along
+			Segment::Conic(conic)
+				if distance_to_line(&LineSegment::new(conic.from, conic.to), conic.ctrl) * conic.weight
+					<= tolerance * (1.0 + conic.weight) =>
+			{
+				Segment::Line(LineSegment::new(conic.from, conic.to))
+			},
+			seg => seg,
+		};
+		let degenerate = match seg {
+			Segment::Line(line) => line.length() <= tolerance,
+			Segment::Quadratic(QuadraticBezier { from, ctrl, to }) | Segment::Conic(Conic { from, ctrl, to, .. }) => {
+				length(sub(ctrl, from)) <= tolerance && length(sub(to, from)) <= tolerance
+			},
+		};
+		if degenerate {
+			continue;
+		}
+		out.push(seg);
+		pen = seg.to();
+	}
+	out
+}
+
+/// Merges runs of lines that all stay within `tolerance` of a single line, and don't double back along it.
+fn merge_lines(segs: &[Segment], tolerance: f32) -> Vec<Segment> {
+	let mut out = vec![];
+	let mut run: Vec<[f32; 2]> = vec![];
+	for seg in segs {
+		match seg {
+			Segment::Line(line) => {
+				if run.is_empty() {
+					run.push(line.from);
+				}
+				run.push(line.to);
+				if !fits_line(&run, tolerance) {
+					let end = run[run.len() - 2];
+					out.push(Segment::Line(LineSegment::new(run[0], end)));
+					run = vec![end, line.to];
+				}
+			},
+			Segment::Quadratic(_) | Segment::Conic(_) => {
+				if run.len() >= 2 {
+					out.push(Segment::Line(LineSegment::new(run[0], run[run.len() - 1])));
+				}
+				run.clear();
+				out.push(*seg);
+			},
+		}
+	}
+	if run.len() >= 2 {
+		out.push(Segment::Line(LineSegment::new(run[0], run[run.len() - 1])));
+	}
+	out
+}
+
+fn fits_line(run: &[[f32; 2]], tolerance: f32) -> bool {
+	let line = LineSegment::new(run[0], run[run.len() - 1]);
+	let dir = sub(line.to, line.from);
+	let mut progress = 0.0;
+	run[1..run.len() - 1].iter().all(|&p| {
+		let along = dot(sub(p, line.from), dir);
+		let forwards = along >= progress;
+		progress = along;
+		forwards && distance_to_line(&line, p) <= tolerance
+	})
+}
+
+/// Replaces runs of at least two lines with a quadratic, wherever one fits within `tolerance`.
+fn fit_quadratics(segs: &[Segment], tolerance: f32) -> Vec<Segment> {
+	let mut out = vec![];
+	let mut i = 0;
+	while i < segs.len() {
+		let mut points = vec![segs[i].from()];
+		let mut best = None;
+		for seg in &segs[i..] {
+			match seg {
+				Segment::Line(line) => points.push(line.to),
+				Segment::Quadratic(_) | Segment::Conic(_) => break,
+			}
+			if points.len() < 3 {
+				continue;
+			}
+			match fit_quadratic(&points, tolerance) {
+				Some(quad) => best = Some((quad, points.len() - 1)),
+				None => break,
+			}
+		}
+		match best {
+			Some((quad, lines)) => {
+				out.push(Segment::Quadratic(quad));
+				i += lines;
+			},
+			None => {
+				out.push(segs[i]);
+				i += 1;
+			},
+		}
+	}
+	out
+}
+
+/// How many times the fitted quadratic is used to improve the parameters of the points it's fitted to.
+const REFIT_ITERATIONS: usize = 3;
+
+/// The least-squares quadratic through the ends of a polyline, if every point and every line of the polyline is within
+/// `tolerance` of it, and it's within `tolerance` of the polyline.
+fn fit_quadratic(points: &[[f32; 2]], tolerance: f32) -> Option<QuadraticBezier> {
+	// start from the quadratic whose control point is where the polyline's end directions meet, then alternate
+	// between moving each point to the parameter of its nearest point on the fit, and refitting
+	let n = points.len();
+	let (from, to) = (points[0], points[n - 1]);
+	let start_dir = end_direction(points[0], points[1], points[2]);
+	let end_dir = scale(end_direction(points[n - 1], points[n - 2], points[n - 3]), -1.0);
+	let ctrl = tangent_intersection(from, start_dir, to, end_dir)?;
+	let mut quad = QuadraticBezier::new(from, ctrl, to);
+	let mut ts = vec![0.0; n];
+	ts[n - 1] = 1.0;
+	for _ in 0..REFIT_ITERATIONS {
+		for (t, &p) in ts[1..n - 1].iter_mut().zip(&points[1..n - 1]) {
+			*t = quad.nearest_t(p);
+		}
+		quad = least_squares_quadratic(points, &ts)?;
+	}
+
+	let near_curve = |p: [f32; 2]| length(sub(quad.nearest_point(p), p)) <= tolerance;
+	let lines: Vec<LineSegment> = points.windows(2).map(|pair| LineSegment::new(pair[0], pair[1])).collect();
+	let near_polyline = |p: [f32; 2]| lines.iter().any(|line| distance_to_line(line, p) <= tolerance);
+	let samples = points.len() * 4;
+	let fits = lines.iter().all(|line| near_curve(line.to) && near_curve(line.eval(0.5)))
+		&& (1..samples).all(|i| near_polyline(quad.eval(i as f32 / samples as f32)));
+	fits.then_some(quad)
+}
+
+/// The quadratic from the first point to the last one whose control point minimises the squared distances from each
+/// point to the curve at its parameter in `ts`.
+fn least_squares_quadratic(points: &[[f32; 2]], ts: &[f32]) -> Option<QuadraticBezier> {
+	let (from, to) = (points[0], points[points.len() - 1]);
+	let (mut num, mut den) = ([0.0; 2], 0.0);
+	for (&p, &t) in points.iter().zip(ts) {
+		let weight = 2.0 * t * (1.0 - t);
+		let rest = [0, 1].map(|axis| p[axis] - (1.0 - t) * (1.0 - t) * from[axis] - t * t * to[axis]);
+		num = [num[0] + weight * rest[0], num[1] + weight * rest[1]];
+		den += weight * weight;
+	}
+	(den > 0.0).then(|| QuadraticBezier::new(from, [num[0] / den, num[1] / den], to))
+}
+
+/// The direction of the parabola through three polyline points at `a`, going towards `b`. This is much closer to the
+/// direction of the curve the polyline was made from than the direction of the first line.
+fn end_direction(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> [f32; 2] {
+	let (h1, h2) = (length(sub(b, a)), length(sub(c, b)));
+	if h1 == 0.0 || h2 == 0.0 {
+		return sub(b, a);
+	}
+	sub(scale(sub(b, a), (h1 + h2) / (h1 * h2)), scale(sub(c, a), h1 / (h2 * (h1 + h2))))
+}
+
+fn distance_to_line(line: &LineSegment, p: [f32; 2]) -> f32 {
+	length(sub(line.nearest_point(p), p))
+}
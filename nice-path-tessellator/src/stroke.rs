@@ -0,0 +1,135 @@
+use crate::{
+	boolean::resolve,
+	geom::{add, scale, sub, LineSegment, Segment},
+	offset::{end_tangent, left_normal, offset_segments, push_arc, push_segments, start_tangent},
+	LineJoin, Path, DEFAULT_OFFSET_TOLERANCE,
+};
+
+/// How the ends of open subpaths are drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+	/// Ends exactly at the endpoint.
+	Butt,
+	/// A half circle around the endpoint.
+	Round,
+	/// Extends past the endpoint by half the width.
+	Square,
+}
+
+/// A dash pattern, as taken by [`Path::dash`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dash {
+	pub pattern: Vec<f32>,
+	pub offset: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeOptions {
+	pub width: f32,
+	pub join: LineJoin,
+	pub cap: LineCap,
+	pub dash: Option<Dash>,
+	/// How far offset curves may stray from the exact outline, in path units.
+	pub tolerance: f32,
+}
+impl Default for StrokeOptions {
+	fn default() -> Self {
+		Self {
+			width: 1.0,
+			join: LineJoin::Miter(4.0),
+			cap: LineCap::Butt,
+			dash: None,
+			tolerance: DEFAULT_OFFSET_TOLERANCE,
+		}
+	}
+}
+
+impl Path {
+	/// The outline of the path stroked with `options`, as a path that can be filled with either fill rule. Only
+	/// explicit segments are stroked, so a subpath is only stroked closed if it ends where it started. Like
+	/// [`Path::offset`], the outline is made of lines and quadratics, even where the path has conics.
+	pub fn stroke(&self, options: &StrokeOptions) -> Path {
+		let dashed;
+		let path = match &options.dash {
+			Some(dash) => {
+				dashed = self.dash(&dash.pattern, dash.offset);
+				&dashed
+			},
+			None => self,
+		};
+		let half_width = options.width / 2.0;
+		if half_width <= 0.0 {
+			return Path::start();
+		}
+
+		let mut raw = Path::start();
+		for contour in path.contours() {
+			let segs: Vec<Segment> = contour.segments.iter().copied().filter(|seg| seg.from() != seg.to()).collect();
+			let (first, last) = match (segs.first(), segs.last()) {
+				(Some(first), Some(last)) => (first, last),
+				_ => {
+					raw = push_dot(raw, contour.start, half_width, options.cap);
+					continue;
+				},
+			};
+			let reversed: Vec<Segment> = segs.iter().rev().map(|seg| seg.reversed()).collect();
+
+			if contour.end() == contour.start {
+				// the outlines on either side wind in opposite directions, so the nonzero rule leaves the middle empty
+				for side in [&segs, &reversed] {
+					let outline = offset_segments(side, true, half_width, options.join, options.tolerance);
+					if let Some(first) = outline.first() {
+						raw = push_segments(raw.move_to(first.from()), &outline);
+					}
+				}
+			} else {
+				let mut outline = offset_segments(&segs, false, half_width, options.join, options.tolerance);
+				push_cap(&mut outline, last.to(), end_tangent(last), half_width, options.cap);
+				outline.extend(offset_segments(&reversed, false, half_width, options.join, options.tolerance));
+				push_cap(&mut outline, first.from(), scale(start_tangent(first), -1.0), half_width, options.cap);
+				raw = push_segments(raw.move_to(outline[0].from()), &outline);
+			}
+		}
+
+		let index = raw.hit_test_index();
+		resolve(&[&raw], |p| index.winding_number(p) != 0)
+	}
+}
+
+/// Appends a cap at `end`, where the path arrives going in direction `dir`, from the left outline to the right one.
+fn push_cap(out: &mut Vec<Segment>, end: [f32; 2], dir: [f32; 2], half_width: f32, cap: LineCap) {
+	let normal = left_normal(dir);
+	let left = add(end, scale(normal, half_width));
+	let right = sub(end, scale(normal, half_width));
+	match cap {
+		LineCap::Butt => out.push(Segment::Line(LineSegment::new(left, right))),
+		LineCap::Round => push_arc(out, end, half_width, normal, scale(normal, -1.0), dir),
+		LineCap::Square => {
+			let ahead = scale(left_normal(normal), -half_width);
+			let (left_out, right_out) = (add(left, ahead), add(right, ahead));
+			out.push(Segment::Line(LineSegment::new(left, left_out)));
+			out.push(Segment::Line(LineSegment::new(left_out, right_out)));
+			out.push(Segment::Line(LineSegment::new(right_out, right)));
+		},
+	}
+}
+
+/// A subpath without length only shows up as its caps: a circle for round caps, and an axis-aligned square for square
+/// caps.
+fn push_dot(path: Path, center: [f32; 2], half_width: f32, cap: LineCap) -> Path {
+	let [x, y] = center;
+	match cap {
+		LineCap::Butt => path,
+		LineCap::Round => {
+			let mut arc = vec![];
+			push_arc(&mut arc, center, half_width, [0.0, -1.0], [0.0, 1.0], [1.0, 0.0]);
+			push_arc(&mut arc, center, half_width, [0.0, 1.0], [0.0, -1.0], [-1.0, 0.0]);
+			push_segments(path.move_to(arc[0].from()), &arc)
+		},
+		LineCap::Square => path
+			.move_to([x - half_width, y - half_width])
+			.line_to([x + half_width, y - half_width])
+			.line_to([x + half_width, y + half_width])
+			.line_to([x - half_width, y + half_width]),
+	}
+}
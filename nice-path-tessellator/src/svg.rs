@@ -0,0 +1,885 @@
+mod export;
+mod path_data;
+mod values;
+
+use std::{collections::HashMap, f32::consts::FRAC_1_SQRT_2, fmt, mem::replace};
+
+use roxmltree::{Document, Node};
+
+pub use path_data::PathDataError;
+
+use crate::{
+	BlendMode, Bounds, Color, Dash, Draw, FillRule, GradientStop, LineCap, LineJoin, LinearGradient, Paint, Path,
+	RadialGradient, Scene, SpreadMethod, StrokeOptions, Transform,
+};
+use path_data::{parse_number_list, parse_path_data};
+use values::{
+	parse_color, parse_length, parse_length_list, parse_number, parse_paint, parse_transform, Axis, Length, SvgPaint,
+};
+
+const SVG_NS: &str = "http://www.w3.org/2000/svg";
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+/// How deeply `<use>` elements and gradient references may nest. This is also what stops reference cycles.
+const MAX_REFERENCE_DEPTH: u32 = 32;
+/// The size of a document that has neither a size nor a `viewBox`, like an `<img>` in a browser.
+const DEFAULT_SIZE: [f32; 2] = [300.0, 150.0];
+
+/// How far cubics and stroke outlines may stray from the exact curves, in user units, when no tolerance is given.
+pub const DEFAULT_SVG_TOLERANCE: f32 = 0.01;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+	/// How far cubics and stroke outlines may stray from the exact curves, in the user units of the element they're
+	/// on.
+	pub tolerance: f32,
+}
+impl Default for SvgOptions {
+	fn default() -> Self {
+		Self { tolerance: DEFAULT_SVG_TOLERANCE }
+	}
+}
+
+/// Something in the document that was left out of the scene, or only imported approximately.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unsupported {
+	/// The element's tag, followed by `#` and its id if it has one.
+	pub element: String,
+	pub feature: String,
+}
+impl fmt::Display for Unsupported {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "<{}>: {}", self.element, self.feature)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgImport {
+	pub scene: Scene,
+	/// Every unsupported feature that's used, once per element.
+	pub unsupported: Vec<Unsupported>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SvgError {
+	/// The document isn't well-formed XML.
+	Xml(String),
+	/// The root element isn't `<svg>`. This is the root element's tag.
+	NotSvg(String),
+}
+impl fmt::Display for SvgError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			SvgError::Xml(err) => write!(f, "invalid XML: {}", err),
+			SvgError::NotSvg(tag) => write!(f, "the root element is <{}>, not <svg>", tag),
+		}
+	}
+}
+impl std::error::Error for SvgError {}
+
+impl Scene {
+	/// Imports an SVG document. See [`Scene::from_svg_with`].
+	pub fn from_svg(text: &str) -> Result<SvgImport, SvgError> {
+		Self::from_svg_with(text, &SvgOptions::default())
+	}
+
+	/// Imports the shapes of an SVG document: paths, basic shapes, groups, `<use>` and nested viewports, with their
+	/// transforms, fills, strokes, opacity, fill rules and the `mix-blend-mode` of shapes. Paints can be colours or
+	/// linear and radial gradients.
+	///
+	/// The scene's size is the document's, in CSS pixels, and the `viewBox` is mapped onto it. Cubics are approximated
+	/// with quadratics, while arcs, circles and ellipses are filled exactly with conics. Strokes are converted to fills,
+	/// whose outlines are approximated with quadratics like every [`Path::stroke`].
+	/// Anything else that affects rendering, like text, images, filters, clipping, masks, markers and stylesheets, is
+	/// left out and listed in [`SvgImport::unsupported`]. Opacity is applied to every filled path separately, so it's
+	/// only exact for groups without overlapping shapes, which is also listed.
+	pub fn from_svg_with(text: &str, options: &SvgOptions) -> Result<SvgImport, SvgError> {
+		let doc = Document::parse(text).map_err(|err| SvgError::Xml(err.to_string()))?;
+		let root = doc.root_element();
+		if !is_svg(root, "svg") {
+			return Err(SvgError::NotSvg(root.tag_name().name().to_string()));
+		}
+
+		let mut importer = Importer {
+			ids: doc.descendants().filter_map(|node| Some((node.attribute("id")?, node))).collect(),
+			tolerance: options.tolerance,
+			viewport: DEFAULT_SIZE,
+			depth: 0,
+			draws: vec![],
+			unsupported: vec![],
+		};
+		let view_box = importer.view_box(root);
+		let lengths = ["width", "height"].map(|name| match root.attribute(name).and_then(parse_length) {
+			Some(Length::User(length)) if length > 0.0 => Some(length),
+			_ => None,
+		});
+		// a missing dimension follows the viewBox's aspect ratio
+		let size = match (lengths, view_box) {
+			([Some(width), Some(height)], _) => [width, height],
+			([Some(width), None], Some(view_box)) => [width, width * view_box[3] / view_box[2]],
+			([None, Some(height)], Some(view_box)) => [height * view_box[2] / view_box[3], height],
+			(_, Some(view_box)) => [view_box[2], view_box[3]],
+			([width, height], None) => [width.unwrap_or(DEFAULT_SIZE[0]), height.unwrap_or(DEFAULT_SIZE[1])],
+		};
+
+		let style = importer.cascade(&Style::default(), root);
+		let opacity = importer.opacity(root);
+		importer.report_unsupported_properties(root);
+		importer.viewport_children(root, size, &style, &Transform::IDENTITY, opacity);
+
+		let mut scene = Scene::new(size);
+		scene.draws = importer.draws;
+		Ok(SvgImport { scene, unsupported: importer.unsupported })
+	}
+}
+
+impl Color {
+	/// Parses a CSS colour as SVG uses them: hex, `rgb()`, `rgba()`, `hsl()`, `hsla()`, a named colour or
+	/// `transparent`.
+	pub fn from_css(text: &str) -> Option<Color> {
+		parse_color(text)
+	}
+}
+
+impl Path {
+	/// Parses the `d` attribute of an SVG `<path>`. See [`Path::from_svg_path_data_with_tolerance`].
+	pub fn from_svg_path_data(d: &str) -> Result<Path, PathDataError> {
+		Self::from_svg_path_data_with_tolerance(d, DEFAULT_SVG_TOLERANCE)
+	}
+
+	/// Parses the `d` attribute of an SVG `<path>`. Arcs become conics, and cubics are approximated with quadratics
+	/// that are within `tolerance` of them. Closing a subpath adds the closing line if it's needed, so the subpath is
+	/// stroked closed.
+	pub fn from_svg_path_data_with_tolerance(d: &str, tolerance: f32) -> Result<Path, PathDataError> {
+		match parse_path_data(d, tolerance) {
+			(path, None) => Ok(path),
+			(_, Some(err)) => Err(err),
+		}
+	}
+}
+
+/// The inherited properties.
+#[derive(Clone, Debug)]
+struct Style {
+	fill: SvgPaint,
+	fill_opacity: f32,
+	fill_rule: FillRule,
+	stroke: SvgPaint,
+	stroke_opacity: f32,
+	stroke_width: Length,
+	stroke_cap: LineCap,
+	stroke_join: LineJoin,
+	stroke_miter_limit: f32,
+	stroke_dash_array: Option<Vec<Length>>,
+	stroke_dash_offset: Length,
+	color: Color,
+	visible: bool,
+}
+impl Default for Style {
+	fn default() -> Self {
+		Self {
+			fill: SvgPaint::Color(Color::BLACK),
+			fill_opacity: 1.0,
+			fill_rule: FillRule::NonZero,
+			stroke: SvgPaint::None,
+			stroke_opacity: 1.0,
+			stroke_width: Length::User(1.0),
+			stroke_cap: LineCap::Butt,
+			stroke_join: LineJoin::Miter(4.0),
+			stroke_miter_limit: 4.0,
+			stroke_dash_array: None,
+			stroke_dash_offset: Length::User(0.0),
+			color: Color::BLACK,
+			visible: true,
+		}
+	}
+}
+
+struct Importer<'a, 'input> {
+	ids: HashMap<&'a str, Node<'a, 'input>>,
+	tolerance: f32,
+	/// The size percentages refer to.
+	viewport: [f32; 2],
+	/// How many `<use>` elements deep the importer is.
+	depth: u32,
+	draws: Vec<Draw>,
+	unsupported: Vec<Unsupported>,
+}
+impl<'a, 'input> Importer<'a, 'input> {
+	fn report(&mut self, node: Node, feature: impl Into<String>) {
+		let element = match node.attribute("id") {
+			Some(id) => format!("{}#{}", node.tag_name().name(), id),
+			None => node.tag_name().name().to_string(),
+		};
+		let unsupported = Unsupported { element, feature: feature.into() };
+		if !self.unsupported.contains(&unsupported) {
+			self.unsupported.push(unsupported);
+		}
+	}
+
+	fn element(&mut self, node: Node<'a, 'input>, parent_style: &Style, parent_transform: &Transform, opacity: f32) {
+		// elements from other namespaces, like editor metadata, aren't rendered
+		if !node.is_element() || !matches!(node.tag_name().namespace(), None | Some(SVG_NS)) {
+			return;
+		}
+		if property(node, "display") == Some("none") {
+			return;
+		}
+		let tag = node.tag_name().name();
+		match tag {
+			// only drawn when they're referenced
+			"defs" | "symbol" | "linearGradient" | "radialGradient" | "pattern" | "clipPath" | "mask" | "marker"
+			| "filter" => return,
+			"title" | "desc" | "metadata" | "script" => return,
+			"style" => return self.report(node, "CSS stylesheets"),
+			"text" | "image" | "foreignObject" | "video" | "audio" | "iframe" | "canvas" => {
+				return self.report(node, format!("<{}> elements", tag))
+			},
+			_ => {},
+		}
+
+		let style = self.cascade(parent_style, node);
+		let transform = self.transform(node).then(parent_transform);
+		let own_opacity = self.opacity(node);
+		let opacity = opacity * own_opacity;
+		self.report_unsupported_properties(node);
+
+		let first_draw = self.draws.len();
+		match tag {
+			"g" | "a" => {
+				for child in node.children() {
+					self.element(child, &style, &transform, opacity);
+				}
+			},
+			"switch" => {
+				self.report(node, "<switch> conditions, so every child is drawn");
+				for child in node.children() {
+					self.element(child, &style, &transform, opacity);
+				}
+			},
+			"svg" => {
+				let x = self.length(node, "x", Axis::X).unwrap_or(0.0);
+				let y = self.length(node, "y", Axis::Y).unwrap_or(0.0);
+				let size = [
+					self.length(node, "width", Axis::X).unwrap_or(self.viewport[0]),
+					self.length(node, "height", Axis::Y).unwrap_or(self.viewport[1]),
+				];
+				self.report(node, "clipping to nested viewports");
+				self.viewport_children(node, size, &style, &Transform::translate(x, y).then(&transform), opacity);
+			},
+			"use" => self.use_element(node, &style, &transform, opacity),
+			_ => match self.shape(node) {
+				Some(path) => self.draw_shape(node, path, &style, &transform, opacity),
+				None if is_shape(tag) => {},
+				None => self.report(node, format!("<{}> elements", tag)),
+			},
+		}
+		if own_opacity < 1.0 && self.draws.len() - first_draw > 1 {
+			self.report(node, "opacity on overlapping shapes, which is applied to each shape separately");
+		}
+	}
+
+	/// Draws the children of an `<svg>` or `<symbol>`, with its `viewBox` mapped to `size` at the origin of
+	/// `transform`.
+	fn viewport_children(
+		&mut self,
+		node: Node<'a, 'input>,
+		size: [f32; 2],
+		style: &Style,
+		transform: &Transform,
+		opacity: f32,
+	) {
+		let (transform, viewport) = match self.view_box(node) {
+			Some(view_box) => {
+				let aspect = view_box_transform(view_box, self.aspect_ratio(node), size);
+				(aspect.then(transform), [view_box[2], view_box[3]])
+			},
+			None => (*transform, size),
+		};
+		let outer_viewport = replace(&mut self.viewport, viewport);
+		for child in node.children() {
+			self.element(child, style, &transform, opacity);
+		}
+		self.viewport = outer_viewport;
+	}
+
+	fn use_element(&mut self, node: Node<'a, 'input>, style: &Style, transform: &Transform, opacity: f32) {
+		let target = match href(node).and_then(|id| self.ids.get(id)) {
+			Some(&target) => target,
+			None => return self.report(node, "references to missing elements"),
+		};
+		if self.depth >= MAX_REFERENCE_DEPTH {
+			return self.report(node, "references nested too deeply, or in a cycle");
+		}
+		let x = self.length(node, "x", Axis::X).unwrap_or(0.0);
+		let y = self.length(node, "y", Axis::Y).unwrap_or(0.0);
+		let transform = Transform::translate(x, y).then(transform);
+
+		self.depth += 1;
+		if is_svg(target, "symbol") {
+			let size = ["width", "height"].map(|name| node.attribute(name).or_else(|| target.attribute(name)));
+			let size = [(size[0], Axis::X), (size[1], Axis::Y)].map(|(length, axis)| {
+				length
+					.and_then(parse_length)
+					.map_or(axis.reference(self.viewport), |length| length.resolve(axis.reference(self.viewport)))
+			});
+			let style = self.cascade(style, target);
+			let opacity = opacity * self.opacity(target);
+			self.report(target, "clipping to nested viewports");
+			self.viewport_children(target, size, &style, &transform, opacity);
+		} else {
+			self.element(target, style, &transform, opacity);
+		}
+		self.depth -= 1;
+	}
+
+	/// The geometry of a basic shape or path, or `None` if it isn't one or draws nothing.
+	fn shape(&mut self, node: Node<'a, 'input>) -> Option<Path> {
+		let length = |importer: &mut Self, name, axis| importer.length(node, name, axis).unwrap_or(0.0);
+		match node.tag_name().name() {
+			"path" => {
+				let (path, err) = parse_path_data(node.attribute("d")?, self.tolerance);
+				if let Some(err) = err {
+					self.report(node, format!("{}, so it's only drawn up to there", err));
+				}
+				Some(path)
+			},
+			"rect" => {
+				let [x, y] = [length(self, "x", Axis::X), length(self, "y", Axis::Y)];
+				let [width, height] = [length(self, "width", Axis::X), length(self, "height", Axis::Y)];
+				if width <= 0.0 || height <= 0.0 {
+					return None;
+				}
+				// a missing radius is the same as the other one
+				let rx = self.length(node, "rx", Axis::X);
+				let ry = self.length(node, "ry", Axis::Y);
+				let (rx, ry) = match (rx, ry) {
+					(Some(rx), Some(ry)) => (rx, ry),
+					(Some(r), None) | (None, Some(r)) => (r, r),
+					(None, None) => (0.0, 0.0),
+				};
+				Some(rect([x, y], [width, height], [rx.clamp(0.0, width / 2.0), ry.clamp(0.0, height / 2.0)]))
+			},
+			"circle" => {
+				let center = [length(self, "cx", Axis::X), length(self, "cy", Axis::Y)];
+				let r = length(self, "r", Axis::Other);
+				(r > 0.0).then(|| ellipse(center, [r, r]))
+			},
+			"ellipse" => {
+				let center = [length(self, "cx", Axis::X), length(self, "cy", Axis::Y)];
+				let rx = self.length(node, "rx", Axis::X);
+				let ry = self.length(node, "ry", Axis::Y);
+				let [rx, ry] = [rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0)];
+				(rx > 0.0 && ry > 0.0).then(|| ellipse(center, [rx, ry]))
+			},
+			"line" => {
+				let from = [length(self, "x1", Axis::X), length(self, "y1", Axis::Y)];
+				let to = [length(self, "x2", Axis::X), length(self, "y2", Axis::Y)];
+				Some(Path::start().move_to(from).line_to(to))
+			},
+			tag @ ("polyline" | "polygon") => {
+				let (numbers, valid) = parse_number_list(node.attribute("points")?);
+				if !valid || numbers.len() % 2 != 0 {
+					self.report(node, "invalid points, so it's only drawn up to there");
+				}
+				let points: Vec<[f32; 2]> = numbers.chunks_exact(2).map(|pair| [pair[0], pair[1]]).collect();
+				let mut path = Path::start().move_to(*points.first()?);
+				for &point in &points[1..] {
+					path = path.line_to(point);
+				}
+				if tag == "polygon" && points.len() > 1 && points[0] != points[points.len() - 1] {
+					path = path.line_to(points[0]);
+				}
+				Some(path)
+			},
+			_ => None,
+		}
+	}
+
+	fn draw_shape(&mut self, node: Node<'a, 'input>, path: Path, style: &Style, transform: &Transform, opacity: f32) {
+		if !style.visible {
+			return;
+		}
+		let bounds = path.bounds();
+		let blend_mode = self.property(node, "mix-blend-mode", BlendMode::from_css_name).unwrap_or_default();
+		let first_draw = self.draws.len();
+
+		if let Some(paint) = self.paint(node, &style.fill, style, bounds) {
+			self.draws.push(Draw {
+				path: path.clone(),
+				transform: *transform,
+				paint,
+				fill_rule: style.fill_rule,
+				opacity: opacity * style.fill_opacity,
+				blend_mode,
+			});
+		}
+
+		let width = style.stroke_width.resolve(Axis::Other.reference(self.viewport));
+		if width <= 0.0 {
+			return;
+		}
+		if let Some(paint) = self.paint(node, &style.stroke, style, bounds) {
+			let reference = Axis::Other.reference(self.viewport);
+			let dash = style.stroke_dash_array.as_ref().and_then(|lengths| {
+				let pattern: Vec<f32> = lengths.iter().map(|length| length.resolve(reference)).collect();
+				let valid = pattern.iter().all(|&length| length >= 0.0) && pattern.iter().sum::<f32>() > 0.0;
+				valid.then(|| Dash { pattern, offset: style.stroke_dash_offset.resolve(reference) })
+			});
+			let join = match style.stroke_join {
+				LineJoin::Miter(_) => LineJoin::Miter(style.stroke_miter_limit),
+				join => join,
+			};
+			let outline =
+				path.stroke(&StrokeOptions { width, join, cap: style.stroke_cap, dash, tolerance: self.tolerance });
+			// stroke outlines don't overlap themselves, so they don't need the nonzero rule resolved
+			self.draws.push(Draw {
+				path: outline,
+				transform: *transform,
+				paint,
+				fill_rule: FillRule::EvenOdd,
+				opacity: opacity * style.stroke_opacity,
+				blend_mode,
+			});
+			if blend_mode != BlendMode::SrcOver && self.draws.len() - first_draw > 1 {
+				self.report(node, "mix-blend-mode on filled and stroked shapes, which is applied to each separately");
+			}
+		}
+	}
+
+	/// `bounds` are the bounds of the shape being painted, which gradients are relative to by default.
+	fn paint(&mut self, node: Node, paint: &SvgPaint, style: &Style, bounds: Option<Bounds>) -> Option<Paint> {
+		match paint {
+			SvgPaint::None => None,
+			SvgPaint::Color(color) => Some(Paint::Solid(*color)),
+			SvgPaint::CurrentColor => Some(Paint::Solid(style.color)),
+			SvgPaint::Url(id, fallback) => {
+				match self.ids.get(id.as_str()).copied() {
+					Some(server) if is_svg(server, "linearGradient") || is_svg(server, "radialGradient") => {
+						return self.gradient(server, style, bounds);
+					},
+					Some(server) => self.report(node, format!("<{}> paint servers", server.tag_name().name())),
+					None if fallback.is_none() => self.report(node, format!("references to missing paint #{}", id)),
+					None => {},
+				}
+				let fallback = fallback.as_deref()?;
+				self.paint(node, fallback, style, bounds)
+			},
+		}
+	}
+
+	fn gradient(&mut self, server: Node<'a, 'input>, style: &Style, bounds: Option<Bounds>) -> Option<Paint> {
+		// attributes and stops that aren't on the gradient itself come from the gradients it references
+		let mut chain = vec![server];
+		while let Some(&next) = href(chain[chain.len() - 1]).and_then(|id| self.ids.get(id)) {
+			if chain.contains(&next) || chain.len() as u32 >= MAX_REFERENCE_DEPTH {
+				break;
+			}
+			if is_svg(next, "linearGradient") || is_svg(next, "radialGradient") {
+				chain.push(next);
+			} else {
+				break;
+			}
+		}
+		let attribute = |name: &str| chain.iter().find_map(|node| node.attribute(name));
+
+		let stops_node = chain.iter().find(|node| node.children().any(|child| is_svg(child, "stop")))?;
+		let stops = self.gradient_stops(*stops_node, style);
+		if stops.len() == 1 {
+			return Some(Paint::Solid(stops[0].color));
+		}
+
+		let mut transform = match attribute("gradientTransform") {
+			Some(text) => parse_transform(text).unwrap_or_else(|| {
+				self.report(server, "invalid gradientTransform");
+				Transform::IDENTITY
+			}),
+			None => Transform::IDENTITY,
+		};
+		let user_space = attribute("gradientUnits") == Some("userSpaceOnUse");
+		if !user_space {
+			// a gradient relative to the bounds of something without an area isn't drawn
+			let bounds = bounds?;
+			if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+				return None;
+			}
+			transform = transform.then(
+				&Transform::scale(bounds.width(), bounds.height())
+					.then(&Transform::translate(bounds.left_top[0], bounds.left_top[1])),
+			);
+		}
+		let spread = match attribute("spreadMethod") {
+			Some("reflect") => SpreadMethod::Reflect,
+			Some("repeat") => SpreadMethod::Repeat,
+			_ => SpreadMethod::Pad,
+		};
+		let viewport = self.viewport;
+		let coordinate = |name: &str, default: Length, axis: Axis| {
+			let length = attribute(name).and_then(parse_length).unwrap_or(default);
+			match user_space {
+				true => length.resolve(axis.reference(viewport)),
+				false => length.resolve(1.0),
+			}
+		};
+
+		if is_svg(server, "linearGradient") {
+			Some(Paint::LinearGradient(LinearGradient {
+				start: [
+					coordinate("x1", Length::Percent(0.0), Axis::X),
+					coordinate("y1", Length::Percent(0.0), Axis::Y),
+				],
+				end: [coordinate("x2", Length::Percent(1.0), Axis::X), coordinate("y2", Length::Percent(0.0), Axis::Y)],
+				stops,
+				spread,
+				transform,
+			}))
+		} else {
+			let center =
+				[coordinate("cx", Length::Percent(0.5), Axis::X), coordinate("cy", Length::Percent(0.5), Axis::Y)];
+			let focal = [
+				coordinate("fx", Length::User(center[0]), Axis::X),
+				coordinate("fy", Length::User(center[1]), Axis::Y),
+			];
+			if coordinate("fr", Length::User(0.0), Axis::Other) != 0.0 {
+				self.report(server, "focal radii");
+			}
+			Some(Paint::RadialGradient(RadialGradient {
+				center,
+				radius: coordinate("r", Length::Percent(0.5), Axis::Other),
+				focal,
+				stops,
+				spread,
+				transform,
+			}))
+		}
+	}
+
+	fn gradient_stops(&mut self, node: Node, style: &Style) -> Vec<GradientStop> {
+		let mut stops: Vec<GradientStop> = vec![];
+		for stop in node.children().filter(|child| is_svg(*child, "stop")) {
+			// offsets can't go backwards
+			let min_offset = stops.last().map_or(0.0, |stop| stop.offset);
+			let offset = stop.attribute("offset").and_then(parse_length).map_or(0.0, |offset| offset.resolve(1.0));
+			let color = match property(stop, "stop-color") {
+				Some("currentColor") => property(stop, "color").and_then(parse_color).unwrap_or(style.color),
+				Some(text) => parse_color(text).unwrap_or_else(|| {
+					self.report(stop, format!("invalid stop-color \"{}\"", text));
+					Color::BLACK
+				}),
+				None => Color::BLACK,
+			};
+			let opacity = property(stop, "stop-opacity").and_then(parse_opacity).unwrap_or(1.0);
+			stops.push(GradientStop {
+				offset: offset.clamp(min_offset, 1.0),
+				color: color.with_alpha(color.a * opacity),
+			});
+		}
+		stops
+	}
+
+	/// The inherited properties of `node`.
+	fn cascade(&mut self, parent: &Style, node: Node) -> Style {
+		let mut style = parent.clone();
+		if let Some(fill) = self.property(node, "fill", parse_paint) {
+			style.fill = fill;
+		}
+		if let Some(opacity) = self.property(node, "fill-opacity", parse_opacity) {
+			style.fill_opacity = opacity;
+		}
+		if let Some(rule) = self.property(node, "fill-rule", parse_fill_rule) {
+			style.fill_rule = rule;
+		}
+		if let Some(stroke) = self.property(node, "stroke", parse_paint) {
+			style.stroke = stroke;
+		}
+		if let Some(opacity) = self.property(node, "stroke-opacity", parse_opacity) {
+			style.stroke_opacity = opacity;
+		}
+		let non_negative = |text: &str| parse_length(text).filter(|length| length.resolve(1.0) >= 0.0);
+		if let Some(width) = self.property(node, "stroke-width", non_negative) {
+			style.stroke_width = width;
+		}
+		if let Some(cap) = self.property(node, "stroke-linecap", parse_line_cap) {
+			style.stroke_cap = cap;
+		}
+		if let Some(join) = self.property(node, "stroke-linejoin", parse_line_join) {
+			if matches!(property(node, "stroke-linejoin"), Some("miter-clip" | "arcs")) {
+				self.report(node, "miter-clip and arcs line joins, which are drawn as miter joins");
+			}
+			style.stroke_join = join;
+		}
+		let miter_limit = |text: &str| parse_number(text).filter(|&limit| limit >= 1.0);
+		if let Some(limit) = self.property(node, "stroke-miterlimit", miter_limit) {
+			style.stroke_miter_limit = limit;
+		}
+		if let Some(dash_array) = self.property(node, "stroke-dasharray", parse_dash_array) {
+			style.stroke_dash_array = dash_array;
+		}
+		if let Some(offset) = self.property(node, "stroke-dashoffset", parse_length) {
+			style.stroke_dash_offset = offset;
+		}
+		if let Some(color) = self.property(node, "color", parse_color) {
+			style.color = color;
+		}
+		if let Some(visible) = self.property(node, "visibility", parse_visibility) {
+			style.visible = visible;
+		}
+		style
+	}
+
+	/// A property of `node`, or `None` if it's not set or invalid, which is reported.
+	fn property<T>(&mut self, node: Node, name: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+		let text = property(node, name)?;
+		let value = parse(text);
+		if value.is_none() {
+			self.report(node, format!("invalid {} \"{}\"", name, text));
+		}
+		value
+	}
+
+	fn opacity(&mut self, node: Node) -> f32 {
+		self.property(node, "opacity", parse_opacity).unwrap_or(1.0)
+	}
+
+	fn transform(&mut self, node: Node) -> Transform {
+		match node.attribute("transform") {
+			Some(text) => parse_transform(text).unwrap_or_else(|| {
+				self.report(node, format!("invalid transform \"{}\"", text));
+				Transform::IDENTITY
+			}),
+			None => Transform::IDENTITY,
+		}
+	}
+
+	/// A length attribute in user units, with percentages of the viewport along `axis`.
+	fn length(&mut self, node: Node, name: &str, axis: Axis) -> Option<f32> {
+		let text = node.attribute(name)?;
+		match parse_length(text) {
+			Some(length) => Some(length.resolve(axis.reference(self.viewport))),
+			None => {
+				self.report(node, format!("invalid {} \"{}\"", name, text));
+				None
+			},
+		}
+	}
+
+	/// `[x, y, width, height]`, if there's a valid `viewBox`.
+	fn view_box(&mut self, node: Node) -> Option<[f32; 4]> {
+		let text = node.attribute("viewBox")?;
+		match parse_number_list(text) {
+			(numbers, true) if numbers.len() == 4 && numbers[2] > 0.0 && numbers[3] > 0.0 => {
+				Some([numbers[0], numbers[1], numbers[2], numbers[3]])
+			},
+			_ => {
+				self.report(node, format!("invalid viewBox \"{}\"", text));
+				None
+			},
+		}
+	}
+
+	/// The `preserveAspectRatio` of `node`, which defaults to centering the `viewBox` without cutting any of it off.
+	fn aspect_ratio(&mut self, node: Node) -> AspectRatio {
+		let text = match node.attribute("preserveAspectRatio") {
+			Some(text) => text,
+			None => return AspectRatio::DEFAULT,
+		};
+		parse_aspect_ratio(text).unwrap_or_else(|| {
+			self.report(node, format!("invalid preserveAspectRatio \"{}\"", text));
+			AspectRatio::DEFAULT
+		})
+	}
+
+	fn report_unsupported_properties(&mut self, node: Node) {
+		for (name, default) in [
+			("clip-path", "none"),
+			("mask", "none"),
+			("filter", "none"),
+			("marker", "none"),
+			("marker-start", "none"),
+			("marker-mid", "none"),
+			("marker-end", "none"),
+			("mix-blend-mode", "normal"),
+			("paint-order", "normal"),
+			("vector-effect", "none"),
+		] {
+			// shapes blend, but groups would have to be drawn separately first
+			if name == "mix-blend-mode" && is_shape(node.tag_name().name()) {
+				continue;
+			}
+			if property(node, name).is_some_and(|value| value != default) {
+				self.report(node, format!("the {} property", name));
+			}
+		}
+	}
+}
+
+/// Whether `node` is the SVG element `tag`. Documents without the SVG namespace are accepted too.
+fn is_svg(node: Node, tag: &str) -> bool {
+	node.is_element() && node.tag_name().name() == tag && matches!(node.tag_name().namespace(), None | Some(SVG_NS))
+}
+
+fn is_shape(tag: &str) -> bool {
+	matches!(tag, "path" | "rect" | "circle" | "ellipse" | "line" | "polyline" | "polygon")
+}
+
+/// The id an `href` or `xlink:href` points to.
+fn href<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+	node.attribute("href").or_else(|| node.attribute((XLINK_NS, "href")))?.strip_prefix('#')
+}
+
+/// A property from the `style` attribute, or else the presentation attribute. `inherit` is the same as not setting
+/// it.
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+	let declared = node.attribute("style").and_then(|style| {
+		style
+			.split(';')
+			.filter_map(|declaration| declaration.split_once(':'))
+			.rfind(|(key, _)| key.trim() == name)
+			.map(|(_, value)| value.trim().trim_end_matches("!important").trim_end())
+	});
+	declared.or_else(|| node.attribute(name).map(str::trim)).filter(|&value| value != "inherit")
+}
+
+fn parse_opacity(text: &str) -> Option<f32> {
+	let opacity = match text.strip_suffix('%') {
+		Some(percent) => parse_number(percent)? / 100.0,
+		None => parse_number(text)?,
+	};
+	Some(opacity.clamp(0.0, 1.0))
+}
+
+fn parse_fill_rule(text: &str) -> Option<FillRule> {
+	match text {
+		"nonzero" => Some(FillRule::NonZero),
+		"evenodd" => Some(FillRule::EvenOdd),
+		_ => None,
+	}
+}
+
+fn parse_line_cap(text: &str) -> Option<LineCap> {
+	match text {
+		"butt" => Some(LineCap::Butt),
+		"round" => Some(LineCap::Round),
+		"square" => Some(LineCap::Square),
+		_ => None,
+	}
+}
+
+/// The miter limit is filled in when the stroke is drawn, since it's a separate property.
+fn parse_line_join(text: &str) -> Option<LineJoin> {
+	match text {
+		"miter" | "miter-clip" | "arcs" => Some(LineJoin::Miter(4.0)),
+		"round" => Some(LineJoin::Round),
+		"bevel" => Some(LineJoin::Bevel),
+		_ => None,
+	}
+}
+
+fn parse_dash_array(text: &str) -> Option<Option<Vec<Length>>> {
+	match text {
+		"none" => Some(None),
+		_ => parse_length_list(text).map(Some),
+	}
+}
+
+fn parse_visibility(text: &str) -> Option<bool> {
+	match text {
+		"visible" => Some(true),
+		"hidden" | "collapse" => Some(false),
+		_ => None,
+	}
+}
+
+/// A parsed `preserveAspectRatio`.
+#[derive(Clone, Copy)]
+struct AspectRatio {
+	/// Where the `viewBox` goes in any space it leaves, from 0 for the left or top to 1 for the right or bottom. `None`
+	/// stretches it to fill the viewport instead.
+	align: Option<[f32; 2]>,
+	/// Whether the `viewBox` covers the whole viewport, cutting off what doesn't fit, rather than fitting inside it.
+	slice: bool,
+}
+impl AspectRatio {
+	const DEFAULT: Self = Self { align: Some([0.5, 0.5]), slice: false };
+}
+
+/// `[defer] <align> [meet | slice]`, where `<align>` is `none` or one of the nine `x{Min,Mid,Max}Y{Min,Mid,Max}`.
+fn parse_aspect_ratio(text: &str) -> Option<AspectRatio> {
+	let mut words = text.split_ascii_whitespace().peekable();
+	words.next_if_eq(&"defer");
+	let align = match words.next()? {
+		"none" => None,
+		align => Some(parse_align(align)?),
+	};
+	let slice = match words.next() {
+		None | Some("meet") => false,
+		Some("slice") => true,
+		Some(_) => return None,
+	};
+	words.next().is_none().then_some(AspectRatio { align, slice })
+}
+
+/// One of the nine `x{Min,Mid,Max}Y{Min,Mid,Max}` values, as positions along each axis.
+fn parse_align(text: &str) -> Option<[f32; 2]> {
+	/// Strips `axis` and a position along it off the front of `text`.
+	fn position(text: &str, axis: char) -> Option<(f32, &str)> {
+		let text = text.strip_prefix(axis)?;
+		[("Min", 0.0), ("Mid", 0.5), ("Max", 1.0)]
+			.into_iter()
+			.find_map(|(name, position)| Some((position, text.strip_prefix(name)?)))
+	}
+	let (x, rest) = position(text, 'x')?;
+	let (y, rest) = position(rest, 'Y')?;
+	rest.is_empty().then_some([x, y])
+}
+
+/// Maps a `viewBox` onto a viewport of `size` at the origin, following `preserveAspectRatio`.
+fn view_box_transform(view_box: [f32; 4], aspect_ratio: AspectRatio, size: [f32; 2]) -> Transform {
+	let mut scale = [size[0] / view_box[2], size[1] / view_box[3]];
+	let mut offset = [0.0; 2];
+	if let Some(align) = aspect_ratio.align {
+		let uniform = if aspect_ratio.slice { scale[0].max(scale[1]) } else { scale[0].min(scale[1]) };
+		scale = [uniform; 2];
+		let slack = [size[0] - view_box[2] * uniform, size[1] - view_box[3] * uniform];
+		offset = [slack[0] * align[0], slack[1] * align[1]];
+	}
+	Transform([scale[0], 0.0, 0.0, scale[1], offset[0] - view_box[0] * scale[0], offset[1] - view_box[1] * scale[1]])
+}
+
+/// A rectangle with elliptical corners, clockwise from the end of the top left corner, as in the SVG spec.
+fn rect(origin: [f32; 2], size: [f32; 2], radii: [f32; 2]) -> Path {
+	let [x, y] = origin;
+	let [right, bottom] = [x + size[0], y + size[1]];
+	let [rx, ry] = radii;
+	if rx == 0.0 || ry == 0.0 {
+		return Path::start()
+			.move_to([x, y])
+			.line_to([right, y])
+			.line_to([right, bottom])
+			.line_to([x, bottom])
+			.line_to([x, y]);
+	}
+	let w = FRAC_1_SQRT_2;
+	Path::start()
+		.move_to([x + rx, y])
+		.line_to([right - rx, y])
+		.conic_to([right, y], [right, y + ry], w)
+		.line_to([right, bottom - ry])
+		.conic_to([right, bottom], [right - rx, bottom], w)
+		.line_to([x + rx, bottom])
+		.conic_to([x, bottom], [x, bottom - ry], w)
+		.line_to([x, y + ry])
+		.conic_to([x, y], [x + rx, y], w)
+}
+
+/// An ellipse drawn with four conics, clockwise from its rightmost point, as in the SVG spec.
+fn ellipse(center: [f32; 2], radii: [f32; 2]) -> Path {
+	let [cx, cy] = center;
+	let [rx, ry] = radii;
+	let w = FRAC_1_SQRT_2;
+	Path::start()
+		.move_to([cx + rx, cy])
+		.conic_to([cx + rx, cy + ry], [cx, cy + ry], w)
+		.conic_to([cx - rx, cy + ry], [cx - rx, cy], w)
+		.conic_to([cx - rx, cy - ry], [cx, cy - ry], w)
+		.conic_to([cx + rx, cy - ry], [cx + rx, cy], w)
+}
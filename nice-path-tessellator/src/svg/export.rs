@@ -0,0 +1,232 @@
+use std::fmt::Write;
+
+use crate::{
+	BlendMode, Color, Conic, Draw, FillRule, GradientStop, Paint, Path, PathCommands, Scene, SpreadMethod, Transform,
+	DEFAULT_SVG_TOLERANCE,
+};
+
+impl Path {
+	/// Writes the path as the `d` attribute of an SVG `<path>`, in absolute coordinates. Elliptical conics become
+	/// arcs, so circles stay exact, but hyperbolic ones (with a weight above 1) are approximated with quadratics. A
+	/// subpath that ends where it started is closed with `Z`, since that's how it's stroked.
+	pub fn to_svg_path_data(&self) -> String {
+		let mut d = String::new();
+		let commands = self.commands();
+		let mut pen = [0.0; 2];
+		let mut start = [0.0; 2];
+		// the first subpath starts at the origin if there's no move
+		if !matches!(commands.first(), None | Some(PathCommands::MoveTo(_))) {
+			command(&mut d, 'M', &[0.0, 0.0]);
+		}
+		for (i, cmd) in commands.iter().enumerate() {
+			let ends_subpath = !matches!(commands.get(i + 1), Some(cmd) if !matches!(cmd, PathCommands::MoveTo(_)));
+			let after_move = i == 0 || matches!(commands[i - 1], PathCommands::MoveTo(_));
+			match *cmd {
+				PathCommands::MoveTo(to) => {
+					command(&mut d, 'M', &to);
+					start = to;
+				},
+				// the closing line is implied by Z, unless it's the subpath's only segment
+				PathCommands::LineTo(to) if ends_subpath && to == start && !after_move => {},
+				PathCommands::LineTo(to) => command(&mut d, 'L', &to),
+				PathCommands::QuadraticCurveTo(ctrl, to) => command(&mut d, 'Q', &[ctrl[0], ctrl[1], to[0], to[1]]),
+				PathCommands::ConicTo(ctrl, to, weight) => conic(&mut d, &Conic::new(pen, ctrl, to, weight)),
+			}
+			pen = match *cmd {
+				PathCommands::MoveTo(to)
+				| PathCommands::LineTo(to)
+				| PathCommands::QuadraticCurveTo(_, to)
+				| PathCommands::ConicTo(_, to, _) => to,
+			};
+			if ends_subpath && pen == start && !matches!(cmd, PathCommands::MoveTo(_)) {
+				d.push_str(" Z");
+			}
+		}
+		d.trim_start().to_string()
+	}
+
+	/// Writes an SVG document that fills the path in black, framed by its bounds.
+	pub fn to_svg(&self, fill_rule: FillRule) -> String {
+		let view_box = match self.bounds() {
+			Some(bounds) => [
+				bounds.left_top[0],
+				bounds.left_top[1],
+				bounds.right_bottom[0] - bounds.left_top[0],
+				bounds.right_bottom[1] - bounds.left_top[1],
+			],
+			None => [0.0; 4],
+		};
+		let mut draw = Draw::new(self.clone(), Paint::Solid(Color::BLACK));
+		draw.fill_rule = fill_rule;
+		document(view_box, &[draw])
+	}
+}
+
+impl Scene {
+	/// Writes the scene as an SVG document, with a `<path>` for every draw. Gradients are written in user space, so
+	/// they look the same as in the scene. [`Scene::from_svg`] reads it back as it was, except that colours are
+	/// rounded to 8 bits, hyperbolic conics are approximated, and the Porter-Duff blend modes CSS doesn't have are
+	/// left out.
+	pub fn to_svg(&self) -> String {
+		document([0.0, 0.0, self.size[0], self.size[1]], &self.draws)
+	}
+}
+
+fn document(view_box: [f32; 4], draws: &[Draw]) -> String {
+	let mut svg = String::new();
+	let _ = writeln!(
+		svg,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+		number(view_box[2]),
+		number(view_box[3]),
+		number(view_box[0]),
+		number(view_box[1]),
+		number(view_box[2]),
+		number(view_box[3]),
+	);
+	for (i, draw) in draws.iter().enumerate() {
+		let id = format!("paint{}", i);
+		let fill = match &draw.paint {
+			Paint::Solid(color) => {
+				let mut fill = format!(r#"fill="{}""#, hex(*color));
+				if color.a < 1.0 {
+					let _ = write!(fill, r#" fill-opacity="{}""#, number(color.a));
+				}
+				fill
+			},
+			// like in SVG, a gradient without stops paints nothing
+			Paint::LinearGradient(gradient) if gradient.stops.is_empty() => r#"fill="none""#.to_string(),
+			Paint::RadialGradient(gradient) if gradient.stops.is_empty() => r#"fill="none""#.to_string(),
+			Paint::LinearGradient(gradient) => {
+				let _ = write!(
+					svg,
+					r#"	<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}""#,
+					id,
+					number(gradient.start[0]),
+					number(gradient.start[1]),
+					number(gradient.end[0]),
+					number(gradient.end[1]),
+				);
+				gradient_element(&mut svg, "linearGradient", gradient.spread, &gradient.transform, &gradient.stops);
+				format!(r#"fill="url(#{})""#, id)
+			},
+			Paint::RadialGradient(gradient) => {
+				let _ = write!(
+					svg,
+					r#"	<radialGradient id="{}" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{}" fx="{}" fy="{}""#,
+					id,
+					number(gradient.center[0]),
+					number(gradient.center[1]),
+					number(gradient.radius),
+					number(gradient.focal[0]),
+					number(gradient.focal[1]),
+				);
+				gradient_element(&mut svg, "radialGradient", gradient.spread, &gradient.transform, &gradient.stops);
+				format!(r#"fill="url(#{})""#, id)
+			},
+		};
+
+		let _ = write!(svg, r#"	<path d="{}" {}"#, draw.path.to_svg_path_data(), fill);
+		if draw.fill_rule == FillRule::EvenOdd {
+			svg.push_str(r#" fill-rule="evenodd""#);
+		}
+		if draw.opacity != 1.0 {
+			let _ = write!(svg, r#" opacity="{}""#, number(draw.opacity));
+		}
+		if let Some(name) = draw.blend_mode.css_name().filter(|_| draw.blend_mode != BlendMode::SrcOver) {
+			let _ = write!(svg, r#" style="mix-blend-mode:{}""#, name);
+		}
+		transform_attribute(&mut svg, "transform", &draw.transform);
+		svg.push_str("/>\n");
+	}
+	svg.push_str("</svg>\n");
+	svg
+}
+
+/// Finishes a gradient element whose start tag is open.
+fn gradient_element(svg: &mut String, tag: &str, spread: SpreadMethod, transform: &Transform, stops: &[GradientStop]) {
+	match spread {
+		SpreadMethod::Pad => {},
+		SpreadMethod::Reflect => svg.push_str(r#" spreadMethod="reflect""#),
+		SpreadMethod::Repeat => svg.push_str(r#" spreadMethod="repeat""#),
+	}
+	transform_attribute(svg, "gradientTransform", transform);
+	svg.push_str(">\n");
+	for stop in stops {
+		let _ = write!(svg, r#"		<stop offset="{}" stop-color="{}""#, number(stop.offset), hex(stop.color));
+		if stop.color.a < 1.0 {
+			let _ = write!(svg, r#" stop-opacity="{}""#, number(stop.color.a));
+		}
+		svg.push_str("/>\n");
+	}
+	let _ = writeln!(svg, "	</{}>", tag);
+}
+
+fn transform_attribute(svg: &mut String, name: &str, transform: &Transform) {
+	if !transform.is_identity() {
+		let [a, b, c, d, e, f] = transform.0.map(number);
+		let _ = write!(svg, r#" {}="matrix({} {} {} {} {} {})""#, name, a, b, c, d, e, f);
+	}
+}
+
+fn hex(color: Color) -> String {
+	let [r, g, b, _] = color.to_rgba8();
+	format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// The shortest text that parses back to `x`.
+fn number(x: f32) -> String {
+	// adding zero turns -0 into 0
+	format!("{}", x + 0.0)
+}
+
+fn command(d: &mut String, letter: char, args: &[f32]) {
+	let _ = write!(d, " {}", letter);
+	for (i, arg) in args.iter().enumerate() {
+		let separator = if i == 0 { "" } else { " " };
+		let _ = write!(d, "{}{}", separator, number(*arg));
+	}
+}
+
+/// Writes a conic as an arc if it's part of an ellipse.
+fn conic(d: &mut String, conic: &Conic) {
+	let [p0, p1, p2] = [conic.from, conic.ctrl, conic.to].map(|p| p.map(|c| c as f64));
+	let w = conic.weight as f64;
+	let sub = |a: [f64; 2], b: [f64; 2]| [a[0] - b[0], a[1] - b[1]];
+	let (a, b) = (sub(p1, p0), sub(p2, p1));
+	let cross = a[0] * b[1] - a[1] * b[0];
+	if cross == 0.0 {
+		// the control point is on the chord, so the conic doesn't leave it
+		command(d, 'L', &conic.to);
+		return;
+	}
+	if w >= 1.0 {
+		for quad in conic.to_quadratics(DEFAULT_SVG_TOLERANCE) {
+			command(d, 'Q', &[quad.ctrl[0], quad.ctrl[1], quad.to[0], quad.to[1]]);
+		}
+		return;
+	}
+
+	// the conic is the arc of the unit circle from -alpha to alpha, with cos(alpha) = w, mapped by an affine transform
+	// whose columns u and v are conjugate semi-diameters of the ellipse around `center`
+	let mid = [(p0[0] + p2[0]) / 2.0, (p0[1] + p2[1]) / 2.0];
+	let center = [0, 1].map(|i| p1[i] + (mid[i] - p1[i]) / (1.0 - w * w));
+	let sin = (1.0 - w * w).sqrt();
+	let u = [0, 1].map(|i| (p1[i] - center[i]) * w);
+	let v = [0, 1].map(|i| (p2[i] - p0[i]) / (2.0 * sin));
+
+	// the radii and rotation are the singular values and the left rotation of [u v]
+	let e = (u[0] + v[1]) / 2.0;
+	let f = (u[0] - v[1]) / 2.0;
+	let g = (u[1] + v[0]) / 2.0;
+	let h = (u[1] - v[0]) / 2.0;
+	let q = e.hypot(h);
+	let r = f.hypot(g);
+	let rotation = (g.atan2(f) + h.atan2(e)) / 2.0;
+	let sweep = (cross > 0.0) as u8 as f32;
+	command(
+		d,
+		'A',
+		&[(q + r) as f32, (q - r).abs() as f32, rotation.to_degrees() as f32, 0.0, sweep, conic.to[0], conic.to[1]],
+	);
+}
@@ -0,0 +1,392 @@
+use std::{
+	f32::consts::{FRAC_PI_2, PI},
+	fmt,
+};
+
+use crate::{
+	geom::{add, length, lerp, scale, sub},
+	Path, PathCommands, Transform,
+};
+
+/// Where and why SVG path data couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathDataError {
+	/// Byte offset into the path data.
+	pub position: usize,
+	pub message: &'static str,
+}
+impl fmt::Display for PathDataError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid path data at byte {}: {}", self.position, self.message)
+	}
+}
+impl std::error::Error for PathDataError {}
+
+/// The largest number of quadratics a single cubic is approximated with.
+const MAX_CUBIC_PIECES: usize = 64;
+
+/// Parses as much of `d` as is valid. Like browsers, everything up to the first error is kept, and the error is
+/// returned with it.
+pub(crate) fn parse_path_data(d: &str, tolerance: f32) -> (Path, Option<PathDataError>) {
+	let mut parser = Parser { bytes: d.as_bytes(), pos: 0 };
+	let mut out = PathBuilder::new();
+	let result = parse_commands(&mut parser, &mut out, tolerance);
+	(out.path, result.err())
+}
+
+fn parse_commands(parser: &mut Parser, out: &mut PathBuilder, tolerance: f32) -> Result<(), PathDataError> {
+	let mut command = None;
+	// the control point a smooth curve command reflects, and whether it was a cubic's
+	let mut last_ctrl: Option<([f32; 2], bool)> = None;
+	loop {
+		parser.skip_whitespace();
+		let next = match parser.peek() {
+			Some(next) => next,
+			None => return Ok(()),
+		};
+		if next.is_ascii_alphabetic() {
+			if command.is_none() && !matches!(next, b'M' | b'm') {
+				return Err(parser.error("path data must start with a move"));
+			}
+			parser.pos += 1;
+			command = Some(next);
+		} else if command.is_none() {
+			return Err(parser.error("expected a command"));
+		} else if matches!(command, Some(b'Z' | b'z')) {
+			return Err(parser.error("expected a command after a close path"));
+		}
+		let cmd = command.unwrap();
+		let relative = cmd.is_ascii_lowercase();
+		let origin = if relative { out.pen } else { [0.0; 2] };
+		let point = |parser: &mut Parser| -> Result<[f32; 2], PathDataError> {
+			let x = parser.number()?;
+			let y = parser.number()?;
+			Ok(add(origin, [x, y]))
+		};
+
+		let mut ctrl = None;
+		match cmd.to_ascii_uppercase() {
+			b'M' => {
+				let to = point(parser)?;
+				out.move_to(to);
+				// further pairs are lines
+				command = Some(if relative { b'l' } else { b'L' });
+			},
+			b'Z' => out.close(),
+			b'L' => {
+				let to = point(parser)?;
+				out.line_to(to);
+			},
+			b'H' => {
+				let x = parser.number()?;
+				out.line_to([if relative { out.pen[0] + x } else { x }, out.pen[1]]);
+			},
+			b'V' => {
+				let y = parser.number()?;
+				out.line_to([out.pen[0], if relative { out.pen[1] + y } else { y }]);
+			},
+			b'C' => {
+				let c1 = point(parser)?;
+				let c2 = point(parser)?;
+				let to = point(parser)?;
+				out.cubic_to(c1, c2, to, tolerance);
+				ctrl = Some((c2, true));
+			},
+			b'S' => {
+				let c1 = reflect(last_ctrl, true, out.pen);
+				let c2 = point(parser)?;
+				let to = point(parser)?;
+				out.cubic_to(c1, c2, to, tolerance);
+				ctrl = Some((c2, true));
+			},
+			b'Q' => {
+				let c = point(parser)?;
+				let to = point(parser)?;
+				out.quadratic_to(c, to);
+				ctrl = Some((c, false));
+			},
+			b'T' => {
+				let c = reflect(last_ctrl, false, out.pen);
+				let to = point(parser)?;
+				out.quadratic_to(c, to);
+				ctrl = Some((c, false));
+			},
+			b'A' => {
+				let rx = parser.number()?;
+				let ry = parser.number()?;
+				let rotation = parser.number()?;
+				let large_arc = parser.flag()?;
+				let sweep = parser.flag()?;
+				let to = point(parser)?;
+				out.arc_to([rx, ry], rotation.to_radians(), large_arc, sweep, to);
+			},
+			_ => {
+				parser.pos -= 1;
+				return Err(parser.error("unknown command"));
+			},
+		}
+		last_ctrl = ctrl;
+	}
+}
+
+/// The reflection of the previous control point, if the previous command was the same kind of curve, or the pen.
+fn reflect(last_ctrl: Option<([f32; 2], bool)>, cubic: bool, pen: [f32; 2]) -> [f32; 2] {
+	match last_ctrl {
+		Some((ctrl, was_cubic)) if was_cubic == cubic => sub(scale(pen, 2.0), ctrl),
+		_ => pen,
+	}
+}
+
+struct Parser<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+}
+impl Parser<'_> {
+	fn error(&self, message: &'static str) -> PathDataError {
+		PathDataError { position: self.pos, message }
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.bytes.get(self.pos).copied()
+	}
+
+	fn skip_whitespace(&mut self) {
+		while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')) {
+			self.pos += 1;
+		}
+	}
+
+	/// Skips whitespace, and at most one comma.
+	fn skip_separator(&mut self) {
+		self.skip_whitespace();
+		if self.peek() == Some(b',') {
+			self.pos += 1;
+			self.skip_whitespace();
+		}
+	}
+
+	fn number(&mut self) -> Result<f32, PathDataError> {
+		self.skip_separator();
+		let start = self.pos;
+		if matches!(self.peek(), Some(b'+' | b'-')) {
+			self.pos += 1;
+		}
+		let int_digits = self.digits();
+		let mut frac_digits = 0;
+		if self.peek() == Some(b'.') {
+			self.pos += 1;
+			frac_digits = self.digits();
+		}
+		if int_digits + frac_digits == 0 {
+			self.pos = start;
+			return Err(self.error("expected a number"));
+		}
+		if matches!(self.peek(), Some(b'e' | b'E')) {
+			// an `e` that isn't followed by an exponent belongs to whatever comes next
+			let before_exponent = self.pos;
+			self.pos += 1;
+			if matches!(self.peek(), Some(b'+' | b'-')) {
+				self.pos += 1;
+			}
+			if self.digits() == 0 {
+				self.pos = before_exponent;
+			}
+		}
+		let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+		let value: f32 = text.parse().map_err(|_| PathDataError { position: start, message: "expected a number" })?;
+		match value.is_finite() {
+			true => Ok(value),
+			false => Err(PathDataError { position: start, message: "number out of range" }),
+		}
+	}
+
+	fn digits(&mut self) -> usize {
+		let start = self.pos;
+		while matches!(self.peek(), Some(b'0'..=b'9')) {
+			self.pos += 1;
+		}
+		self.pos - start
+	}
+
+	/// An arc flag, which is a single digit that needn't be separated from what follows it.
+	fn flag(&mut self) -> Result<bool, PathDataError> {
+		self.skip_separator();
+		let flag = match self.peek() {
+			Some(b'0') => false,
+			Some(b'1') => true,
+			_ => return Err(self.error("expected a flag")),
+		};
+		self.pos += 1;
+		Ok(flag)
+	}
+}
+
+/// Builds a [`Path`] with SVG's pen semantics, where drawing after a close path starts a new subpath where the
+/// closed one started.
+struct PathBuilder {
+	path: Path,
+	pen: [f32; 2],
+	start: [f32; 2],
+	closed: bool,
+	in_subpath: bool,
+}
+impl PathBuilder {
+	fn new() -> Self {
+		Self { path: Path::start(), pen: [0.0; 2], start: [0.0; 2], closed: false, in_subpath: false }
+	}
+
+	fn move_to(&mut self, to: [f32; 2]) {
+		self.path.push(PathCommands::MoveTo(to));
+		self.pen = to;
+		self.start = to;
+		self.closed = false;
+		self.in_subpath = true;
+	}
+
+	/// Called before drawing anything.
+	fn reopen(&mut self) {
+		if self.closed || !self.in_subpath {
+			self.move_to(self.start);
+		}
+	}
+
+	fn close(&mut self) {
+		if !self.in_subpath {
+			return;
+		}
+		// paths are closed implicitly when filled, but strokes need the closing segment to be explicit
+		if self.pen != self.start {
+			self.path.push(PathCommands::LineTo(self.start));
+		}
+		self.pen = self.start;
+		self.closed = true;
+	}
+
+	fn line_to(&mut self, to: [f32; 2]) {
+		self.reopen();
+		self.path.push(PathCommands::LineTo(to));
+		self.pen = to;
+	}
+
+	fn quadratic_to(&mut self, ctrl: [f32; 2], to: [f32; 2]) {
+		self.reopen();
+		self.path.push(PathCommands::QuadraticCurveTo(ctrl, to));
+		self.pen = to;
+	}
+
+	fn conic_to(&mut self, ctrl: [f32; 2], to: [f32; 2], weight: f32) {
+		self.reopen();
+		self.path.push(PathCommands::ConicTo(ctrl, to, weight));
+		self.pen = to;
+	}
+
+	/// Cubics are approximated with quadratics. A quadratic is within `√3/36 |p3 - 3 c2 + 3 c1 - p0|` of the cubic
+	/// it's fitted to, and splitting the cubic into `n` pieces divides that by `n³`, so the number of pieces is known
+	/// up front.
+	fn cubic_to(&mut self, c1: [f32; 2], c2: [f32; 2], to: [f32; 2], tolerance: f32) {
+		self.reopen();
+		let points = [self.pen, c1, c2, to];
+		let third_difference = add(sub(sub(to, scale(c2, 3.0)), self.pen), scale(c1, 3.0));
+		let error = 3.0f32.sqrt() / 36.0 * length(third_difference);
+		let pieces = match tolerance > 0.0 {
+			true => ((error / tolerance).cbrt().ceil() as usize).clamp(1, MAX_CUBIC_PIECES),
+			false => MAX_CUBIC_PIECES,
+		};
+		for i in 0..pieces {
+			let (t0, t1) = (i as f32 / pieces as f32, (i + 1) as f32 / pieces as f32);
+			let from = blossom(&points, t0, t0, t0);
+			let a = blossom(&points, t0, t0, t1);
+			let b = blossom(&points, t0, t1, t1);
+			let end = if i + 1 == pieces { to } else { blossom(&points, t1, t1, t1) };
+			let ctrl = scale(sub(scale(add(a, b), 3.0), add(from, end)), 0.25);
+			self.path.push(PathCommands::QuadraticCurveTo(ctrl, end));
+		}
+		self.pen = to;
+	}
+
+	/// An elliptical arc, from the endpoint parametrisation in the SVG spec, drawn exactly with one conic per quarter
+	/// turn or less.
+	fn arc_to(&mut self, radii: [f32; 2], rotation: f32, large_arc: bool, sweep: bool, to: [f32; 2]) {
+		let from = self.pen;
+		let [mut rx, mut ry] = radii.map(f32::abs);
+		if from == to {
+			return;
+		}
+		if rx == 0.0 || ry == 0.0 {
+			self.line_to(to);
+			return;
+		}
+
+		// the endpoints in the ellipse's unrotated frame, relative to the midpoint of the chord
+		let unrotate = Transform::rotate(-rotation);
+		let p = unrotate.apply_vector(scale(sub(from, to), 0.5));
+		// radii that are too small are scaled up until the ellipse just fits
+		let lambda = (p[0] / rx).powi(2) + (p[1] / ry).powi(2);
+		if lambda > 1.0 {
+			rx *= lambda.sqrt();
+			ry *= lambda.sqrt();
+		}
+		let num = (rx * ry).powi(2) - (rx * p[1]).powi(2) - (ry * p[0]).powi(2);
+		let den = (rx * p[1]).powi(2) + (ry * p[0]).powi(2);
+		let mut coef = (num / den).max(0.0).sqrt();
+		if large_arc == sweep {
+			coef = -coef;
+		}
+		let center_unrotated = [coef * rx * p[1] / ry, -coef * ry * p[0] / rx];
+		let center = add(Transform::rotate(rotation).apply_vector(center_unrotated), lerp(from, to, 0.5));
+
+		let angle = |v: [f32; 2]| v[1].atan2(v[0]);
+		let start_angle = angle([(p[0] - center_unrotated[0]) / rx, (p[1] - center_unrotated[1]) / ry]);
+		let end_angle = angle([(-p[0] - center_unrotated[0]) / rx, (-p[1] - center_unrotated[1]) / ry]);
+		let mut sweep_angle = end_angle - start_angle;
+		if sweep && sweep_angle < 0.0 {
+			sweep_angle += 2.0 * PI;
+		} else if !sweep && sweep_angle > 0.0 {
+			sweep_angle -= 2.0 * PI;
+		}
+		// radii or endpoints too big for f32 leave no ellipse to follow
+		if !sweep_angle.is_finite() {
+			self.line_to(to);
+			return;
+		}
+
+		let unit_to_user = Transform::scale(rx, ry)
+			.then(&Transform::rotate(rotation))
+			.then(&Transform::translate(center[0], center[1]));
+		let pieces = ((sweep_angle.abs() / FRAC_PI_2) - 1e-3).ceil().max(1.0) as usize;
+		let step = sweep_angle / pieces as f32;
+		for i in 0..pieces {
+			let mid = start_angle + step * (i as f32 + 0.5);
+			let weight = (step / 2.0).cos();
+			let ctrl = unit_to_user.apply([mid.cos() / weight, mid.sin() / weight]);
+			let end_angle = start_angle + step * (i + 1) as f32;
+			let end = if i + 1 == pieces { to } else { unit_to_user.apply([end_angle.cos(), end_angle.sin()]) };
+			self.conic_to(ctrl, end, weight);
+		}
+	}
+}
+
+/// The blossom of a cubic Bézier, whose values at equal arguments are points on the curve, and which gives the
+/// control points of any piece of it.
+fn blossom(points: &[[f32; 2]; 4], u: f32, v: f32, w: f32) -> [f32; 2] {
+	let a = [lerp(points[0], points[1], u), lerp(points[1], points[2], u), lerp(points[2], points[3], u)];
+	let b = [lerp(a[0], a[1], v), lerp(a[1], a[2], v)];
+	lerp(b[0], b[1], w)
+}
+
+/// Parses numbers separated by whitespace and/or commas, as in `points` and `viewBox`. Parsing stops at the first
+/// thing that isn't a number, and the flag says whether that was the end of `text`.
+pub(super) fn parse_number_list(text: &str) -> (Vec<f32>, bool) {
+	let mut parser = Parser { bytes: text.as_bytes(), pos: 0 };
+	let mut numbers = vec![];
+	loop {
+		parser.skip_whitespace();
+		if parser.peek().is_none() {
+			return (numbers, true);
+		}
+		match parser.number() {
+			Ok(number) => numbers.push(number),
+			Err(_) => return (numbers, false),
+		}
+	}
+}
@@ -0,0 +1,375 @@
+use std::f32::consts::SQRT_2;
+
+use super::path_data::parse_number_list;
+use crate::{Color, Transform};
+
+/// A length that may be relative to the viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Length {
+	/// In user units.
+	User(f32),
+	/// A fraction, so `50%` is 0.5.
+	Percent(f32),
+}
+impl Length {
+	/// Resolves percentages against `reference`.
+	pub(super) fn resolve(self, reference: f32) -> f32 {
+		match self {
+			Length::User(value) => value,
+			Length::Percent(fraction) => fraction * reference,
+		}
+	}
+}
+
+/// Which viewport dimension a percentage refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Axis {
+	X,
+	Y,
+	/// The viewport's diagonal divided by √2, for lengths that aren't along either axis.
+	Other,
+}
+impl Axis {
+	pub(super) fn reference(self, viewport: [f32; 2]) -> f32 {
+		match self {
+			Axis::X => viewport[0],
+			Axis::Y => viewport[1],
+			Axis::Other => viewport[0].hypot(viewport[1]) / SQRT_2,
+		}
+	}
+}
+
+/// CSS pixels per unit. Font-relative units assume the default 16px font, since text isn't supported.
+const UNITS: &[(&str, f32)] = &[
+	("px", 1.0),
+	("pt", 4.0 / 3.0),
+	("pc", 16.0),
+	("mm", 96.0 / 25.4),
+	("cm", 96.0 / 2.54),
+	("in", 96.0),
+	("em", 16.0),
+	("ex", 8.0),
+];
+
+pub(super) fn parse_length(text: &str) -> Option<Length> {
+	let text = text.trim();
+	if let Some(number) = text.strip_suffix('%') {
+		return parse_number(number).map(|value| Length::Percent(value / 100.0));
+	}
+	for &(unit, scale) in UNITS {
+		if let Some(number) = text.strip_suffix(unit) {
+			return parse_number(number).map(|value| Length::User(value * scale));
+		}
+	}
+	parse_number(text).map(Length::User)
+}
+
+pub(super) fn parse_number(text: &str) -> Option<f32> {
+	match parse_number_list(text) {
+		(numbers, true) if numbers.len() == 1 => Some(numbers[0]),
+		_ => None,
+	}
+}
+
+/// A list of lengths separated by commas and/or whitespace, as in `stroke-dasharray`.
+pub(super) fn parse_length_list(text: &str) -> Option<Vec<Length>> {
+	text.split(|c: char| c == ',' || c.is_ascii_whitespace())
+		.filter(|item| !item.is_empty())
+		.map(parse_length)
+		.collect()
+}
+
+/// A `transform` attribute: a list of transform functions, applied right to left.
+pub(super) fn parse_transform(text: &str) -> Option<Transform> {
+	let mut total = Transform::IDENTITY;
+	let mut rest = text.trim();
+	while !rest.is_empty() {
+		let (name, after_name) = rest.split_once('(')?;
+		let (args, after_args) = after_name.split_once(')')?;
+		let args = match parse_number_list(args) {
+			(args, true) => args,
+			_ => return None,
+		};
+		let transform = match (name.trim(), args.as_slice()) {
+			("matrix", &[a, b, c, d, e, f]) => Transform([a, b, c, d, e, f]),
+			("translate", &[x]) => Transform::translate(x, 0.0),
+			("translate", &[x, y]) => Transform::translate(x, y),
+			("scale", &[s]) => Transform::scale(s, s),
+			("scale", &[x, y]) => Transform::scale(x, y),
+			("rotate", &[angle]) => Transform::rotate(angle.to_radians()),
+			("rotate", &[angle, x, y]) => Transform::translate(-x, -y)
+				.then(&Transform::rotate(angle.to_radians()))
+				.then(&Transform::translate(x, y)),
+			("skewX", &[angle]) => Transform::skew_x(angle.to_radians()),
+			("skewY", &[angle]) => Transform::skew_y(angle.to_radians()),
+			_ => return None,
+		};
+		total = transform.then(&total);
+		rest = after_args.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+	}
+	Some(total)
+}
+
+/// A `fill` or `stroke` value.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum SvgPaint {
+	None,
+	Color(Color),
+	CurrentColor,
+	/// A paint server's id, and what to use if it can't be used.
+	Url(String, Option<Box<SvgPaint>>),
+}
+
+pub(super) fn parse_paint(text: &str) -> Option<SvgPaint> {
+	let text = text.trim();
+	if let Some(rest) = text.strip_prefix("url(") {
+		let (url, fallback) = rest.split_once(')')?;
+		let id = url.trim().trim_matches(|c| c == '"' || c == '\'').strip_prefix('#')?;
+		let fallback = match fallback.trim() {
+			"" => None,
+			fallback => Some(Box::new(parse_paint(fallback)?)),
+		};
+		return Some(SvgPaint::Url(id.to_string(), fallback));
+	}
+	match text {
+		"none" => Some(SvgPaint::None),
+		"currentColor" => Some(SvgPaint::CurrentColor),
+		_ => parse_color(text).map(SvgPaint::Color),
+	}
+}
+
+/// A CSS colour: a hex colour, `rgb()`, `rgba()`, `hsl()`, `hsla()`, or a colour keyword.
+pub(super) fn parse_color(text: &str) -> Option<Color> {
+	let text = text.trim();
+	if let Some(hex) = text.strip_prefix('#') {
+		return parse_hex(hex);
+	}
+	if let Some((function, args)) = text.strip_suffix(')').and_then(|text| text.split_once('(')) {
+		let args: Vec<&str> = args
+			.split(|c: char| c == ',' || c == '/' || c.is_ascii_whitespace())
+			.filter(|arg| !arg.is_empty())
+			.collect();
+		let alpha = match args.get(3) {
+			Some(alpha) => channel(alpha, 1.0)?,
+			None if args.len() == 3 => 1.0,
+			None => return None,
+		};
+		return match function.trim().to_ascii_lowercase().as_str() {
+			"rgb" | "rgba" => {
+				let [r, g, b] = [0, 1, 2].map(|i| channel(args[i], 255.0));
+				Some(Color::new(r?, g?, b?, alpha))
+			},
+			"hsl" | "hsla" => {
+				let hue = parse_number(args[0].trim_end_matches("deg"))?;
+				let saturation = channel(args[1], f32::NAN)?;
+				let lightness = channel(args[2], f32::NAN)?;
+				let [r, g, b] = hsl_to_rgb(hue, saturation, lightness);
+				Some(Color::new(r, g, b, alpha))
+			},
+			_ => None,
+		};
+	}
+
+	let name = text.to_ascii_lowercase();
+	if name == "transparent" {
+		return Some(Color::TRANSPARENT);
+	}
+	let index = NAMED_COLORS.binary_search_by(|(named, _)| named.cmp(&name.as_str())).ok()?;
+	let [r, g, b] = NAMED_COLORS[index].1;
+	Some(Color::from_rgba8([r, g, b, 255]))
+}
+
+/// A colour channel as a number out of `max`, or a percentage, clamped to `[0, 1]`. A `max` of NaN only allows
+/// percentages.
+fn channel(text: &str, max: f32) -> Option<f32> {
+	let value = match text.strip_suffix('%') {
+		Some(percent) => parse_number(percent)? / 100.0,
+		None if max.is_nan() => return None,
+		None => parse_number(text)? / max,
+	};
+	Some(value.clamp(0.0, 1.0))
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+	if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+		return None;
+	}
+	let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+	let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+	let rgba = match hex.len() {
+		3 => [digit(0) * 17, digit(1) * 17, digit(2) * 17, 255],
+		4 => [digit(0) * 17, digit(1) * 17, digit(2) * 17, digit(3) * 17],
+		6 => [byte(0), byte(2), byte(4), 255],
+		8 => [byte(0), byte(2), byte(4), byte(6)],
+		_ => return None,
+	};
+	Some(Color::from_rgba8(rgba))
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> [f32; 3] {
+	let hue = (hue / 360.0).rem_euclid(1.0) * 6.0;
+	let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+	let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+	let [r, g, b] = match hue as u32 {
+		0 => [chroma, x, 0.0],
+		1 => [x, chroma, 0.0],
+		2 => [0.0, chroma, x],
+		3 => [0.0, x, chroma],
+		4 => [x, 0.0, chroma],
+		_ => [chroma, 0.0, x],
+	};
+	let m = lightness - chroma / 2.0;
+	[r + m, g + m, b + m]
+}
+
+/// The CSS colour keywords, sorted by name.
+const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+	("aliceblue", [240, 248, 255]),
+	("antiquewhite", [250, 235, 215]),
+	("aqua", [0, 255, 255]),
+	("aquamarine", [127, 255, 212]),
+	("azure", [240, 255, 255]),
+	("beige", [245, 245, 220]),
+	("bisque", [255, 228, 196]),
+	("black", [0, 0, 0]),
+	("blanchedalmond", [255, 235, 205]),
+	("blue", [0, 0, 255]),
+	("blueviolet", [138, 43, 226]),
+	("brown", [165, 42, 42]),
+	("burlywood", [222, 184, 135]),
+	("cadetblue", [95, 158, 160]),
+	("chartreuse", [127, 255, 0]),
+	("chocolate", [210, 105, 30]),
+	("coral", [255, 127, 80]),
+	("cornflowerblue", [100, 149, 237]),
+	("cornsilk", [255, 248, 220]),
+	("crimson", [220, 20, 60]),
+	("cyan", [0, 255, 255]),
+	("darkblue", [0, 0, 139]),
+	("darkcyan", [0, 139, 139]),
+	("darkgoldenrod", [184, 134, 11]),
+	("darkgray", [169, 169, 169]),
+	("darkgreen", [0, 100, 0]),
+	("darkgrey", [169, 169, 169]),
+	("darkkhaki", [189, 183, 107]),
+	("darkmagenta", [139, 0, 139]),
+	("darkolivegreen", [85, 107, 47]),
+	("darkorange", [255, 140, 0]),
+	("darkorchid", [153, 50, 204]),
+	("darkred", [139, 0, 0]),
+	("darksalmon", [233, 150, 122]),
+	("darkseagreen", [143, 188, 143]),
+	("darkslateblue", [72, 61, 139]),
+	("darkslategray", [47, 79, 79]),
+	("darkslategrey", [47, 79, 79]),
+	("darkturquoise", [0, 206, 209]),
+	("darkviolet", [148, 0, 211]),
+	("deeppink", [255, 20, 147]),
+	("deepskyblue", [0, 191, 255]),
+	("dimgray", [105, 105, 105]),
+	("dimgrey", [105, 105, 105]),
+	("dodgerblue", [30, 144, 255]),
+	("firebrick", [178, 34, 34]),
+	("floralwhite", [255, 250, 240]),
+	("forestgreen", [34, 139, 34]),
+	("fuchsia", [255, 0, 255]),
+	("gainsboro", [220, 220, 220]),
+	("ghostwhite", [248, 248, 255]),
+	("gold", [255, 215, 0]),
+	("goldenrod", [218, 165, 32]),
+	("gray", [128, 128, 128]),
+	("green", [0, 128, 0]),
+	("greenyellow", [173, 255, 47]),
+	("grey", [128, 128, 128]),
+	("honeydew", [240, 255, 240]),
+	("hotpink", [255, 105, 180]),
+	("indianred", [205, 92, 92]),
+	("indigo", [75, 0, 130]),
+	("ivory", [255, 255, 240]),
+	("khaki", [240, 230, 140]),
+	("lavender", [230, 230, 250]),
+	("lavenderblush", [255, 240, 245]),
+	("lawngreen", [124, 252, 0]),
+	("lemonchiffon", [255, 250, 205]),
+	("lightblue", [173, 216, 230]),
+	("lightcoral", [240, 128, 128]),
+	("lightcyan", [224, 255, 255]),
+	("lightgoldenrodyellow", [250, 250, 210]),
+	("lightgray", [211, 211, 211]),
+	("lightgreen", [144, 238, 144]),
+	("lightgrey", [211, 211, 211]),
+	("lightpink", [255, 182, 193]),
+	("lightsalmon", [255, 160, 122]),
+	("lightseagreen", [32, 178, 170]),
+	("lightskyblue", [135, 206, 250]),
+	("lightslategray", [119, 136, 153]),
+	("lightslategrey", [119, 136, 153]),
+	("lightsteelblue", [176, 196, 222]),
+	("lightyellow", [255, 255, 224]),
+	("lime", [0, 255, 0]),
+	("limegreen", [50, 205, 50]),
+	("linen", [250, 240, 230]),
+	("magenta", [255, 0, 255]),
+	("maroon", [128, 0, 0]),
+	("mediumaquamarine", [102, 205, 170]),
+	("mediumblue", [0, 0, 205]),
+	("mediumorchid", [186, 85, 211]),
+	("mediumpurple", [147, 112, 219]),
+	("mediumseagreen", [60, 179, 113]),
+	("mediumslateblue", [123, 104, 238]),
+	("mediumspringgreen", [0, 250, 154]),
+	("mediumturquoise", [72, 209, 204]),
+	("mediumvioletred", [199, 21, 133]),
+	("midnightblue", [25, 25, 112]),
+	("mintcream", [245, 255, 250]),
+	("mistyrose", [255, 228, 225]),
+	("moccasin", [255, 228, 181]),
+	("navajowhite", [255, 222, 173]),
+	("navy", [0, 0, 128]),
+	("oldlace", [253, 245, 230]),
+	("olive", [128, 128, 0]),
+	("olivedrab", [107, 142, 35]),
+	("orange", [255, 165, 0]),
+	("orangered", [255, 69, 0]),
+	("orchid", [218, 112, 214]),
+	("palegoldenrod", [238, 232, 170]),
+	("palegreen", [152, 251, 152]),
+	("paleturquoise", [175, 238, 238]),
+	("palevioletred", [219, 112, 147]),
+	("papayawhip", [255, 239, 213]),
+	("peachpuff", [255, 218, 185]),
+	("peru", [205, 133, 63]),
+	("pink", [255, 192, 203]),
+	("plum", [221, 160, 221]),
+	("powderblue", [176, 224, 230]),
+	("purple", [128, 0, 128]),
+	("rebeccapurple", [102, 51, 153]),
+	("red", [255, 0, 0]),
+	("rosybrown", [188, 143, 143]),
+	("royalblue", [65, 105, 225]),
+	("saddlebrown", [139, 69, 19]),
+	("salmon", [250, 128, 114]),
+	("sandybrown", [244, 164, 96]),
+	("seagreen", [46, 139, 87]),
+	("seashell", [255, 245, 238]),
+	("sienna", [160, 82, 45]),
+	("silver", [192, 192, 192]),
+	("skyblue", [135, 206, 235]),
+	("slateblue", [106, 90, 205]),
+	("slategray", [112, 128, 144]),
+	("slategrey", [112, 128, 144]),
+	("snow", [255, 250, 250]),
+	("springgreen", [0, 255, 127]),
+	("steelblue", [70, 130, 180]),
+	("tan", [210, 180, 140]),
+	("teal", [0, 128, 128]),
+	("thistle", [216, 191, 216]),
+	("tomato", [255, 99, 71]),
+	("turquoise", [64, 224, 208]),
+	("violet", [238, 130, 238]),
+	("wheat", [245, 222, 179]),
+	("white", [255, 255, 255]),
+	("whitesmoke", [245, 245, 245]),
+	("yellow", [255, 255, 0]),
+	("yellowgreen", [154, 205, 50]),
+];
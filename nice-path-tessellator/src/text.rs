@@ -0,0 +1,188 @@
+use crate::{
+	geom::{add, length, scale, sub, Conic, LineSegment, QuadraticBezier, Segment},
+	offset::push_segments,
+	ArcLengthTable, Path, DEFAULT_OFFSET_TOLERANCE,
+};
+
+/// How many times a glyph segment may be halved while warping it.
+const MAX_WARP_DEPTH: u32 = 8;
+
+/// One shaped glyph. The outline is in the glyph's own space, with the pen at the origin on the baseline, x along the
+/// baseline and y down, so the outline of an upright glyph is mostly above the x axis.
+#[derive(Clone, Debug)]
+pub struct Glyph {
+	pub outline: Path,
+	/// How far the pen moves along the baseline after this glyph.
+	pub advance: f32,
+}
+
+/// Which part of the text is placed at [`TextOnPathOptions::offset`], like SVG's `text-anchor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+	Start,
+	Center,
+	End,
+}
+
+/// What happens to text that runs past either end of the path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextOverflow {
+	/// Glyphs that don't fit entirely on the path are left out.
+	Hide,
+	/// The path is extended along its end tangents, so the glyphs carry on in a straight line.
+	Extend,
+	/// The text is scaled down until it's no longer than the path, and anything that still overflows is left out.
+	Shrink,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextOnPathOptions {
+	pub align: TextAlign,
+	/// How far along the path the text is anchored.
+	pub offset: f32,
+	pub overflow: TextOverflow,
+	/// Whether glyph outlines are bent to follow the path's curvature. Otherwise every glyph is only rotated to the
+	/// tangent at its center, which keeps the glyph undistorted but can make glyphs overlap on tight curves.
+	pub warp: bool,
+	/// How far warped outlines may stray from the exact warp, in path units.
+	pub tolerance: f32,
+}
+impl Default for TextOnPathOptions {
+	fn default() -> Self {
+		Self {
+			align: TextAlign::Start,
+			offset: 0.0,
+			overflow: TextOverflow::Hide,
+			warp: false,
+			tolerance: DEFAULT_OFFSET_TOLERANCE,
+		}
+	}
+}
+
+impl Path {
+	/// Lays `glyphs` out along the path, with their baseline on it, and returns all of their outlines as one path. The
+	/// glyphs are placed by arc length, so they're evenly spaced however the path is parametrised. Glyphs that are only
+	/// rotated keep their conics, while [`TextOnPathOptions::warp`] fits quadratics to every bent segment.
+	pub fn text_on_path(&self, glyphs: &[Glyph], options: &TextOnPathOptions) -> Path {
+		let table = self.arc_length_table();
+		let mut out = Path::start();
+		if table.length() <= 0.0 {
+			return out;
+		}
+
+		let text_length: f32 = glyphs.iter().map(|glyph| glyph.advance).sum();
+		let size = match options.overflow {
+			TextOverflow::Shrink if text_length > table.length() => table.length() / text_length,
+			_ => 1.0,
+		};
+		let mut pen = options.offset
+			- match options.align {
+				TextAlign::Start => 0.0,
+				TextAlign::Center => text_length * size / 2.0,
+				TextAlign::End => text_length * size,
+			};
+
+		let extend = options.overflow == TextOverflow::Extend;
+		for glyph in glyphs {
+			let advance = glyph.advance * size;
+			let fits = pen >= 0.0 && pen + advance <= table.length() * (1.0 + 1e-6);
+			if fits || extend {
+				out = if options.warp {
+					place_warped(out, &table, glyph, pen, size, options.tolerance)
+				} else {
+					place_rigid(out, &table, glyph, pen, size)
+				};
+			}
+			pen += advance;
+		}
+		out
+	}
+}
+
+/// The point and unit tangent `distance` along the path, continuing in a straight line past the ends.
+fn frame(table: &ArcLengthTable, distance: f32) -> ([f32; 2], [f32; 2]) {
+	let clamped = distance.clamp(0.0, table.length());
+	let point = table.point_at_length(clamped).unwrap_or([0.0; 2]);
+	let tangent = table.tangent_at_length(clamped).unwrap_or([1.0, 0.0]);
+	(add(point, scale(tangent, distance - clamped)), tangent)
+}
+
+/// Maps a point in glyph space onto the frame: x along the tangent, and y to its right, which is down for a path
+/// going left to right.
+fn to_frame(origin: [f32; 2], tangent: [f32; 2], p: [f32; 2]) -> [f32; 2] {
+	let down = [-tangent[1], tangent[0]];
+	add(origin, add(scale(tangent, p[0]), scale(down, p[1])))
+}
+
+fn place_rigid(out: Path, table: &ArcLengthTable, glyph: &Glyph, pen: f32, size: f32) -> Path {
+	let half = glyph.advance * size / 2.0;
+	let (point, tangent) = frame(table, pen + half);
+	let origin = sub(point, scale(tangent, half));
+	let map = |p: [f32; 2]| to_frame(origin, tangent, scale(p, size));
+
+	let mut out = out;
+	for contour in glyph.outline.contours() {
+		let segs: Vec<Segment> = contour
+			.closed_segments()
+			.map(|seg| match seg {
+				Segment::Line(line) => Segment::Line(LineSegment::new(map(line.from), map(line.to))),
+				Segment::Quadratic(quad) => {
+					Segment::Quadratic(QuadraticBezier::new(map(quad.from), map(quad.ctrl), map(quad.to)))
+				},
+				// conics are mapped exactly by anything affine, like rotating and scaling
+				Segment::Conic(conic) => {
+					Segment::Conic(Conic::new(map(conic.from), map(conic.ctrl), map(conic.to), conic.weight))
+				},
+			})
+			.collect();
+		out = push_segments(out.move_to(segs[0].from()), &segs);
+	}
+	out
+}
+
+fn place_warped(out: Path, table: &ArcLengthTable, glyph: &Glyph, pen: f32, size: f32, tolerance: f32) -> Path {
+	let map = |p: [f32; 2]| {
+		let (point, tangent) = frame(table, pen + p[0] * size);
+		to_frame(point, tangent, [0.0, p[1] * size])
+	};
+
+	let mut out = out;
+	for contour in glyph.outline.contours() {
+		let mut segs = vec![];
+		for seg in contour.closed_segments() {
+			warp_segment(&seg, &map, tolerance, 0, &mut segs);
+		}
+		out = push_segments(out.move_to(segs[0].from()), &segs);
+	}
+	out
+}
+
+/// Fits a quadratic through the warped endpoints and midpoint of `seg`, halving `seg` until that's within `tolerance`
+/// of the exact warp.
+fn warp_segment(
+	seg: &Segment,
+	map: &impl Fn([f32; 2]) -> [f32; 2],
+	tolerance: f32,
+	depth: u32,
+	out: &mut Vec<Segment>,
+) {
+	let from = map(seg.from());
+	let to = map(seg.to());
+	let mid = map(seg.eval(0.5));
+	let chord_mid = scale(add(from, to), 0.5);
+	if matches!(seg, Segment::Line(_)) && length(sub(mid, chord_mid)) <= tolerance {
+		out.push(Segment::Line(LineSegment::new(from, to)));
+		return;
+	}
+
+	// the quadratic that passes through `mid` at t = 0.5
+	let fitted = QuadraticBezier::new(from, sub(scale(mid, 2.0), chord_mid), to);
+	let fits = [0.25, 0.75].iter().all(|&t| length(sub(fitted.eval(t), map(seg.eval(t)))) <= tolerance);
+	if fits || depth >= MAX_WARP_DEPTH {
+		out.push(Segment::Quadratic(fitted));
+	} else {
+		let (a, b) = seg.split(0.5);
+		warp_segment(&a, map, tolerance, depth + 1, out);
+		warp_segment(&b, map, tolerance, depth + 1, out);
+	}
+}
@@ -0,0 +1,100 @@
+use crate::{Path, PathCommands};
+
+/// An affine transform `[a, b, c, d, e, f]`, in the same order as SVG's `matrix(a b c d e f)`. It maps `(x, y)` to
+/// `(a x + c y + e, b x + d y + f)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform(pub [f32; 6]);
+impl Transform {
+	pub const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+	pub fn translate(x: f32, y: f32) -> Self {
+		Transform([1.0, 0.0, 0.0, 1.0, x, y])
+	}
+
+	pub fn scale(x: f32, y: f32) -> Self {
+		Transform([x, 0.0, 0.0, y, 0.0, 0.0])
+	}
+
+	/// Rotates by `angle` radians, clockwise on screen since y points down.
+	pub fn rotate(angle: f32) -> Self {
+		let (sin, cos) = angle.sin_cos();
+		Transform([cos, sin, -sin, cos, 0.0, 0.0])
+	}
+
+	pub fn skew_x(angle: f32) -> Self {
+		Transform([1.0, 0.0, angle.tan(), 1.0, 0.0, 0.0])
+	}
+
+	pub fn skew_y(angle: f32) -> Self {
+		Transform([1.0, angle.tan(), 0.0, 1.0, 0.0, 0.0])
+	}
+
+	/// This transform followed by `next`.
+	pub fn then(&self, next: &Transform) -> Transform {
+		let [a, b, c, d, e, f] = self.0;
+		let [na, nb, nc, nd, ne, nf] = next.0;
+		Transform([
+			na * a + nc * b,
+			nb * a + nd * b,
+			na * c + nc * d,
+			nb * c + nd * d,
+			na * e + nc * f + ne,
+			nb * e + nd * f + nf,
+		])
+	}
+
+	pub fn apply(&self, p: [f32; 2]) -> [f32; 2] {
+		let [a, b, c, d, e, f] = self.0;
+		[a * p[0] + c * p[1] + e, b * p[0] + d * p[1] + f]
+	}
+
+	/// Applies the transform without its translation, as for a direction or a distance.
+	pub fn apply_vector(&self, v: [f32; 2]) -> [f32; 2] {
+		let [a, b, c, d, ..] = self.0;
+		[a * v[0] + c * v[1], b * v[0] + d * v[1]]
+	}
+
+	pub fn determinant(&self) -> f32 {
+		let [a, b, c, d, ..] = self.0;
+		a * d - b * c
+	}
+
+	/// The transform that undoes this one, or `None` if this one flattens everything onto a line or a point.
+	pub fn invert(&self) -> Option<Transform> {
+		let det = self.determinant();
+		if det == 0.0 || !det.is_finite() {
+			return None;
+		}
+		let [a, b, c, d, e, f] = self.0;
+		Some(Transform([d / det, -b / det, -c / det, a / det, (c * f - d * e) / det, (b * e - a * f) / det]))
+	}
+
+	pub fn is_identity(&self) -> bool {
+		*self == Transform::IDENTITY
+	}
+}
+impl Default for Transform {
+	fn default() -> Self {
+		Transform::IDENTITY
+	}
+}
+
+impl Path {
+	/// The path with every point mapped through `transform`. Affine transforms map quadratics to quadratics and conics to
+	/// conics with the same weight, so this is exact.
+	pub fn transform(&self, transform: &Transform) -> Path {
+		let map = |p| transform.apply(p);
+		let mut commands = Vec::with_capacity(self.len() + 1);
+		// the implicit start at the origin moves too
+		if !matches!(self.commands().first(), None | Some(PathCommands::MoveTo(_))) {
+			commands.push(PathCommands::MoveTo(map([0.0; 2])));
+		}
+		commands.extend(self.commands().iter().map(|&cmd| match cmd {
+			PathCommands::MoveTo(to) => PathCommands::MoveTo(map(to)),
+			PathCommands::LineTo(to) => PathCommands::LineTo(map(to)),
+			PathCommands::QuadraticCurveTo(ctrl, to) => PathCommands::QuadraticCurveTo(map(ctrl), map(to)),
+			PathCommands::ConicTo(ctrl, to, weight) => PathCommands::ConicTo(map(ctrl), map(to), weight),
+		}));
+		Path::from_commands(commands)
+	}
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::assert_close;
+use nice_path_tessellator::Path;
+
+#[test]
+fn walk_along_lines() {
+	let path = Path::start().move_to([0.0, 0.0]).line_to([10.0, 0.0]).line_to([10.0, 10.0]);
+	assert_eq!(path.point_at_length(5.0), Some([5.0, 0.0]));
+	assert_eq!(path.point_at_length(15.0), Some([10.0, 5.0]));
+	assert_eq!(path.tangent_at_length(15.0), Some([0.0, 1.0]));
+	// out of range distances are clamped
+	assert_eq!(path.point_at_length(-1.0), Some([0.0, 0.0]));
+	assert_eq!(path.point_at_length(100.0), Some([10.0, 10.0]));
+	assert_eq!(Path::start().point_at_length(0.0), None);
+}
+
+#[test]
+fn quadratics_are_walked_by_arc_length() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let table = path.arc_length_table();
+	let total = table.length();
+	assert_close(total, path.length(), 1e-3);
+
+	// the curve is symmetric, so half way along is the apex
+	let apex = table.point_at_length(total / 2.0).unwrap();
+	assert_close(apex[0], 50.0, 1e-3);
+	assert_close(apex[1], 50.0, 1e-3);
+	let tangent = table.tangent_at_length(total / 2.0).unwrap();
+	assert_close(tangent[0], 1.0, 1e-3);
+	assert_close(tangent[1], 0.0, 1e-3);
+
+	// a quarter of the way along isn't a quarter of the way through the parameter range, because the curve is faster
+	// near its ends
+	let (head, tail) = path.split_at_length(total / 4.0);
+	assert_close(head.length(), total / 4.0, 1e-3);
+	assert_close(tail.length(), total * 3.0 / 4.0, 1e-3);
+	assert_eq!(head.point_at_length(total), tail.point_at_length(0.0));
+}
+
+#[test]
+fn split_keeps_other_subpaths() {
+	let path = Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([10.0, 0.0])
+		.move_to([0.0, 10.0])
+		.line_to([10.0, 10.0])
+		.line_to([10.0, 20.0]);
+	let (head, tail) = path.split_at_length(15.0);
+	assert_close(head.length(), 15.0, 1e-3);
+	assert_close(tail.length(), 15.0, 1e-3);
+	assert_eq!(head.point_at_length(10.0), Some([10.0, 0.0]));
+	assert_eq!(head.point_at_length(15.0), Some([5.0, 10.0]));
+	assert_eq!(tail.point_at_length(0.0), Some([5.0, 10.0]));
+
+	// cutting at a vertex doesn't leave empty segments behind
+	let (head, tail) = path.split_at_length(20.0);
+	assert_eq!(head.arc_length_table().point_at_length(20.0), Some([10.0, 10.0]));
+	assert_eq!(tail.point_at_length(0.0), Some([10.0, 10.0]));
+	assert_close(tail.length(), 10.0, 1e-3);
+}
@@ -0,0 +1,74 @@
+use nice_path_tessellator::{BlendMode, Color};
+
+fn assert_rgba(a: [f32; 4], b: [f32; 4], name: &str) {
+	assert!(a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5), "{}: {:?} != {:?}", name, a, b);
+}
+
+#[test]
+fn porter_duff() {
+	let src = Color::new(1.0, 0.0, 0.0, 0.5).premultiplied();
+	let dst = Color::new(0.0, 0.0, 1.0, 0.75).premultiplied();
+	let cases = [
+		(BlendMode::Clear, [0.0, 0.0, 0.0, 0.0]),
+		(BlendMode::Src, src),
+		(BlendMode::Dst, dst),
+		(BlendMode::SrcOver, [0.5, 0.0, 0.375, 0.875]),
+		(BlendMode::DstOver, [0.125, 0.0, 0.75, 0.875]),
+		(BlendMode::SrcIn, [0.375, 0.0, 0.0, 0.375]),
+		(BlendMode::DstIn, [0.0, 0.0, 0.375, 0.375]),
+		(BlendMode::SrcOut, [0.125, 0.0, 0.0, 0.125]),
+		(BlendMode::DstOut, [0.0, 0.0, 0.375, 0.375]),
+		(BlendMode::SrcAtop, [0.375, 0.0, 0.375, 0.75]),
+		(BlendMode::DstAtop, [0.125, 0.0, 0.375, 0.5]),
+		(BlendMode::Xor, [0.125, 0.0, 0.375, 0.5]),
+		(BlendMode::Plus, [0.5, 0.0, 0.75, 1.0]),
+	];
+	for (mode, expected) in cases {
+		assert_rgba(mode.blend(src, dst), expected, &format!("{:?}", mode));
+	}
+}
+
+#[test]
+fn blend_modes() {
+	let (src, dst) = ([0.25, 0.5, 1.0], [0.5, 0.5, 0.0]);
+	let opaque = |rgb: [f32; 3]| [rgb[0], rgb[1], rgb[2], 1.0];
+	let cases = [
+		(BlendMode::Multiply, [0.125, 0.25, 0.0]),
+		(BlendMode::Screen, [0.625, 0.75, 1.0]),
+		(BlendMode::Overlay, [0.25, 0.5, 0.0]),
+		(BlendMode::Darken, [0.25, 0.5, 0.0]),
+		(BlendMode::Lighten, [0.5, 0.5, 1.0]),
+		(BlendMode::ColorDodge, [2.0 / 3.0, 1.0, 0.0]),
+		(BlendMode::ColorBurn, [0.0, 0.0, 0.0]),
+		(BlendMode::HardLight, [0.25, 0.5, 1.0]),
+		(BlendMode::Difference, [0.25, 0.0, 1.0]),
+		(BlendMode::Exclusion, [0.5, 0.5, 1.0]),
+	];
+	for (mode, expected) in cases {
+		assert_rgba(mode.blend(opaque(src), opaque(dst)), opaque(expected), &format!("{:?}", mode));
+	}
+
+	// the non-separable modes keep the luminosity they take
+	let luminosity = |c: [f32; 4]| 0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2];
+	for (mode, from) in
+		[(BlendMode::Hue, dst), (BlendMode::Saturation, dst), (BlendMode::Color, dst), (BlendMode::Luminosity, src)]
+	{
+		let blended = mode.blend(opaque(src), opaque(dst));
+		assert!((luminosity(blended) - luminosity(opaque(from))).abs() < 1e-5, "{:?}: {:?}", mode, blended);
+	}
+	// greys have no hue or saturation to give
+	let grey = opaque([0.25; 3]);
+	assert_rgba(BlendMode::Color.blend(grey, opaque(dst)), opaque([0.445; 3]), "Color of grey");
+
+	// where either is transparent, they're composited like SrcOver
+	let src = Color::new(0.2, 0.4, 0.6, 1.0).premultiplied();
+	for mode in &BlendMode::ALL[13..] {
+		assert_rgba(mode.blend(src, [0.0; 4]), src, &format!("{:?} onto nothing", mode));
+		assert_rgba(mode.blend([0.0; 4], src), src, &format!("{:?} of nothing", mode));
+	}
+
+	for mode in BlendMode::ALL {
+		assert_eq!(mode.css_name().and_then(BlendMode::from_css_name), mode.css_name().map(|_| mode));
+	}
+	assert_eq!(BlendMode::from_css_name("normal"), Some(BlendMode::SrcOver));
+}
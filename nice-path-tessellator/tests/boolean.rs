@@ -0,0 +1,111 @@
+mod common;
+
+use common::{assert_close, circle, rect};
+use nice_path_tessellator::{BooleanOp, Color, Draw, FillRule, Paint, Path, Transform};
+
+/// A pentagram, whose middle is only filled with the nonzero rule.
+fn star() -> Path {
+	let point = |i: usize| {
+		let angle = i as f32 * 4.0 * std::f32::consts::PI / 5.0 - std::f32::consts::FRAC_PI_2;
+		[50.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin()]
+	};
+	let mut path = Path::start().move_to(point(0));
+	for i in 1..5 {
+		path = path.line_to(point(i));
+	}
+	path
+}
+
+#[test]
+fn overlapping_rects() {
+	let a = rect([0.0, 0.0], 10.0);
+	let b = rect([5.0, 5.0], 10.0);
+	assert_close(a.union(&b, FillRule::NonZero).area(), 175.0, 1e-3);
+	assert_close(a.intersect(&b, FillRule::NonZero).area(), 25.0, 1e-3);
+	assert_close(a.difference(&b, FillRule::NonZero).area(), 75.0, 1e-3);
+	assert_close(a.xor(&b, FillRule::NonZero).area(), 150.0, 1e-3);
+}
+
+#[test]
+fn shared_edges() {
+	let a = rect([0.0, 0.0], 10.0);
+	let b = rect([10.0, 0.0], 10.0);
+	let union = a.union(&b, FillRule::NonZero);
+	assert_close(union.area(), 200.0, 1e-3);
+	// the shared edge is gone, leaving a single rectangle outline
+	assert_close(union.length(), 60.0, 1e-3);
+	assert_close(a.intersect(&b, FillRule::NonZero).area(), 0.0, 1e-3);
+}
+
+#[test]
+fn holes_are_separate_contours() {
+	let outer = rect([0.0, 0.0], 30.0);
+	let inner = rect([10.0, 10.0], 10.0);
+	let frame = outer.difference(&inner, FillRule::NonZero);
+	assert_close(frame.area(), 800.0, 1e-3);
+	assert!(!frame.contains([15.0, 15.0], FillRule::NonZero));
+	assert!(!frame.contains([15.0, 15.0], FillRule::EvenOdd));
+	assert!(frame.contains([5.0, 15.0], FillRule::NonZero));
+}
+
+#[test]
+fn curves() {
+	let a = circle([0.0, 0.0], 10.0);
+	let b = circle([8.0, 3.0], 7.0);
+	let (area_a, area_b) = (a.area(), b.area());
+	let union = a.union(&b, FillRule::NonZero).area();
+	let intersection = a.intersect(&b, FillRule::NonZero).area();
+	assert!(intersection > 0.0 && intersection < area_b);
+	assert_close(union + intersection, area_a + area_b, 1e-2);
+	assert_close(a.difference(&b, FillRule::NonZero).area(), area_a - intersection, 1e-2);
+	assert_close(a.xor(&b, FillRule::NonZero).area(), union - intersection, 1e-2);
+
+	// boundaries stay on the original curves, rather than on a flattened approximation
+	assert_close(a.intersect(&a, FillRule::NonZero).length(), a.length(), 1e-3);
+
+	let xor = a.xor(&b, FillRule::NonZero);
+	for y in -12..12 {
+		for x in -12..18 {
+			let p = [x as f32 + 0.37, y as f32 + 0.61];
+			let expected = a.contains(p, FillRule::NonZero) != b.contains(p, FillRule::NonZero);
+			assert_eq!(xor.contains(p, FillRule::NonZero), expected, "{:?}", p);
+		}
+	}
+}
+
+#[test]
+fn fill_rules_of_inputs() {
+	let star = star();
+	let empty = Path::start();
+	let even_odd = star.boolean(&empty, BooleanOp::Union, [FillRule::EvenOdd, FillRule::NonZero]);
+	let nonzero = star.boolean(&empty, BooleanOp::Union, [FillRule::NonZero, FillRule::NonZero]);
+	assert!(!even_odd.contains([50.0, 50.0], FillRule::NonZero));
+	assert!(nonzero.contains([50.0, 50.0], FillRule::NonZero));
+	assert!(nonzero.area() > even_odd.area());
+	// the star's tips are filled either way
+	assert!(even_odd.contains([50.0, 15.0], FillRule::NonZero));
+}
+
+#[test]
+fn outlines_only_resolve_overlaps() {
+	let to_view = Transform::scale(2.0, 2.0);
+	let outline = |path: Path| Draw::new(path, Paint::Solid(Color::BLACK)).outline(&to_view);
+
+	// a single contour that doesn't cross itself, even one that's made of curves and that touches itself where its
+	// ends meet, is only transformed
+	let circle = circle([50.0, 50.0], 40.0);
+	assert_eq!(outline(circle.clone()), circle.transform(&to_view));
+	let arch = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]).line_to([0.0, 0.0]);
+	assert_eq!(outline(arch.clone()), arch.transform(&to_view));
+
+	// crossing itself, touching itself or having several contours all mean it might fill differently with even-odd
+	let bowtie = Path::start().move_to([0.0, 0.0]).line_to([20.0, 0.0]).line_to([10.0, 10.0]).line_to([20.0, 20.0]);
+	let touching = bowtie.clone().line_to([0.0, 20.0]).line_to([10.0, 10.0]);
+	let nested =
+		rect([0.0, 0.0], 30.0).move_to([10.0, 10.0]).line_to([20.0, 10.0]).line_to([20.0, 20.0]).line_to([10.0, 20.0]);
+	for path in [star(), bowtie, touching, nested] {
+		let resolved = outline(path.clone());
+		assert_ne!(resolved, path.transform(&to_view));
+		assert_eq!(resolved, path.transform(&to_view).union(&Path::start(), FillRule::NonZero));
+	}
+}
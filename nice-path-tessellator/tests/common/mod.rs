@@ -0,0 +1,29 @@
+//! Shared by the test files, though not every one of them uses all of it.
+#![allow(dead_code)]
+
+use std::f32::consts::PI;
+
+use nice_path_tessellator::Path;
+
+pub fn assert_close(a: f32, b: f32, tolerance: f32) {
+	assert!((a - b).abs() < tolerance, "{} != {}", a, b);
+}
+
+pub fn rect(left_top: [f32; 2], size: f32) -> Path {
+	let [x, y] = left_top;
+	Path::start().move_to([x, y]).line_to([x + size, y]).line_to([x + size, y + size]).line_to([x, y + size])
+}
+
+/// A circle out of eight quadratics.
+pub fn circle(center: [f32; 2], radius: f32) -> Path {
+	let ctrl_radius = radius / (PI / 8.0).cos();
+	let point = |i: usize, r: f32| {
+		let angle = i as f32 * PI / 8.0;
+		[center[0] + r * angle.cos(), center[1] + r * angle.sin()]
+	};
+	let mut path = Path::start().move_to(point(0, radius));
+	for i in (1..16).step_by(2) {
+		path = path.quadratic_curve_to(point(i, ctrl_radius), point(i + 1, radius));
+	}
+	path
+}
@@ -0,0 +1,176 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use nice_path_tessellator::{fill_tessellate, Conic, FillRule, Path, PathCommands, Segment};
+
+/// A circle of radius 50 around (50, 50), as one conic per quarter.
+fn circle() -> Path {
+	Path::start()
+		.move_to([100.0, 50.0])
+		.conic_to([100.0, 100.0], [50.0, 100.0], FRAC_1_SQRT_2)
+		.conic_to([0.0, 100.0], [0.0, 50.0], FRAC_1_SQRT_2)
+		.conic_to([0.0, 0.0], [50.0, 0.0], FRAC_1_SQRT_2)
+		.conic_to([100.0, 0.0], [100.0, 50.0], FRAC_1_SQRT_2)
+}
+
+fn radius(p: [f32; 2]) -> f32 {
+	(p[0] - 50.0).hypot(p[1] - 50.0)
+}
+
+#[test]
+fn quarter_circles_are_exact() {
+	let conic = Conic::new([100.0, 50.0], [100.0, 100.0], [50.0, 100.0], FRAC_1_SQRT_2);
+	let (a, b) = conic.split(0.3);
+	for i in 0..=20 {
+		let t = i as f32 / 20.0;
+		for p in [conic.eval(t), a.eval(t), b.eval(t)] {
+			assert!((radius(p) - 50.0).abs() < 1e-3, "{:?}", p);
+		}
+	}
+
+	for quad in conic.to_quadratics(1e-3) {
+		for i in 0..=10 {
+			assert!((radius(quad.eval(i as f32 / 10.0)) - 50.0).abs() < 1.5e-3);
+		}
+	}
+}
+
+#[test]
+fn geometry_queries_see_the_circle() {
+	let circle = circle();
+	// the conics are measured themselves, so only rounding is left
+	assert!((circle.area() / (PI * 2500.0) - 1.0).abs() < 1e-5, "{}", circle.area());
+	assert!((circle.length() / (PI * 100.0) - 1.0).abs() < 1e-5, "{}", circle.length());
+	let bounds = circle.bounds().unwrap();
+	assert!((bounds.left_top[0]).abs() < 1e-4 && (bounds.right_bottom[1] - 100.0).abs() < 1e-4);
+	let centroid = circle.centroid().unwrap();
+	assert!((centroid[0] - 50.0).abs() < 1e-3 && (centroid[1] - 50.0).abs() < 1e-3, "{:?}", centroid);
+	assert!(circle.contains([50.0, 99.9], FillRule::NonZero) && !circle.contains([85.4, 85.4], FillRule::NonZero));
+
+	let mut reversed = circle.clone();
+	reversed.reverse();
+	assert_eq!(reversed.commands()[1], PathCommands::ConicTo([100.0, 0.0], [50.0, 0.0], FRAC_1_SQRT_2));
+}
+
+#[test]
+fn conic_segments() {
+	let conic = Conic::new([100.0, 50.0], [100.0, 100.0], [50.0, 100.0], FRAC_1_SQRT_2);
+	let quarter = Segment::Conic(conic);
+	assert!((quarter.length() - 25.0 * PI).abs() < 1e-4, "{}", quarter.length());
+
+	// a slice is still on the circle, and is the same curve as that stretch of the original
+	let slice = conic.slice(0.2, 0.7);
+	assert_eq!(slice.from, conic.eval(0.2));
+	assert!((slice.to[0] - conic.eval(0.7)[0]).abs() < 1e-4 && (slice.to[1] - conic.eval(0.7)[1]).abs() < 1e-4);
+	for i in 0..=10 {
+		assert!((radius(slice.eval(i as f32 / 10.0)) - 50.0).abs() < 1e-3);
+	}
+
+	// the eighth of the circle halfway along
+	let eighth = quarter.length() / 2.0;
+	let t = quarter.t_at_length(eighth);
+	let mid = conic.eval(t);
+	assert!((mid[0] - mid[1]).abs() < 1e-3, "{:?}", mid);
+	let d = conic.derivative(t);
+	assert!((d[0] + d[1]).abs() < 1e-3 * d[0].abs(), "{:?}", d);
+	let nearest = conic.nearest_point([200.0, 200.0]);
+	assert!((nearest[0] - mid[0]).abs() < 1e-3 && (nearest[1] - mid[1]).abs() < 1e-3, "{:?}", nearest);
+
+	// a third of the circle, around its rightmost point, turns around in x there
+	let third = Conic::new([75.0, 50.0 - 25.0 * 3f32.sqrt()], [150.0, 50.0], [75.0, 50.0 + 25.0 * 3f32.sqrt()], 0.5);
+	let [x, y] = third.extrema();
+	assert!(y.is_none());
+	let rightmost = third.eval(x.unwrap());
+	assert!((rightmost[0] - 100.0).abs() < 1e-3 && (rightmost[1] - 50.0).abs() < 1e-3, "{:?}", rightmost);
+	assert!((third.bounding_box().right_bottom[0] - 100.0).abs() < 1e-3);
+
+	// a line through the center meets the circle where it should
+	let line = Segment::Line(nice_path_tessellator::LineSegment::new([50.0, 50.0], [150.0, 150.0]));
+	let hits = quarter.intersections(&line, 1e-4);
+	assert_eq!(hits.len(), 1);
+	assert!((radius(hits[0].point) - 50.0).abs() < 1e-3 && (hits[0].point[0] - hits[0].point[1]).abs() < 1e-3);
+}
+
+#[test]
+fn operations_keep_conics() {
+	let on_circle = |path: &Path| {
+		let mut conics = 0;
+		for (_, seg) in path.segments() {
+			if let Segment::Conic(conic) = seg.into() {
+				conics += 1;
+				for i in 0..=10 {
+					let p = conic.eval(i as f32 / 10.0);
+					assert!((radius(p) - 50.0).abs() < 1e-3, "{:?}", p);
+				}
+			}
+		}
+		conics
+	};
+
+	let (first, second) = circle().split_at_length(10.0);
+	assert_eq!((on_circle(&first), on_circle(&second)), (1, 4));
+	// one conic per dash, and another for each dash over the end of a quarter
+	assert_eq!(on_circle(&circle().dash(&[30.0, 5.0], 0.0)), 12);
+
+	// the square cuts each quarter in two, and only the outer halves are kept
+	let square =
+		Path::start().move_to([50.0, 50.0]).line_to([150.0, 50.0]).line_to([150.0, 150.0]).line_to([50.0, 150.0]);
+	let union = circle().union(&square, FillRule::NonZero);
+	assert_eq!(on_circle(&union), 3);
+	assert!((union.area() - (PI * 2500.0 * 0.75 + 10000.0)).abs() < 0.1, "{}", union.area());
+	assert_eq!(on_circle(&circle().simplify(0.1)), 4);
+}
+
+#[test]
+fn curve_triangles_carry_their_own_coordinates() {
+	let path = circle().move_to([200.0, 0.0]).quadratic_curve_to([250.0, 100.0], [300.0, 0.0]);
+	let bufs = fill_tessellate(&path);
+	assert_eq!(bufs.curve_coords.len(), bufs.verts.len());
+	assert_eq!(bufs.quadratic_idxs.len(), 5 * 3);
+
+	for tri in bufs.quadratic_idxs.chunks(3) {
+		// no vertex is shared, so every corner has its own role
+		let coords: Vec<[f32; 4]> = tri.iter().map(|&i| bufs.curve_coords[i as usize]).collect();
+		let weight = coords[0][3];
+		assert_eq!([coords[0][0], coords[0][1], coords[0][2]], [1.0, 0.0, 0.0]);
+		assert_eq!([coords[1][0], coords[1][1], coords[1][2]], [0.0, 1.0, 0.0]);
+		assert_eq!([coords[2][0], coords[2][1], coords[2][2]], [0.0, 0.0, 1.0]);
+		assert!(coords.iter().all(|c| c[3] == weight));
+	}
+	let mut sorted = bufs.quadratic_idxs.clone();
+	sorted.sort_unstable();
+	sorted.dedup();
+	assert_eq!(sorted.len(), bufs.quadratic_idxs.len());
+}
+
+#[test]
+fn implicit_matches_the_circle() {
+	let bufs = fill_tessellate(&circle());
+	let tri: Vec<[f32; 2]> = bufs.quadratic_idxs[..3].iter().map(|&i| bufs.verts[i as usize]).collect();
+	let weight = bufs.curve_coords[bufs.quadratic_idxs[0] as usize][3];
+
+	// what jitter.wgsl evaluates, from the interpolated barycentric coordinates
+	let implicit = |p: [f32; 2]| {
+		let area =
+			|a: [f32; 2], b: [f32; 2], c: [f32; 2]| (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+		let total = area(tri[0], tri[1], tri[2]);
+		let b0 = area(p, tri[1], tri[2]) / total;
+		let b1 = area(tri[0], p, tri[2]) / total;
+		let b2 = area(tri[0], tri[1], p) / total;
+		b1 * b1 - 4.0 * weight * weight * b0 * b2
+	};
+	for i in 1..20 {
+		let angle = i as f32 / 20.0 * PI / 2.0;
+		let on = [50.0 + 50.0 * angle.cos(), 50.0 + 50.0 * angle.sin()];
+		let inside = [50.0 + 49.5 * angle.cos(), 50.0 + 49.5 * angle.sin()];
+		let outside = [50.0 + 50.5 * angle.cos(), 50.0 + 50.5 * angle.sin()];
+		assert!(implicit(on).abs() < 1e-4, "{}", implicit(on));
+		assert!(implicit(inside) < 0.0);
+		assert!(implicit(outside) > 0.0);
+	}
+}
+
+#[test]
+#[should_panic(expected = "conic weights must be positive")]
+fn negative_weight() {
+	let _ = Path::start().move_to([0.0, 0.0]).conic_to([10.0, 10.0], [20.0, 0.0], -1.0);
+}
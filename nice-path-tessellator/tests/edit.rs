@@ -0,0 +1,98 @@
+use nice_path_tessellator::{Path, PathCommands, PathSegment};
+
+fn two_subpaths() -> Path {
+	Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([10.0, 0.0])
+		.quadratic_curve_to([10.0, 10.0], [0.0, 10.0])
+		.move_to([20.0, 0.0])
+		.line_to([30.0, 0.0])
+}
+
+#[test]
+fn segments_know_where_they_start() {
+	let path = two_subpaths();
+	assert_eq!(path.len(), 5);
+	assert_eq!(path.commands()[3], PathCommands::MoveTo([20.0, 0.0]));
+	let segments: Vec<_> = path.segments().collect();
+	assert_eq!(
+		segments,
+		vec![
+			(1, PathSegment::Line { from: [0.0, 0.0], to: [10.0, 0.0] }),
+			(2, PathSegment::Quadratic { from: [10.0, 0.0], ctrl: [10.0, 10.0], to: [0.0, 10.0] }),
+			(4, PathSegment::Line { from: [20.0, 0.0], to: [30.0, 0.0] }),
+		]
+	);
+	assert_eq!(path.segment(0), None);
+
+	// without a move, drawing starts at the origin
+	let path = Path::start().line_to([1.0, 1.0]);
+	assert_eq!(path.segment(0), Some(PathSegment::Line { from: [0.0, 0.0], to: [1.0, 1.0] }));
+}
+
+#[test]
+fn editing() {
+	let mut path = two_subpaths();
+	path.insert(2, PathCommands::LineTo([10.0, 5.0]));
+	assert_eq!(path.segment(3).unwrap().from(), [10.0, 5.0]);
+	assert_eq!(path.remove(2), PathCommands::LineTo([10.0, 5.0]));
+	assert_eq!(path, two_subpaths());
+
+	let old = path.replace(4, PathCommands::QuadraticCurveTo([25.0, 5.0], [30.0, 0.0]));
+	assert_eq!(old, PathCommands::LineTo([30.0, 0.0]));
+	path.push(PathCommands::LineTo([20.0, 0.0]));
+	assert_eq!(path.len(), 6);
+}
+
+#[test]
+fn subpaths() {
+	let path = two_subpaths();
+	assert_eq!(path.subpath_ranges(), vec![0..3, 3..5]);
+	let subpaths = path.subpaths();
+	assert_eq!(subpaths[1], Path::start().move_to([20.0, 0.0]).line_to([30.0, 0.0]));
+
+	let implicit = Path::start().line_to([1.0, 0.0]).move_to([5.0, 5.0]).line_to([6.0, 5.0]);
+	assert_eq!(implicit.subpaths()[0], Path::start().move_to([0.0, 0.0]).line_to([1.0, 0.0]));
+}
+
+#[test]
+fn reverse_subpath() {
+	let mut path = two_subpaths();
+	path.reverse_subpath(0);
+	let expected = Path::start()
+		.move_to([0.0, 10.0])
+		.quadratic_curve_to([10.0, 10.0], [10.0, 0.0])
+		.line_to([0.0, 0.0])
+		.move_to([20.0, 0.0])
+		.line_to([30.0, 0.0]);
+	assert_eq!(path, expected);
+	path.reverse_subpath(0);
+	assert_eq!(path, two_subpaths());
+}
+
+#[test]
+#[should_panic(expected = "conic weights must be positive")]
+fn from_commands_checks_weights() {
+	let _ = Path::from_commands(vec![
+		PathCommands::MoveTo([0.0, 0.0]),
+		PathCommands::ConicTo([5.0, 5.0], [10.0, 0.0], f32::NAN),
+	]);
+}
+
+#[test]
+#[should_panic(expected = "conic weights must be positive")]
+fn push_checks_weights() {
+	two_subpaths().push(PathCommands::ConicTo([5.0, 5.0], [10.0, 0.0], 0.0));
+}
+
+#[test]
+#[should_panic(expected = "conic weights must be positive")]
+fn insert_checks_weights() {
+	two_subpaths().insert(1, PathCommands::ConicTo([5.0, 5.0], [10.0, 0.0], -1.0));
+}
+
+#[test]
+#[should_panic(expected = "conic weights must be positive")]
+fn replace_checks_weights() {
+	two_subpaths().replace(1, PathCommands::ConicTo([5.0, 5.0], [10.0, 0.0], f32::INFINITY));
+}
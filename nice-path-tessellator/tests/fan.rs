@@ -0,0 +1,30 @@
+use nice_path_tessellator::{fill_tessellate, Path, VecPathVertBufs};
+
+fn signed_area(tri: [[f32; 2]; 3]) -> f32 {
+	let (a, b) = ([tri[1][0] - tri[0][0], tri[1][1] - tri[0][1]], [tri[2][0] - tri[0][0], tri[2][1] - tri[0][1]]);
+	(a[0] * b[1] - a[1] * b[0]) / 2.0
+}
+
+fn tris(bufs: &VecPathVertBufs, idxs: &[u32]) -> Vec<[[f32; 2]; 3]> {
+	idxs.chunks(3).map(|idxs| [idxs[0], idxs[1], idxs[2]].map(|i| bufs.verts[i as usize])).collect()
+}
+
+#[test]
+fn fan_winds_like_the_path() {
+	// a rounded square with a hole wound the other way, so their fans cancel out
+	let path = Path::start()
+		.move_to([50.0, 0.0])
+		.quadratic_curve_to([100.0, 0.0], [100.0, 50.0])
+		.quadratic_curve_to([100.0, 100.0], [50.0, 100.0])
+		.line_to([0.0, 100.0])
+		.line_to([0.0, 0.0])
+		.move_to([40.0, 20.0])
+		.line_to([20.0, 20.0])
+		.line_to([20.0, 40.0])
+		.quadratic_curve_to([40.0, 40.0], [40.0, 20.0]);
+	let bufs = fill_tessellate(&path);
+	// the fan covers the chords, and a quadratic's curve triangle adds 2/3 of its area between the chord and the curve
+	let fan: f32 = tris(&bufs, &bufs.fan_idxs).into_iter().map(signed_area).sum();
+	let curves: f32 = tris(&bufs, &bufs.quadratic_idxs).into_iter().map(|tri| signed_area(tri) * 2.0 / 3.0).sum();
+	assert!(((fan + curves) / path.area() - 1.0).abs() < 1e-4, "{} != {}", fan + curves, path.area());
+}
@@ -0,0 +1,70 @@
+use std::f32::consts::PI;
+
+use nice_path_tessellator::{fill_tessellate, FillRule, Path, PathCommands};
+
+/// The area lyon's triangles fill, which the fill pass counts the parity of.
+fn fill_area(path: &Path) -> f32 {
+	let bufs = fill_tessellate(path);
+	let area = bufs.fill_idxs.chunks(3).map(|idxs| {
+		let [a, b, c] = [0, 1, 2].map(|i| bufs.verts[idxs[i] as usize]);
+		((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.0
+	});
+	area.sum()
+}
+
+/// The path with its curves replaced by their chords, filled with the even-odd rule.
+fn chord_area(path: &Path) -> f32 {
+	let mut chords = Path::start();
+	for cmd in path.commands() {
+		chords = match *cmd {
+			PathCommands::MoveTo(to) => chords.move_to(to),
+			PathCommands::LineTo(to) | PathCommands::QuadraticCurveTo(_, to) | PathCommands::ConicTo(_, to, _) => {
+				chords.line_to(to)
+			},
+		};
+	}
+	chords.union(&Path::start(), FillRule::EvenOdd).area()
+}
+
+#[test]
+fn self_intersecting_paths_fill_between_their_chords() {
+	let mut star = Path::start();
+	for i in 0..5 {
+		let angle = i as f32 * 4.0 * PI / 5.0;
+		let point = [19.25 + 17.0 * angle.sin(), 18.5 - 17.0 * angle.cos()];
+		star = match i {
+			0 => star.move_to(point),
+			_ => star.line_to(point),
+		};
+	}
+	let curved = Path::from_svg_path_data("M2 3 Q30 -10 37 20 C20 40 10 10 2 33 Z").unwrap();
+	for path in [star, curved] {
+		let (filled, expected) = (fill_area(&path), chord_area(&path));
+		assert!((filled - expected).abs() < 1e-3 * expected, "{} != {}", filled, expected);
+	}
+}
+
+#[test]
+fn paths_can_have_more_vertices_than_16_bit_indices_reach() {
+	// a wavy line of 30,000 curves, with three vertices of its own for each curve triangle
+	let mut path = Path::start().move_to([0.0, 0.0]);
+	for i in 0..30_000 {
+		let x = i as f32;
+		path = path.quadratic_curve_to([x + 0.5, if i % 2 == 0 { 1.0 } else { -1.0 }], [x + 1.0, 0.0]);
+	}
+	path = path.line_to([30_000.0, 10.0]).line_to([0.0, 10.0]);
+	let bufs = fill_tessellate(&path);
+	assert!(bufs.verts.len() > 90_000);
+
+	// the last curve triangle is indexed past 65,535 and still finds its own corners
+	let last: Vec<[f32; 2]> = bufs.quadratic_idxs[bufs.quadratic_idxs.len() - 3..]
+		.iter()
+		.map(|&idx| {
+			assert!(idx > u16::MAX as u32);
+			bufs.verts[idx as usize]
+		})
+		.collect();
+	assert_eq!(last, [[29_999.0, 0.0], [29_999.5, -1.0], [30_000.0, 0.0]]);
+	let max_idx = bufs.fill_idxs.iter().chain(&bufs.fan_idxs).max().unwrap();
+	assert!((*max_idx as usize) < bufs.verts.len());
+}
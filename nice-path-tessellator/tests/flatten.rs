@@ -0,0 +1,72 @@
+use nice_path_tessellator::Path;
+
+fn max_error(path: &Path, polyline: &[[f32; 2]]) -> f32 {
+	// the polyline's points are on the curve, so the error is largest between them
+	let mut error: f32 = 0.0;
+	for pair in polyline.windows(2) {
+		for i in 1..8 {
+			let t = i as f32 / 8.0;
+			let p = [pair[0][0] + (pair[1][0] - pair[0][0]) * t, pair[0][1] + (pair[1][1] - pair[0][1]) * t];
+			let (mut lo, mut hi) = (0.0, 10.0);
+			for _ in 0..20 {
+				let mid = (lo + hi) / 2.0;
+				if path.hit_test_stroke(p, mid * 2.0) {
+					hi = mid;
+				} else {
+					lo = mid;
+				}
+			}
+			error = error.max(hi);
+		}
+	}
+	error
+}
+
+#[test]
+fn lines_are_kept() {
+	let path = Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([10.0, 0.0])
+		.line_to([10.0, 10.0])
+		.move_to([20.0, 0.0])
+		.line_to([30.0, 0.0]);
+	let polylines: Vec<_> = path.flatten(0.1).collect();
+	assert_eq!(polylines, vec![vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0]], vec![[20.0, 0.0], [30.0, 0.0]]]);
+}
+
+#[test]
+fn quadratics_are_within_tolerance() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let mut previous = 0;
+	for tolerance in [1.0, 0.1, 0.01] {
+		let polyline = path.flatten(tolerance).next().unwrap();
+		assert_eq!(polyline.first(), Some(&[0.0, 0.0]));
+		assert_eq!(polyline.last(), Some(&[100.0, 0.0]));
+		assert!(max_error(&path, &polyline) <= tolerance * 1.05, "{} > {}", max_error(&path, &polyline), tolerance);
+		// the error shrinks with the square of the point count, so a tenth of the tolerance takes about 3x the points
+		assert!(polyline.len() > previous);
+		assert!(previous == 0 || polyline.len() <= previous * 4);
+		previous = polyline.len();
+	}
+}
+
+#[test]
+fn sharp_quadratics() {
+	// nearly folded back on itself, so almost all of the curvature is at the tip
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([100.0, 1.0], [0.0, 2.0]);
+	let polyline = path.flatten(0.05).next().unwrap();
+	assert!(max_error(&path, &polyline) <= 0.05 * 1.05);
+
+	// a straight curve that doubles back still reaches its far end
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([20.0, 0.0], [10.0, 0.0]);
+	let polyline = path.flatten(0.05).next().unwrap();
+	let far = polyline.iter().map(|p| p[0]).fold(0.0, f32::max);
+	assert!((far - 40.0 / 3.0).abs() < 1e-3, "{}", far);
+}
+
+#[test]
+#[should_panic(expected = "tolerance must be positive")]
+fn zero_tolerance() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let _ = path.flatten(0.0);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use common::assert_close;
+use nice_path_tessellator::{Conic, LineSegment, Path, PathSegment, QuadraticBezier, Segment};
+
+fn arch() -> QuadraticBezier {
+	QuadraticBezier::new([0.0, 0.0], [50.0, 100.0], [100.0, 0.0])
+}
+
+#[test]
+fn eval_and_derivative() {
+	let quad = arch();
+	assert_eq!(quad.eval(0.0), [0.0, 0.0]);
+	assert_eq!(quad.eval(0.5), [50.0, 50.0]);
+	assert_eq!(quad.eval(1.0), [100.0, 0.0]);
+	assert_eq!(quad.derivative(0.0), [100.0, 200.0]);
+	assert_eq!(quad.derivative(0.5), [100.0, 0.0]);
+
+	let line = LineSegment::new([0.0, 0.0], [10.0, 20.0]);
+	assert_eq!(line.eval(0.25), [2.5, 5.0]);
+	assert_eq!(line.derivative(0.7), [10.0, 20.0]);
+}
+
+#[test]
+fn split() {
+	let quad = arch();
+	let (a, b) = quad.split(0.25);
+	assert_eq!(a.to, b.from);
+	assert_eq!(a.to, quad.eval(0.25));
+	for i in 0..=10 {
+		let t = i as f32 / 10.0;
+		let [x, y] = a.eval(t);
+		let [ex, ey] = quad.eval(t * 0.25);
+		assert_close(x, ex, 1e-4);
+		assert_close(y, ey, 1e-4);
+	}
+
+	let (a, b) = LineSegment::new([0.0, 0.0], [10.0, 0.0]).split(0.3);
+	assert_eq!((a.to, b.from, b.to), ([3.0, 0.0], [3.0, 0.0], [10.0, 0.0]));
+}
+
+#[test]
+fn extrema_and_bounding_box() {
+	let quad = arch();
+	assert_eq!(quad.extrema(), [None, Some(0.5)]);
+	let bounds = quad.bounding_box();
+	assert_eq!(bounds.left_top, [0.0, 0.0]);
+	assert_eq!(bounds.right_bottom, [100.0, 50.0]);
+
+	// a curve that overshoots its end in x
+	let hook = QuadraticBezier::new([0.0, 0.0], [20.0, 10.0], [10.0, 10.0]);
+	let [tx, ty] = hook.extrema();
+	assert!(ty.is_none());
+	let tx = tx.unwrap();
+	assert_close(hook.bounding_box().right_bottom[0], hook.eval(tx)[0], 1e-4);
+	assert!(hook.bounding_box().right_bottom[0] > 10.0);
+
+	assert_eq!(Segment::Line(LineSegment::new([5.0, 0.0], [0.0, 5.0])).extrema(), [None, None]);
+}
+
+#[test]
+fn nearest_point() {
+	let quad = arch();
+	assert_eq!(quad.nearest_point([50.0, 80.0]), [50.0, 50.0]);
+	assert_eq!(quad.nearest_point([-10.0, -10.0]), [0.0, 0.0]);
+	let p = quad.nearest_point([80.0, 80.0]);
+	// the offset from the curve is along its normal
+	let d = quad.derivative(quad.nearest_t([80.0, 80.0]));
+	let offset = [80.0 - p[0], 80.0 - p[1]];
+	let cos = (offset[0] * d[0] + offset[1] * d[1]) / (offset[0].hypot(offset[1]) * d[0].hypot(d[1]));
+	assert_close(cos, 0.0, 1e-4);
+
+	let line = LineSegment::new([0.0, 0.0], [10.0, 0.0]);
+	assert_eq!(line.nearest_point([4.0, 3.0]), [4.0, 0.0]);
+	assert_eq!(line.nearest_point([14.0, 3.0]), [10.0, 0.0]);
+}
+
+#[test]
+fn intersections() {
+	let quad = Segment::Quadratic(arch());
+	let line = Segment::Line(LineSegment::new([0.0, 25.0], [100.0, 25.0]));
+	let mut hits = quad.intersections(&line, 1e-4);
+	hits.sort_by(|a, b| a.t_a.total_cmp(&b.t_a));
+	assert_eq!(hits.len(), 2);
+	for hit in &hits {
+		assert_close(hit.point[1], 25.0, 1e-4);
+		assert_close(quad.eval(hit.t_a)[1], 25.0, 1e-4);
+		assert_close(line.eval(hit.t_b)[0], hit.point[0], 1e-4);
+	}
+	assert!(hits[0].point[0] < 50.0 && hits[1].point[0] > 50.0);
+
+	// two arches crossing each other
+	let flipped = Segment::Quadratic(QuadraticBezier::new([0.0, 50.0], [50.0, -50.0], [100.0, 50.0]));
+	let hits = quad.intersections(&flipped, 1e-3);
+	assert_eq!(hits.len(), 2);
+	for hit in &hits {
+		assert!((hit.point[1] - 25.0).abs() < 1e-2, "{:?}", hit);
+	}
+
+	// lines that only touch at an end
+	let a = Segment::Line(LineSegment::new([0.0, 0.0], [10.0, 0.0]));
+	let b = Segment::Line(LineSegment::new([10.0, 0.0], [10.0, 10.0]));
+	let hits = a.intersections(&b, 1e-4);
+	assert_eq!(hits.len(), 1);
+	assert_eq!((hits[0].t_a, hits[0].t_b, hits[0].point), (1.0, 0.0, [10.0, 0.0]));
+	let far = Segment::Line(LineSegment::new([20.0, 0.0], [30.0, 0.0]));
+	assert!(a.intersections(&far, 1e-4).is_empty());
+}
+
+#[test]
+fn path_segments_convert() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let (_, seg) = path.segments().next().unwrap();
+	assert_eq!(seg, PathSegment::Quadratic { from: [0.0, 0.0], ctrl: [50.0, 100.0], to: [100.0, 0.0] });
+	let converted: Segment = seg.into();
+	assert_eq!(converted, Segment::Quadratic(arch()));
+
+	let path = Path::start().move_to([100.0, 50.0]).conic_to([100.0, 100.0], [50.0, 100.0], 0.5);
+	let (_, seg) = path.segments().next().unwrap();
+	let converted: Segment = seg.into();
+	assert_eq!(converted, Segment::Conic(Conic::new([100.0, 50.0], [100.0, 100.0], [50.0, 100.0], 0.5)));
+}
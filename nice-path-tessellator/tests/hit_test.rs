@@ -0,0 +1,99 @@
+use nice_path_tessellator::{FillRule, Path, Segment};
+
+/// A square with a square hole, both winding the same way.
+fn frame() -> Path {
+	Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([30.0, 0.0])
+		.line_to([30.0, 30.0])
+		.line_to([0.0, 30.0])
+		.move_to([10.0, 10.0])
+		.line_to([20.0, 10.0])
+		.line_to([20.0, 20.0])
+		.line_to([10.0, 20.0])
+}
+
+#[test]
+fn fill_rules() {
+	let path = frame();
+	assert!(path.contains([5.0, 5.0], FillRule::EvenOdd));
+	assert!(!path.contains([15.0, 15.0], FillRule::EvenOdd));
+	assert!(path.contains([15.0, 15.0], FillRule::NonZero));
+	assert!(!path.contains([35.0, 15.0], FillRule::NonZero));
+	// a ray through vertices should still count each edge once
+	assert!(path.contains([5.0, 10.0], FillRule::EvenOdd));
+}
+
+#[test]
+fn quadratic_fill() {
+	// the lens between the chord and the curve, which bulges down to y = 50
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	assert!(path.contains([50.0, 49.0], FillRule::NonZero));
+	assert!(!path.contains([50.0, 51.0], FillRule::NonZero));
+	assert!(path.contains([10.0, 17.0], FillRule::NonZero));
+	assert!(!path.contains([10.0, 19.0], FillRule::NonZero));
+}
+
+#[test]
+fn stroke() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	assert!(path.hit_test_stroke([50.0, 51.0], 4.0));
+	assert!(!path.hit_test_stroke([50.0, 53.0], 4.0));
+	// the closing line isn't stroked
+	assert!(!path.hit_test_stroke([50.0, 0.0], 4.0));
+}
+
+#[test]
+fn index_matches_path() {
+	// a zigzag with a lot of segments
+	let mut path = Path::start().move_to([0.0, 0.0]);
+	for i in 0..2000 {
+		let x = i as f32 * 0.5;
+		path = path.quadratic_curve_to([x + 0.25, if i % 2 == 0 { 20.0 } else { -20.0 }], [x + 0.5, 0.0]);
+	}
+	path = path.line_to([1000.0, 100.0]).line_to([0.0, 100.0]);
+
+	let index = path.hit_test_index();
+	for y in (-15..110).step_by(9) {
+		for x in (-10..1010).step_by(37) {
+			let point = [x as f32 + 0.1, y as f32 + 0.1];
+			for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+				assert_eq!(index.contains(point, fill_rule), path.contains(point, fill_rule), "{:?}", point);
+			}
+			assert_eq!(index.hit_test_stroke(point, 3.0), path.hit_test_stroke(point, 3.0), "{:?}", point);
+		}
+	}
+}
+
+#[test]
+fn monotonic_pieces() {
+	// a closed circle made of conics, and a curve that turns around in both x and y, left open
+	let path = Path::from_svg_path_data("M0 10 A10 10 0 0 1 20 10 A10 10 0 0 1 0 10 Z M30 0 Q60 40 30 20").unwrap();
+	let pieces = path.monotonic_pieces();
+	for piece in &pieces {
+		let (from, ctrl, to) = match *piece {
+			Segment::Line(_) => continue,
+			Segment::Quadratic(quad) => (quad.from, quad.ctrl, quad.to),
+			Segment::Conic(conic) => (conic.from, conic.ctrl, conic.to),
+		};
+		for axis in 0..2 {
+			let (lo, hi) = (from[axis].min(to[axis]), from[axis].max(to[axis]));
+			assert!((lo..=hi).contains(&ctrl[axis]), "{:?}", piece);
+		}
+	}
+	// the circle stays made of conics
+	let circle: Vec<&Segment> = pieces.iter().filter(|piece| piece.from()[0] <= 20.0).collect();
+	assert!(circle.len() >= 4 && circle.iter().all(|piece| matches!(piece, Segment::Conic(_))), "{:?}", circle);
+
+	// the pieces join up into closed loops
+	let mut ends: Vec<[f32; 2]> = pieces.iter().map(|piece| piece.to()).collect();
+	for piece in &pieces {
+		let end = ends.iter().position(|&end| end == piece.from()).expect("pieces should join up");
+		ends.swap_remove(end);
+	}
+
+	// the second curve is split where it turns around in x and in y, then closed with a line
+	let second: Vec<&Segment> = pieces.iter().filter(|piece| piece.from()[0] >= 30.0).collect();
+	assert_eq!(second.len(), 4);
+	assert!(matches!(second[3], Segment::Line(_)));
+}
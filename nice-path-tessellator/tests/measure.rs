@@ -0,0 +1,42 @@
+mod common;
+
+use common::assert_close;
+use nice_path_tessellator::{Path, WindingDirection};
+
+#[test]
+fn square() {
+	let path = Path::start().move_to([0.0, 0.0]).line_to([10.0, 0.0]).line_to([10.0, 10.0]).line_to([0.0, 10.0]);
+	assert_close(path.area(), 100.0, 1e-3);
+	assert_eq!(path.centroid(), Some([5.0, 5.0]));
+	assert_eq!(path.winding_direction(), Some(WindingDirection::Clockwise));
+	// the closing line isn't part of the length
+	assert_close(path.length(), 30.0, 1e-3);
+}
+
+#[test]
+fn quadratic_bounds_are_tight() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let bounds = path.bounds().unwrap();
+	assert_eq!(bounds.left_top, [0.0, 0.0]);
+	assert_close(bounds.right_bottom[0], 100.0, 1e-3);
+	assert_close(bounds.right_bottom[1], 50.0, 1e-3);
+	assert_eq!(path.control_bounds().unwrap().right_bottom, [100.0, 100.0]);
+
+	// a parabolic segment encloses 2/3 of its control triangle with the chord
+	assert_close(path.area(), -2.0 / 3.0 * 5000.0, 1e-3);
+	assert_eq!(path.winding_direction(), Some(WindingDirection::CounterClockwise));
+	let centroid = path.centroid().unwrap();
+	assert_close(centroid[0], 50.0, 1e-3);
+	assert_close(centroid[1], 20.0, 1e-3);
+}
+
+#[test]
+fn quadratic_length() {
+	// y = x^2 from 0 to 1, whose length is sqrt(5)/2 + asinh(2)/4
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([0.5, 0.0], [1.0, 1.0]);
+	assert_close(path.length(), 1.4789429, 1e-3);
+
+	// a degenerate quadratic that doubles back over its start
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([10.0, 0.0], [0.0, 0.0]);
+	assert_close(path.length(), 10.0, 1e-3);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{assert_close, circle, rect};
+use nice_path_tessellator::{FillRule, LineJoin, Path};
+
+#[test]
+fn rect_joins() {
+	let path = rect([0.0, 0.0], 10.0);
+	assert_close(path.offset(2.0, LineJoin::Miter(4.0)).area(), 196.0, 1e-2);
+	assert_close(path.offset(2.0, LineJoin::Bevel).area(), 196.0 - 4.0 * 2.0, 1e-2);
+	assert_close(path.offset(2.0, LineJoin::Round).area(), 100.0 + 80.0 + 4.0 * std::f32::consts::PI, 0.1);
+	// a miter limit below sqrt(2) bevels square corners
+	assert_close(path.offset(2.0, LineJoin::Miter(1.2)).area(), 196.0 - 4.0 * 2.0, 1e-2);
+	assert_close(path.offset(-2.0, LineJoin::Miter(4.0)).area(), 36.0, 1e-2);
+	assert_close(path.offset(-6.0, LineJoin::Miter(4.0)).area(), 0.0, 1e-2);
+}
+
+#[test]
+fn winding_direction_doesnt_matter() {
+	let cw = rect([0.0, 0.0], 10.0);
+	let ccw = Path::start().move_to([0.0, 0.0]).line_to([0.0, 10.0]).line_to([10.0, 10.0]).line_to([10.0, 0.0]);
+	assert_close(cw.offset(1.0, LineJoin::Miter(4.0)).area(), ccw.offset(1.0, LineJoin::Miter(4.0)).area(), 1e-3);
+}
+
+#[test]
+fn holes_shrink_as_the_shape_grows() {
+	let frame = rect([0.0, 0.0], 30.0).difference(&rect([10.0, 10.0], 10.0), FillRule::NonZero);
+	let grown = frame.offset(2.0, LineJoin::Miter(4.0));
+	assert_close(grown.area(), 34.0 * 34.0 - 6.0 * 6.0, 1e-2);
+	// and disappear once they're grown over
+	let grown = frame.offset(6.0, LineJoin::Miter(4.0));
+	assert_close(grown.area(), 42.0 * 42.0, 1e-1);
+	assert!(grown.contains([15.0, 15.0], FillRule::EvenOdd));
+}
+
+#[test]
+fn circles_stay_round() {
+	let path = circle([0.0, 0.0], 10.0);
+	for (distance, radius) in [(3.0, 13.0), (-4.0, 6.0)] {
+		let offset = path.offset(distance, LineJoin::Round);
+		let expected = circle([0.0, 0.0], radius);
+		assert_close(offset.area(), expected.area(), 0.5);
+		for i in 0..32 {
+			let angle = i as f32 / 32.0 * std::f32::consts::TAU;
+			let [x, y] = [angle.cos(), angle.sin()];
+			assert!(offset.contains([x * (radius - 0.1), y * (radius - 0.1)], FillRule::NonZero));
+			assert!(!offset.contains([x * (radius + 0.1), y * (radius + 0.1)], FillRule::NonZero));
+		}
+	}
+}
+
+#[test]
+fn tight_curves_lose_their_cusps() {
+	// a lens whose ends are sharper than the inset distance
+	let lens = Path::start()
+		.move_to([0.0, 0.0])
+		.quadratic_curve_to([50.0, 20.0], [100.0, 0.0])
+		.quadratic_curve_to([50.0, -20.0], [0.0, 0.0]);
+	let inset = lens.offset(-3.0, LineJoin::Miter(4.0));
+	assert!(inset.area() > 0.0 && inset.area() < lens.area().abs());
+	assert!(inset.contains([50.0, 0.0], FillRule::NonZero));
+	assert!(!inset.contains([5.0, 0.0], FillRule::NonZero));
+	assert!(!inset.contains([50.0, 8.0], FillRule::NonZero));
+}
@@ -0,0 +1,43 @@
+use nice_path_tessellator::{FillRule, Path, WindingDirection};
+
+fn square(path: Path, left_top: [f32; 2], size: f32) -> Path {
+	let [x, y] = left_top;
+	path.move_to([x, y]).line_to([x + size, y]).line_to([x + size, y + size]).line_to([x, y + size])
+}
+
+#[test]
+fn reverse() {
+	let mut path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([10.0, 0.0], [10.0, 10.0]).line_to([0.0, 10.0]);
+	let area = path.area();
+	path.reverse();
+	assert_eq!(
+		path,
+		Path::start().move_to([0.0, 10.0]).line_to([10.0, 10.0]).quadratic_curve_to([10.0, 0.0], [0.0, 0.0])
+	);
+	assert_eq!(path.area(), -area);
+}
+
+#[test]
+fn normalize_nested_squares() {
+	// every square winds clockwise, so the nonzero rule fills the holes
+	let mut path = square(square(square(Path::start(), [0.0, 0.0], 30.0), [5.0, 5.0], 20.0), [10.0, 10.0], 10.0);
+	assert!(path.contains([7.0, 7.0], FillRule::NonZero));
+
+	path.normalize_orientation(WindingDirection::Clockwise);
+	let directions: Vec<_> = path.subpaths().iter().map(|subpath| subpath.winding_direction()).collect();
+	assert_eq!(
+		directions,
+		vec![
+			Some(WindingDirection::Clockwise),
+			Some(WindingDirection::CounterClockwise),
+			Some(WindingDirection::Clockwise)
+		]
+	);
+	for point in [[2.0, 2.0], [7.0, 7.0], [15.0, 15.0], [40.0, 40.0]] {
+		assert_eq!(path.contains(point, FillRule::NonZero), path.contains(point, FillRule::EvenOdd));
+	}
+
+	path.normalize_orientation(WindingDirection::CounterClockwise);
+	assert_eq!(path.winding_direction(), Some(WindingDirection::CounterClockwise));
+	assert_eq!(path.subpaths()[1].winding_direction(), Some(WindingDirection::Clockwise));
+}
@@ -0,0 +1,88 @@
+use nice_path_tessellator::{fill_tessellate, FillRule, Path, VecPathVertBufs};
+
+/// The "o" from DejaVu Sans ExtraLight, in font units with y flipped to point down. Its strokes are so thin that the
+/// control triangles of the inner contour's curves reach across into the outer contour's.
+const HAIRLINE_O: &str = "M358 -929Q258 -793 258 -561Q258 -323 357 -187Q456 -50 628 -50Q799 -50 898 -187Q998 -325 998 \
+	-561Q998 -790 898 -928Q799 -1066 628 -1066Q457 -1066 358 -929ZM627 -1147Q841 -1147 965 -987Q1090 -826 1090 \
+	-560Q1090 -285 965 -126Q841 34 627 34Q411 34 286 -125Q162 -284 162 -560Q162 -829 287 -988Q412 -1147 627 -1147Z";
+
+fn quadratic_tris(bufs: &VecPathVertBufs) -> Vec<[[f32; 2]; 3]> {
+	bufs.quadratic_idxs
+		.chunks(3)
+		.map(|idxs| [bufs.verts[idxs[0] as usize], bufs.verts[idxs[1] as usize], bufs.verts[idxs[2] as usize]])
+		.collect()
+}
+
+fn barycentric(tri: [[f32; 2]; 3], p: [f32; 2]) -> Option<[f64; 3]> {
+	let [a, b, c] = tri.map(|v| [v[0] as f64, v[1] as f64]);
+	let p = [p[0] as f64, p[1] as f64];
+	let det = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+	if det == 0.0 {
+		return None;
+	}
+	let l1 = ((p[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (p[1] - a[1])) / det;
+	let l2 = ((b[0] - a[0]) * (p[1] - a[1]) - (p[0] - a[0]) * (b[1] - a[1])) / det;
+	let coords = [1.0 - l1 - l2, l1, l2];
+	coords.iter().all(|&l| l >= 0.0).then_some(coords)
+}
+
+/// Whether `p` is covered the way the jitter pass counts it, i.e. the parity of the fill triangles over `p` plus the
+/// curve triangles whose implicit curve equation `p` is inside of, and how many curve triangles `p` is in.
+fn coverage(bufs: &VecPathVertBufs, p: [f32; 2]) -> (bool, usize) {
+	let (mut count, mut curve_tris) = (0, 0);
+	for idxs in bufs.fill_idxs.chunks(3) {
+		if barycentric([0, 1, 2].map(|i| bufs.verts[idxs[i] as usize]), p).is_some() {
+			count += 1;
+		}
+	}
+	for idxs in bufs.quadratic_idxs.chunks(3) {
+		if let Some([b0, b1, b2]) = barycentric([0, 1, 2].map(|i| bufs.verts[idxs[i] as usize]), p) {
+			curve_tris += 1;
+			let w = bufs.curve_coords[idxs[0] as usize][3] as f64;
+			if b1 * b1 - 4.0 * w * w * b0 * b2 <= 0.0 {
+				count += 1;
+			}
+		}
+	}
+	(count % 2 == 1, curve_tris)
+}
+
+/// The centers of a grid of `step`-sized cells over `left_top` to `right_bottom`.
+fn grid(left_top: [f32; 2], right_bottom: [f32; 2], step: f32) -> impl Iterator<Item = [f32; 2]> {
+	let [cols, rows] = [0, 1].map(|axis| ((right_bottom[axis] - left_top[axis]) / step) as usize);
+	(0..rows).flat_map(move |row| {
+		(0..cols).map(move |col| [left_top[0] + (col as f32 + 0.5) * step, left_top[1] + (row as f32 + 0.5) * step])
+	})
+}
+
+#[test]
+fn hairline_glyph_is_covered_exactly() {
+	let glyph = Path::from_svg_path_data(HAIRLINE_O).unwrap();
+	let bufs = fill_tessellate(&glyph);
+	assert!(quadratic_tris(&bufs).len() > 16, "overlapping curves should have been subdivided");
+
+	// away from the outline where rounding decides, on a grid that doesn't land exactly on any chord
+	let samples: Vec<_> =
+		grid([150.0, -1160.0], [1100.0, 50.0], 3.9).filter(|&p| !glyph.hit_test_stroke(p, 1.0)).collect();
+	assert!(samples.len() > 50_000);
+	let wrong: Vec<_> =
+		samples.iter().filter(|&&p| coverage(&bufs, p).0 != glyph.contains(p, FillRule::EvenOdd)).collect();
+	assert!(wrong.is_empty(), "{} of {} samples are covered wrongly, e.g. {:?}", wrong.len(), samples.len(), wrong[0]);
+
+	// at the top right of the bowl, the inner contour's control point pokes through the chord of the outer contour's
+	// curve, so their curve triangles overlapped by about a third of a square unit before subdivision
+	let doubled: Vec<_> =
+		grid([790.0, -1070.0], [805.0, -1060.0], 0.05).filter(|&p| coverage(&bufs, p).1 > 1).collect();
+	assert!(doubled.is_empty(), "{:?} is in more than one curve triangle", doubled[0]);
+}
+
+#[test]
+fn disjoint_curves_are_left_alone() {
+	let path = Path::start()
+		.move_to([100.0, 100.0])
+		.quadratic_curve_to([150.0, 120.0], [200.0, 100.0])
+		.line_to([200.0, 200.0])
+		.line_to([100.0, 200.0]);
+	let bufs = fill_tessellate(&path);
+	assert_eq!(quadratic_tris(&bufs).len(), 1);
+}
@@ -0,0 +1,53 @@
+use nice_path_tessellator::{Path, PathCommands, SimplifyOptions};
+
+#[test]
+fn removes_degenerate_segments() {
+	let path = Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([0.0, 0.0])
+		.line_to([10.0, 0.0])
+		.quadratic_curve_to([10.0, 0.0], [10.0, 0.0])
+		.line_to([10.0, 10.0])
+		.move_to([50.0, 50.0])
+		.line_to([50.0, 50.0]);
+	assert_eq!(path.simplify(1e-3), Path::start().move_to([0.0, 0.0]).line_to([10.0, 0.0]).line_to([10.0, 10.0]));
+}
+
+#[test]
+fn merges_collinear_lines() {
+	let path = Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([5.0, 0.0])
+		.line_to([10.0, 0.01])
+		.line_to([20.0, 0.0])
+		.line_to([20.0, 10.0]);
+	assert_eq!(path.simplify(0.1), Path::start().move_to([0.0, 0.0]).line_to([20.0, 0.0]).line_to([20.0, 10.0]));
+
+	// a line that doubles back isn't merged away
+	let spike = Path::start().move_to([0.0, 0.0]).line_to([10.0, 0.0]).line_to([5.0, 0.0]);
+	assert_eq!(spike.simplify(0.1).len(), 3);
+}
+
+#[test]
+fn demotes_flat_quadratics() {
+	let path = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([5.0, 0.1], [10.0, 0.0]);
+	assert_eq!(path.simplify(0.1).commands()[1], PathCommands::LineTo([10.0, 0.0]));
+	assert_eq!(path.simplify(0.01), path);
+}
+
+#[test]
+fn fits_quadratics_to_polylines() {
+	let curve = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let polyline = curve.flatten(0.01).next().unwrap();
+	let mut path = Path::start().move_to(polyline[0]);
+	for &p in &polyline[1..] {
+		path = path.line_to(p);
+	}
+	assert!(path.len() > 10);
+
+	let fitted = path.simplify_with(&SimplifyOptions { tolerance: 0.1, fit_curves: true });
+	assert!(fitted.len() <= 3, "{:?}", fitted);
+	assert!((fitted.length() - curve.length()).abs() < 0.1);
+	// without fitting, the polyline stays a polyline
+	assert!(path.simplify(0.1).commands().iter().all(|cmd| !matches!(cmd, PathCommands::QuadraticCurveTo(..))));
+}
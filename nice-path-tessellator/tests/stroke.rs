@@ -0,0 +1,65 @@
+mod common;
+
+use common::assert_close;
+use nice_path_tessellator::{Dash, FillRule, LineCap, LineJoin, Path, StrokeOptions};
+
+#[test]
+fn dash_lines() {
+	let line = Path::start().move_to([0.0, 0.0]).line_to([100.0, 0.0]);
+	assert_close(line.dash(&[10.0, 5.0], 0.0).length(), 70.0, 1e-3);
+	// starting 5 into the first dash shortens it, and shifts the rest along
+	assert_close(line.dash(&[10.0, 5.0], 5.0).length(), 5.0 + 6.0 * 10.0, 1e-3);
+	// odd patterns are repeated, so this is 10 on, 5 off, 5 on, 10 off, 5 on, 5 off, ...
+	assert_close(line.dash(&[10.0, 5.0, 5.0], 0.0).length(), 10.0 + 5.0 + 5.0 + 10.0 + 5.0 + 5.0 + 10.0 + 5.0, 1e-3);
+	// invalid patterns don't dash
+	assert_close(line.dash(&[], 0.0).length(), 100.0, 1e-3);
+	assert_close(line.dash(&[0.0, 0.0], 0.0).length(), 100.0, 1e-3);
+}
+
+#[test]
+fn dash_curves_at_exact_lengths() {
+	let curve = Path::start().move_to([0.0, 0.0]).quadratic_curve_to([50.0, 100.0], [100.0, 0.0]);
+	let total = curve.length();
+	let dashed = curve.dash(&[7.0, 3.0], 0.0);
+	let full_periods = (total / 10.0).floor();
+	let expected = full_periods * 7.0 + (total - full_periods * 10.0).min(7.0);
+	assert_close(dashed.length(), expected, 1e-2);
+}
+
+#[test]
+fn stroke_caps() {
+	let line = Path::start().move_to([0.0, 0.0]).line_to([100.0, 0.0]);
+	let stroke = |cap| line.stroke(&StrokeOptions { width: 10.0, cap, ..Default::default() }).area();
+	assert_close(stroke(LineCap::Butt), 1000.0, 1e-2);
+	assert_close(stroke(LineCap::Square), 1100.0, 1e-2);
+	assert_close(stroke(LineCap::Round), 1000.0 + 25.0 * std::f32::consts::PI, 0.5);
+}
+
+#[test]
+fn stroke_closed_subpaths() {
+	let square = Path::start()
+		.move_to([0.0, 0.0])
+		.line_to([10.0, 0.0])
+		.line_to([10.0, 10.0])
+		.line_to([0.0, 10.0])
+		.line_to([0.0, 0.0]);
+	let stroke = square.stroke(&StrokeOptions { width: 2.0, join: LineJoin::Miter(4.0), ..Default::default() });
+	assert_close(stroke.area(), 144.0 - 64.0, 1e-2);
+	assert!(!stroke.contains([5.0, 5.0], FillRule::EvenOdd));
+	assert!(stroke.contains([0.5, 5.0], FillRule::EvenOdd));
+}
+
+#[test]
+fn dotted() {
+	let line = Path::start().move_to([0.0, 0.0]).line_to([100.0, 0.0]);
+	let dots = line.stroke(&StrokeOptions {
+		width: 4.0,
+		cap: LineCap::Round,
+		dash: Some(Dash { pattern: vec![0.0, 10.0], offset: 0.0 }),
+		..Default::default()
+	});
+	// a dot every 10 units, including both ends
+	assert_close(dots.area(), 11.0 * 4.0 * std::f32::consts::PI, 0.5);
+	assert!(dots.contains([50.0, 1.5], FillRule::NonZero));
+	assert!(!dots.contains([55.0, 0.0], FillRule::NonZero));
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use std::f32::consts::PI;
+
+use common::assert_close;
+use nice_path_tessellator::{Color, FillRule, Paint, Path, PathCommands, Scene, SvgError, Transform};
+
+fn assert_color(a: Color, b: Color) {
+	let (a, b) = (a.to_rgba8(), b.to_rgba8());
+	assert!(a.iter().zip(&b).all(|(a, b)| a.abs_diff(*b) <= 1), "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn path_data() {
+	let square = Path::from_svg_path_data("M10 10 h 20 v20 H10 z").unwrap();
+	assert_close(square.area(), 400.0, 1e-3);
+	assert_close(square.length(), 80.0, 1e-3);
+
+	// numbers can run into each other, and repeated arguments repeat the command
+	let compact = Path::from_svg_path_data("m0,0l10-5.5.5e1 10").unwrap();
+	assert_eq!(compact.pen_before(compact.len()), [15.0, 4.5]);
+
+	let err = Path::from_svg_path_data("M0 0 L10 10 L20 x").unwrap_err();
+	assert_eq!(err.position, 16);
+	assert!(Path::from_svg_path_data("L10 10").is_err());
+
+	// after a close path, drawing starts from where the subpath started
+	let two = Path::from_svg_path_data("M0 0 h10 v10 z l-10 0 v-10").unwrap();
+	assert_eq!(two.subpath_ranges().len(), 2);
+	assert_eq!(two.subpaths()[1].commands()[0], nice_path_tessellator::PathCommands::MoveTo([0.0, 0.0]));
+}
+
+#[test]
+fn arcs_are_exact() {
+	let circle = Path::from_svg_path_data("M0 50 A50 50 0 0 1 100 50 A50 50 0 0 1 0 50 Z").unwrap();
+	assert_close(circle.area() / (PI * 2500.0), 1.0, 1e-3);
+	let bounds = circle.bounds().unwrap();
+	assert_close(bounds.left_top[1], 0.0, 1e-3);
+	assert_close(bounds.right_bottom[1], 100.0, 1e-3);
+
+	// radii that are too small are scaled up, and a rotated ellipse keeps its area
+	let scaled = Path::from_svg_path_data("M0 0 A1 1 0 0 0 100 0 Z").unwrap();
+	assert_close(scaled.area().abs(), PI * 2500.0 / 2.0, 2.0);
+	let rotated = Path::from_svg_path_data("M0 0 a40 20 30 1 1 0.01 0 Z").unwrap();
+	assert_close(rotated.area().abs() / (PI * 800.0), 1.0, 1e-2);
+
+	// an arc too big for f32 is drawn as a line rather than as a conic with a NaN weight
+	let huge = Path::from_svg_path_data("M-3e38 0 A1 1 0 0 1 3e38 0").unwrap();
+	assert_eq!(huge.commands()[1], PathCommands::LineTo([3e38, 0.0]));
+}
+
+#[test]
+fn cubics_within_tolerance() {
+	let tolerance = 0.01;
+	let path =
+		Path::from_svg_path_data_with_tolerance("M0 0 C 0 100 100 100 100 0 S 200 -100 200 0", tolerance).unwrap();
+	let cubic = |p: [[f32; 2]; 4], t: f32| {
+		let mt = 1.0 - t;
+		let w = [mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t];
+		[0, 1].map(|axis| (0..4).map(|i| w[i] * p[i][axis]).sum::<f32>())
+	};
+	let stroke = path.stroke(&Default::default());
+	for curve in [
+		[[0.0, 0.0], [0.0, 100.0], [100.0, 100.0], [100.0, 0.0]],
+		[[100.0, 0.0], [100.0, -100.0], [200.0, -100.0], [200.0, 0.0]],
+	] {
+		for i in 1..50 {
+			// every point of the exact cubic is on the approximation, so inside its unit wide stroke, except the ends on its
+			// butt caps
+			let p = cubic(curve, i as f32 / 50.0);
+			assert!(stroke.contains(p, FillRule::NonZero), "{} {:?}", i, p);
+		}
+	}
+}
+
+#[test]
+fn shapes_and_viewbox() {
+	let import = Scene::from_svg(
+		r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" viewBox="0 0 100 50">
+			<rect x="10" y="10" width="20" height="10" fill="red"/>
+			<rect x="0" y="0" width="20" height="20" rx="5" fill="#00ff00"/>
+			<circle cx="50" cy="25" r="10" style="fill: rgb(0, 0, 255); fill-opacity: 50%"/>
+			<ellipse cx="50" cy="25" rx="10" ry="5" fill="none"/>
+			<polygon points="0,0 10,0 10,10" fill-rule="evenodd"/>
+		</svg>"##,
+	)
+	.unwrap();
+	assert!(import.unsupported.is_empty(), "{:?}", import.unsupported);
+	let scene = import.scene;
+	// the height follows the viewBox's aspect ratio, and the viewBox scales everything by 2
+	assert_eq!(scene.size, [200.0, 100.0]);
+	assert_eq!(scene.draws.len(), 4);
+	assert_eq!(scene.draws[0].transform, Transform::scale(2.0, 2.0));
+	assert_close(scene.draws[0].path.area(), 200.0, 1e-3);
+	assert_eq!(scene.draws[0].paint, Paint::Solid(Color::new(1.0, 0.0, 0.0, 1.0)));
+	assert_close(scene.draws[1].path.area(), 400.0 - (4.0 - PI) * 25.0, 0.05);
+	assert_close(scene.draws[2].path.area() / (PI * 100.0), 1.0, 1e-3);
+	assert_eq!(scene.draws[2].opacity, 0.5);
+	assert_eq!(scene.draws[3].fill_rule, FillRule::EvenOdd);
+	assert_eq!(scene.draws[3].paint, Paint::Solid(Color::BLACK));
+}
+
+#[test]
+fn preserve_aspect_ratio() {
+	let import = |preserve_aspect_ratio: &str| {
+		Scene::from_svg(&format!(
+			r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200" viewBox="0 0 100 50"
+				preserveAspectRatio="{}"><rect width="10" height="10"/></svg>"#,
+			preserve_aspect_ratio
+		))
+		.unwrap()
+	};
+	let centered = Transform::scale(2.0, 2.0).then(&Transform::translate(0.0, 50.0));
+	for (preserve_aspect_ratio, transform) in [
+		("xMidYMid", centered),
+		("defer xMaxYMax meet", Transform::scale(2.0, 2.0).then(&Transform::translate(0.0, 100.0))),
+		("xMaxYMin slice", Transform::scale(4.0, 4.0).then(&Transform::translate(-200.0, 0.0))),
+		(" none ", Transform::scale(2.0, 4.0)),
+	] {
+		let import = import(preserve_aspect_ratio);
+		assert!(import.unsupported.is_empty(), "{:?}", import.unsupported);
+		assert_eq!(import.scene.draws[0].transform, transform, "{}", preserve_aspect_ratio);
+	}
+
+	// anything else is reported and centered, including text that's as long as a valid value in other ways
+	for invalid in ["", "éMinYMi", "xMidYMidd", "xmidymid", "XMidYMid", "xMidYMid bogus", "xMidYMid meet slice"] {
+		let import = import(invalid);
+		let reported: Vec<String> = import.unsupported.iter().map(|unsupported| unsupported.to_string()).collect();
+		assert_eq!(reported, [format!("<svg>: invalid preserveAspectRatio \"{}\"", invalid)]);
+		assert_eq!(import.scene.draws[0].transform, centered, "{}", invalid);
+	}
+}
+
+#[test]
+fn groups_use_and_strokes() {
+	let scene = Scene::from_svg(
+		r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="100" height="100">
+			<defs>
+				<path id="bar" d="M0 0 H10"/>
+			</defs>
+			<g transform="translate(10 20)" stroke="blue" stroke-width="2" fill="none" opacity="0.5">
+				<use xlink:href="#bar" x="5"/>
+				<use href="#bar" transform="scale(2)" stroke-linecap="square" color="red" stroke="currentColor"/>
+			</g>
+		</svg>"##,
+	)
+	.unwrap()
+	.scene;
+	assert_eq!(scene.draws.len(), 2);
+	for draw in &scene.draws {
+		assert_eq!(draw.opacity, 0.5);
+		assert_eq!(draw.fill_rule, FillRule::EvenOdd);
+	}
+	assert_eq!(scene.draws[0].paint, Paint::Solid(Color::new(0.0, 0.0, 1.0, 1.0)));
+	assert_close(scene.draws[0].path.area(), 20.0, 1e-3);
+	assert_eq!(scene.draws[0].transform, Transform::translate(15.0, 20.0));
+	// the stroke is in the element's own units, so it's scaled with it
+	assert_eq!(scene.draws[1].paint, Paint::Solid(Color::new(1.0, 0.0, 0.0, 1.0)));
+	assert_close(scene.draws[1].path.area(), 24.0, 1e-3);
+	assert_eq!(scene.draws[1].transform, Transform::scale(2.0, 2.0).then(&Transform::translate(10.0, 20.0)));
+}
+
+#[test]
+fn gradients() {
+	let scene = Scene::from_svg(
+		r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="100" height="100">
+			<linearGradient id="stops">
+				<stop offset="0" stop-color="black"/>
+				<stop offset="100%" stop-color="white" stop-opacity="0.5"/>
+			</linearGradient>
+			<linearGradient id="vertical" xlink:href="#stops" x2="0" y2="1"/>
+			<radialGradient id="radial" href="#stops" gradientUnits="userSpaceOnUse" cx="50" cy="50" r="50"
+				spreadMethod="reflect"/>
+			<rect x="20" y="0" width="40" height="10" fill="url(#stops)"/>
+			<rect x="0" y="20" width="10" height="40" fill="url(#vertical)"/>
+			<rect width="100" height="100" fill="url(#radial)"/>
+			<rect width="100" height="100" fill="url(#missing) green"/>
+		</svg>"##,
+	)
+	.unwrap()
+	.scene;
+
+	// halfway between opaque black and half transparent white, interpolated premultiplied
+	let grey = Color::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0, 0.75);
+	assert_color(scene.draws[0].paint.color_at([40.0, 5.0]), grey);
+	assert_color(scene.draws[0].paint.color_at([0.0, 5.0]), Color::BLACK);
+	assert_color(scene.draws[1].paint.color_at([5.0, 40.0]), grey);
+	assert_color(scene.draws[2].paint.color_at([50.0, 25.0]), grey);
+	// reflected past the circle
+	assert_color(scene.draws[2].paint.color_at([50.0, 125.0]), grey);
+	assert_color(scene.draws[2].paint.color_at([50.0, 150.0]), Color::BLACK);
+	assert_eq!(scene.draws[3].paint, Paint::Solid(Color::new(0.0, 128.0 / 255.0, 0.0, 1.0)));
+}
+
+#[test]
+fn unsupported_features_are_reported() {
+	let import = Scene::from_svg(
+		r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape">
+			<style>rect { fill: red }</style>
+			<inkscape:perspective/>
+			<text>hello</text>
+			<g opacity="0.5" filter="url(#blur)" style="mix-blend-mode: screen">
+				<rect width="10" height="10"/>
+				<rect width="10" height="10" fill="rebeccapurple"/>
+			</g>
+			<path id="broken" d="M0 0 L10 0 L10 10 Q"/>
+			<rect width="10" height="10" fill="foo"/>
+		</svg>"#,
+	)
+	.unwrap();
+	let reported: Vec<String> = import.unsupported.iter().map(|unsupported| unsupported.to_string()).collect();
+	assert_eq!(
+		reported,
+		[
+			"<style>: CSS stylesheets",
+			"<text>: <text> elements",
+			"<g>: the filter property",
+			"<g>: the mix-blend-mode property",
+			"<g>: opacity on overlapping shapes, which is applied to each shape separately",
+			"<path#broken>: invalid path data at byte 19: expected a number, so it's only drawn up to there",
+			"<rect>: invalid fill \"foo\"",
+		]
+	);
+	// the default size, and everything that could be drawn is
+	assert_eq!(import.scene.size, [300.0, 150.0]);
+	assert_eq!(import.scene.draws.len(), 4);
+	assert_close(import.scene.draws[2].path.area(), 50.0, 1e-3);
+
+	assert!(matches!(Scene::from_svg("<svg>"), Err(SvgError::Xml(_))));
+	assert_eq!(Scene::from_svg("<html/>"), Err(SvgError::NotSvg("html".to_string())));
+}
+
+#[test]
+fn transforms() {
+	let transform =
+		Transform::translate(10.0, 0.0).then(&Transform::rotate(PI / 2.0)).then(&Transform::scale(2.0, 3.0));
+	let p = transform.apply([1.0, 1.0]);
+	assert_close(p[0], -2.0, 1e-5);
+	assert_close(p[1], 33.0, 1e-5);
+	let back = transform.invert().unwrap().apply(p);
+	assert_close(back[0], 1.0, 1e-5);
+	assert_close(back[1], 1.0, 1e-5);
+	assert!(Transform::scale(0.0, 1.0).invert().is_none());
+
+	let square = Path::start().move_to([0.0, 0.0]).line_to([1.0, 0.0]).line_to([1.0, 1.0]).line_to([0.0, 1.0]);
+	assert_close(square.transform(&transform).area(), 6.0, 1e-4);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::f32::consts::PI;
+
+use common::assert_close;
+use nice_path_tessellator::{
+	BlendMode, Color, Draw, FillRule, GradientStop, LinearGradient, Paint, Path, RadialGradient, Scene, SpreadMethod,
+	Transform,
+};
+
+fn square() -> Path {
+	Path::start()
+		.move_to([10.0, 10.0])
+		.line_to([30.0, 10.0])
+		.quadratic_curve_to([40.0, 20.0], [30.0, 30.0])
+		.line_to([10.0, 30.0])
+		.line_to([10.0, 10.0])
+		.move_to([-0.5, 0.0])
+		.line_to([-1.5, 0.0])
+}
+
+#[test]
+fn path_data() {
+	let d = square().to_svg_path_data();
+	assert_eq!(d, "M10 10 L30 10 Q40 20 30 30 L10 30 Z M-0.5 0 L-1.5 0");
+	assert_eq!(Path::from_svg_path_data(&d).unwrap(), square());
+
+	// an implicit start at the origin is written out, and a lone segment back to the start is kept
+	let implicit = Path::start().line_to([0.0, 0.0]).line_to([1.0, 0.0]);
+	assert_eq!(implicit.to_svg_path_data(), "M0 0 L0 0 L1 0");
+	assert_eq!(Path::start().move_to([1.0, 1.0]).line_to([1.0, 1.0]).to_svg_path_data(), "M1 1 L1 1 Z");
+	assert_eq!(Path::start().to_svg_path_data(), "");
+}
+
+#[test]
+fn conics_become_arcs() {
+	let circle = Path::from_svg_path_data("M0 50 A50 50 0 0 1 100 50 A50 50 0 0 1 0 50 Z").unwrap();
+	let d = circle.to_svg_path_data();
+	assert!(d.contains('A') && !d.contains('Q'), "{}", d);
+	let back = Path::from_svg_path_data(&d).unwrap();
+	assert_close(back.area() / (PI * 2500.0), 1.0, 1e-3);
+
+	// a rotated and squashed circle is still an ellipse
+	let transform = Transform::scale(3.0, 1.0).then(&Transform::rotate(0.5)).then(&Transform::translate(7.0, -3.0));
+	let ellipse = circle.transform(&transform);
+	let back = Path::from_svg_path_data(&ellipse.to_svg_path_data()).unwrap();
+	assert_close(back.area() / ellipse.area(), 1.0, 1e-3);
+	let (a, b) = (back.bounds().unwrap(), ellipse.bounds().unwrap());
+	for i in 0..2 {
+		assert_close(a.left_top[i], b.left_top[i], 1e-2);
+		assert_close(a.right_bottom[i], b.right_bottom[i], 1e-2);
+	}
+
+	// hyperbolas can only be approximated
+	let hyperbola = Path::start().move_to([0.0, 0.0]).conic_to([10.0, 10.0], [20.0, 0.0], 2.0);
+	let d = hyperbola.to_svg_path_data();
+	assert!(d.contains('Q') && !d.contains('A'), "{}", d);
+	assert_close(Path::from_svg_path_data(&d).unwrap().area(), hyperbola.area(), 1e-2);
+}
+
+#[test]
+fn scene_round_trip() {
+	let stops = vec![
+		GradientStop { offset: 0.0, color: Color::new(1.0, 0.0, 0.0, 1.0) },
+		GradientStop { offset: 0.25, color: Color::new(0.0, 0.0, 1.0, 0.5) },
+	];
+	let mut scene = Scene::new([120.0, 80.0]);
+	scene.push(Draw::new(square(), Paint::Solid(Color::new(0.0, 1.0, 0.0, 0.25))));
+	scene.push(Draw {
+		path: square(),
+		transform: Transform::translate(5.0, 6.0).then(&Transform::scale(2.0, 0.5)),
+		paint: Paint::LinearGradient(LinearGradient {
+			start: [10.0, 10.0],
+			end: [30.0, 20.0],
+			stops: stops.clone(),
+			spread: SpreadMethod::Reflect,
+			transform: Transform::skew_x(0.25),
+		}),
+		fill_rule: FillRule::EvenOdd,
+		opacity: 0.75,
+		blend_mode: BlendMode::Multiply,
+	});
+	scene.push(Draw::new(
+		square(),
+		Paint::RadialGradient(RadialGradient {
+			center: [20.0, 20.0],
+			radius: 10.0,
+			focal: [22.0, 18.0],
+			stops,
+			spread: SpreadMethod::Repeat,
+			transform: Transform::IDENTITY,
+		}),
+	));
+
+	let svg = scene.to_svg();
+	let import = Scene::from_svg(&svg).unwrap();
+	assert!(import.unsupported.is_empty(), "{:?}", import.unsupported);
+	// a solid colour's alpha is read back as the draw's opacity, which paints the same
+	let mut expected = scene.clone();
+	expected.draws[0].paint = Paint::Solid(Color::new(0.0, 1.0, 0.0, 1.0));
+	expected.draws[0].opacity = 0.25;
+	assert_eq!(import.scene, expected, "{}", svg);
+
+	let path = Path::from_svg_path_data("M0 0 L10 0 L10 10 L0 10 Z M2 2 L8 2 L8 8 L2 8 Z").unwrap();
+	let import = Scene::from_svg(&path.to_svg(FillRule::EvenOdd)).unwrap().scene;
+	assert_eq!(import.size, [10.0, 10.0]);
+	assert_eq!(import.draws[0].fill_rule, FillRule::EvenOdd);
+	assert_eq!(import.draws[0].path, path);
+	assert_eq!(import.draws[0].transform, Transform::IDENTITY);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use common::assert_close;
+use nice_path_tessellator::{Glyph, Path, TextAlign, TextOnPathOptions, TextOverflow};
+
+/// A 10x10 box sitting on the baseline, with the pen advancing 10.
+fn box_glyph() -> Glyph {
+	let outline = Path::start().move_to([0.0, 0.0]).line_to([0.0, -10.0]).line_to([10.0, -10.0]).line_to([10.0, 0.0]);
+	Glyph { outline, advance: 10.0 }
+}
+
+#[test]
+fn straight_path() {
+	let line = Path::start().move_to([0.0, 100.0]).line_to([100.0, 100.0]);
+	let glyphs = vec![box_glyph(); 3];
+	let text = line.text_on_path(&glyphs, &TextOnPathOptions { offset: 20.0, ..Default::default() });
+	let bounds = text.bounds().unwrap();
+	assert_close(bounds.left_top[0], 20.0, 1e-2);
+	assert_close(bounds.left_top[1], 90.0, 1e-2);
+	assert_close(bounds.right_bottom[0], 50.0, 1e-2);
+	assert_close(bounds.right_bottom[1], 100.0, 1e-2);
+
+	let centered = line.text_on_path(
+		&glyphs,
+		&TextOnPathOptions { offset: 50.0, align: TextAlign::Center, warp: true, ..Default::default() },
+	);
+	let bounds = centered.bounds().unwrap();
+	assert_close(bounds.left_top[0], 35.0, 1e-2);
+	assert_close(bounds.right_bottom[0], 65.0, 1e-2);
+}
+
+#[test]
+fn glyphs_follow_the_tangent() {
+	// going down the screen, the glyphs' tops point right
+	let line = Path::start().move_to([0.0, 0.0]).line_to([0.0, 100.0]);
+	let text = line.text_on_path(&[box_glyph()], &Default::default());
+	let bounds = text.bounds().unwrap();
+	assert_close(bounds.left_top[0], 0.0, 1e-2);
+	assert_close(bounds.right_bottom[0], 10.0, 1e-2);
+	assert_close(bounds.right_bottom[1], 10.0, 1e-2);
+}
+
+#[test]
+fn overflow() {
+	let line = Path::start().move_to([0.0, 0.0]).line_to([25.0, 0.0]);
+	let glyphs = vec![box_glyph(); 4];
+	let options = |overflow| TextOnPathOptions { overflow, ..Default::default() };
+
+	let hidden = line.text_on_path(&glyphs, &options(TextOverflow::Hide));
+	assert_close(hidden.area().abs(), 200.0, 1e-2);
+	let extended = line.text_on_path(&glyphs, &options(TextOverflow::Extend));
+	assert_close(extended.area().abs(), 400.0, 1e-2);
+	assert_close(extended.bounds().unwrap().right_bottom[0], 40.0, 1e-2);
+	let shrunk = line.text_on_path(&glyphs, &options(TextOverflow::Shrink));
+	assert_close(shrunk.bounds().unwrap().right_bottom[0], 25.0, 1e-2);
+	assert_close(shrunk.area().abs(), 4.0 * 6.25 * 6.25, 1e-2);
+}
+
+#[test]
+fn warped_glyphs_bend_with_the_path() {
+	// a half circle of radius 100, going clockwise over the top, so the glyphs sit on the outside
+	let r = 100.0;
+	let k = 0.41421356 * r;
+	let arc = Path::start()
+		.move_to([-r, 0.0])
+		.quadratic_curve_to([-r, -k], [-FRAC_1_SQRT_2 * r, -FRAC_1_SQRT_2 * r])
+		.quadratic_curve_to([-k, -r], [0.0, -r])
+		.quadratic_curve_to([k, -r], [FRAC_1_SQRT_2 * r, -FRAC_1_SQRT_2 * r])
+		.quadratic_curve_to([r, -k], [r, 0.0]);
+	let glyphs = vec![box_glyph(); 5];
+	let options = TextOnPathOptions { offset: 100.0, warp: true, tolerance: 0.01, ..Default::default() };
+	let text = arc.text_on_path(&glyphs, &options);
+
+	// every warped glyph is a slice of an annulus between radius 100 and 110
+	let expected = 5.0 * 10.0 * 10.0 * (110.0 + 100.0) / 2.0 / 100.0;
+	assert!((text.area().abs() - expected).abs() < expected * 0.01, "{}", text.area());
+	let bounds = text.bounds().unwrap();
+	assert!(bounds.left_top[1] > -110.5 && bounds.left_top[1] < -109.0, "{:?}", bounds.left_top);
+}